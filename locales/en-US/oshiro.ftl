@@ -0,0 +1,402 @@
+# English is the fallback for every other locale, so every response needs a message here.
+# Command definitions are written in English in code; other locales can translate them with
+# `cmd-<command>[-<subcommand or option>...]` messages, see es-ES.
+
+## Shared
+
+not-in-guild = This only works in a server.
+need-manage-guild = You need the Manage Server permission for this.
+blacklisted = You can't use this bot.
+command-disabled = This command is disabled in this server.
+command-failed = Error when running a command: { $error }
+mention-prefix = My prefix is { $prefix }, but you can ping me as well.
+cooldown = Slow down, you can use `{ $command }` again in { $seconds ->
+        [one] a second
+       *[other] { $seconds } seconds
+    }.
+
+## admin: modules
+
+modules-unknown-subcommand = Unknown subcommand `{ $name }`, try `list`, `enable`, `disable` or `config`.
+modules-heading = **modules**
+modules-line = `{ $name }` ({ $state ->
+        [always] always on
+        [on] on
+       *[off] off
+    }) - { $description }
+modules-disabled-commands = **disabled commands**: { $commands }
+modules-which = Tell me which module or command.
+modules-always-on = `{ $name }` is always on.
+modules-module-toggled = { $enabled ->
+        [true] Turned the `{ $name }` module on.
+       *[false] Turned the `{ $name }` module off.
+    }
+modules-part-of-always-on = `{ $name }` is part of `{ $module }`, which is always on.
+modules-command-toggled = { $enabled ->
+        [true] Turned the `{ $name }` command on.
+       *[false] Turned the `{ $name }` command off.
+    }
+modules-not-found = There's no module or command called `{ $name }`.
+
+config-which-module = Tell me which module, see `modules list`.
+config-no-settings = `{ $module }` has no settings.
+config-heading = **{ $module } settings**
+config-line = `{ $key }` = { $value } - { $description }
+config-not-set = not set
+config-unknown-key = `{ $module }` has no setting `{ $key }`.
+config-reset = Reset `{ $key }`.
+config-set = Set `{ $key }` to { $value }.
+config-invalid = Couldn't set `{ $key }`: { $error }
+config-foreign-channel = { $value } isn't a channel in this server
+config-foreign-role = { $value } isn't a role in this server
+config-not-bool = `{ $input }` is not true or false
+config-not-number = `{ $input }` is not a number
+config-empty = value must not be empty
+config-not-channel = `{ $input }` is not a channel
+config-not-role = `{ $input }` is not a role
+config-not-duration = `{ $input }` is not a duration, try `30m` or `1h30m`
+config-not-choice = `{ $input }` is not one of { $choices }
+
+## admin: bots
+
+bots-unknown-subcommand = Unknown subcommand `{ $name }`, try `show`, `policy`, `webhooks`, `allow` or `remove`.
+bots-policy-line = **bot policy**: `{ $policy }`
+bots-webhooks-line = { $enabled ->
+        [true] **webhooks**: answered
+       *[false] **webhooks**: ignored
+    }
+bots-allowed-line = **allowed bots**: { $bots }
+bots-allowlist-inactive = Allowed bots are ignored until the policy is `allowlist`.
+bots-pick-policy = Pick a policy: { $policies }.
+bots-policy-set = Bot policy is now `{ $policy }`.
+bots-which-webhooks = Turn webhooks `on` or `off`?
+bots-webhooks-set = { $enabled ->
+        [true] Commands sent through webhooks will be answered.
+       *[false] Commands sent through webhooks will be ignored.
+    }
+bots-which-bot = Tell me which bot, by mention or id.
+bots-not-a-bot = { $bot } isn't a bot.
+bots-allowed = { $bot } can use commands when the policy is `allowlist`.
+bots-removed = { $bot } can't use commands any more.
+
+## system
+
+hi = hello, { $name }. Here's some information about you:
+
+ping-sending = ping!!!
+ping-ack = ayup
+ping-pong = pong!
+ping-http = http: { $ms }ms
+ping-shard = shard id { $id } - ws avg: { $avg }ms over { $window }s - beats: { $beats } - { $state }
+
+stats-system = system
+stats-system-value = running on { $os } { $release } ({ $host })
+stats-cpu = cpu
+stats-cpu-value = { $cores } cores, { $usage }% usage
+stats-memory = memory
+stats-memory-value = { $used } MB used, { $total } GB total
+stats-cache = cache
+stats-cache-value = { $guilds } guilds, { $users } users, { $channels } channels
+stats-shards = shards
+stats-shards-value = { $running } of { $total } running here, { $ready } ready
+stats-footer = running on tiny horse
+
+guild-owner = owner
+guild-channels = channels
+guild-channels-value = { $count } (incl categories)
+guild-members = members
+guild-members-unknown = could not fetch
+guild-emojis = emojis and stickers
+guild-emojis-value = { $emojis } emojis
+    { $stickers } stickers
+guild-features = features
+
+## moderation
+
+mod-no-permission = You need the { $command ->
+        [kick] Kick Members
+        [ban] Ban Members
+        [unban] Ban Members
+        [purge] Manage Messages
+       *[other] Timeout Members
+    } permission for this.
+mod-which-user = Tell me who, by mention or id.
+mod-self = You can't do that to yourself.
+mod-me = I can't do that to myself.
+mod-owner = Nobody can do that to the server owner.
+mod-not-member = { $user } isn't in this server.
+mod-above-you = { $user }'s highest role is at or above yours.
+mod-above-me = { $user }'s highest role is at or above mine, so I can't.
+mod-which-duration = How long for? Like `10m`, `1h30m` or `7d`.
+mod-bad-duration = `{ $duration }` isn't a duration, try `10m`, `1h30m` or `7d`.
+mod-timeout-too-long = Timeouts can be 28 days at most.
+mod-delete-too-long = Messages can only be deleted from up to 7 days back.
+mod-not-banned = { $user } isn't banned.
+mod-kicked = Kicked { $user }.
+mod-banned = Banned { $user }.
+mod-banned-deleted = Banned { $user } and deleted their messages from the last { $window }.
+mod-unbanned = Unbanned { $user }.
+mod-timed-out = Timed out { $user } for { $duration }.
+mod-untimed-out = Lifted { $user }'s timeout.
+mod-reason = **reason**: { $reason }
+mod-dm-failed = Couldn't DM them about it.
+mod-dm-kicked = You were kicked from **{ $guild }**.
+mod-dm-banned = You were banned from **{ $guild }**.
+mod-dm-undone = Never mind, that didn't go through: you're still in **{ $guild }**.
+mod-dm-timed-out = You were timed out in **{ $guild }** for { $duration }.
+mod-dm-untimed-out = Your timeout in **{ $guild }** was lifted.
+mod-warned = Warned { $user }.
+mod-case = (case { $case })
+mod-dm-warned = You were warned in **{ $guild }**.
+mod-escalation-reason = Reached { $warns } warnings
+mod-escalated = That's a multiple of the warning limit, so they were { $action ->
+        [kick] kicked
+        [ban] banned
+       *[timeout] timed out for { $duration }
+    } (case { $case }).
+mod-escalation-failed = They're at the warning limit, but acting on it failed: { $error }
+
+## moderation: cases
+
+cases-none = { $user } has no cases.
+cases-heading = **{ $count ->
+        [one] one case
+       *[other] { $count } cases
+    } for { $user }**
+cases-line = `#{ $case }` { $action } { $when } - { $reason }
+cases-more = ...and { $count } older.
+case-which = Tell me which case, by number.
+case-not-found = There's no case { $case }.
+case-which-reason = What should the reason be?
+case-reason-set = Updated the reason for case { $case }.
+case-deleted = Deleted case { $case }.
+case-title = Case { $case } | { $action }
+case-user = user
+case-moderator = moderator
+case-duration = duration
+case-reason = reason
+case-no-reason = no reason given
+
+## moderation: purge
+
+purge-which-count = Tell me how many recent messages to look through, up to { $max }.
+purge-unknown-filter = Unknown filter `{ $filter }`, try a user, `bots`, `attachments`, `links`, `before:<id>`, `after:<id>` or `contains:<text>`.
+purge-bad-message = `{ $id }` isn't a message id or link.
+purge-done = Deleted { $deleted ->
+        [one] one message
+       *[other] { $deleted } messages
+    } out of the last { $scanned }.
+purge-old = { $count ->
+        [one] One was
+       *[other] { $count } were
+    } older than two weeks, so they went one at a time.
+purge-authors = **from**: { $authors }
+
+## logging
+
+log-deleted-title = Message deleted
+log-edited-title = Message edited
+log-bulk-title = Messages purged
+log-bulk-description = { $count ->
+        [one] One message was
+       *[other] { $count } messages were
+    } deleted in { $channel }, { $cached } of them from the cache. The log is attached.
+log-not-cached = *not in the cache, it was sent before the bot saw it*
+log-no-content = *no text*
+log-channel = channel
+log-author = author
+log-attachments = attachments
+log-before = before
+log-after = after
+log-jump = Jump to the message
+log-message-id = Message { $id }
+log-user-id = User { $id }
+log-joined-title = Member joined
+log-left-title = Member left
+log-member-updated-title = Member updated
+log-account-age = account age
+log-time-in-server = time in the server
+log-roles = roles
+log-no-roles = none
+log-member-not-cached = *not in the cache*
+log-nickname = nickname
+log-no-nickname = *none*
+log-roles-added = roles added
+log-roles-removed = roles removed
+log-username = username
+log-avatar = avatar
+log-avatar-changed = changed, the new one is on the right
+log-channel-id = Channel { $id }
+log-role-id = Role { $id }
+log-channel-created-title = Channel created
+log-channel-deleted-title = Channel deleted
+log-channel-updated-title = Channel updated
+log-thread-created-title = Thread created
+log-thread-deleted-title = Thread deleted
+log-thread-updated-title = Thread updated
+log-thread-not-cached = A thread in { $parent } that wasn't in the cache.
+log-role-created-title = Role created
+log-role-deleted-title = Role deleted
+log-role-updated-title = Role updated
+log-not-cached-short = *not in the cache*
+log-parent = category or channel
+log-name = name
+log-position = position
+log-slowmode = slowmode
+log-off = off
+log-archived = archived
+log-locked = locked
+log-overwrites = permission overwrites
+log-allowed = allowed { $permissions }
+log-denied = denied { $permissions }
+log-inherited = back to inherited { $permissions }
+log-color = color
+log-hoist = shown separately
+log-mentionable = mentionable
+log-permissions = permissions
+log-granted = permissions granted
+log-revoked = permissions revoked
+log-no-permissions = none
+log-yes = yes
+log-no = no
+logignore-unknown-subcommand = Unknown subcommand `{ $name }`, try `list`, `add` or `remove`.
+logignore-which = Tell me which channel.
+logignore-none = Every channel is logged.
+logignore-list = Not logged: { $channels }
+logignore-added = { $channel } won't be logged anymore, nor its threads.
+logignore-removed = { $channel } is logged again.
+
+## welcome
+
+welcome-unknown-subcommand = Unknown subcommand `{ $name }`, try `test`.
+welcome-unknown-message = There's no `{ $name }` message, try `welcome`, `goodbye` or `dm`.
+welcome-dm-off = There's no DM welcome, set one with `modules config welcome dm_message <text>`.
+
+## autorole
+
+autorole-unknown-subcommand = Unknown subcommand `{ $name }`, try `list`, `add` or `remove`.
+autorole-which = Tell me which role.
+autorole-none = No roles are given on join.
+autorole-list-empty = none
+autorole-list-humans = **Members**: { $roles }
+autorole-list-bots = **Bots**: { $roles }
+autorole-added = { $bots ->
+        [true] Bots
+       *[other] Members
+    } will get { $role } when they join.
+autorole-removed = { $bots ->
+        [true] Bots
+       *[other] Members
+    } won't get { $role } when they join anymore.
+autorole-everyone = Everyone already has { $role }.
+autorole-unknown-role = I can't find { $role }.
+autorole-foreign-role = { $role } isn't a role in this server.
+autorole-managed = { $role } belongs to an integration, so nobody can give it out.
+autorole-no-permission = I need Manage Roles to give out { $role }.
+autorole-too-high = { $role } isn't below my highest role, so I can't give it out.
+autorole-dangerous = { $role } has Administrator, Manage Server, Manage Roles, Ban Members or Kick Members, so I won't hand it out.
+autorole-not-allowed = You need Manage Roles to hand out { $role }.
+autorole-above-you = { $role } isn't below your highest role, so you can't hand it out.
+
+## rolemenu
+
+rolemenu-unknown-subcommand = Unknown subcommand `{ $name }`, try `list`, `create`, `add`, `remove`, `max`, `require`, `post` or `delete`.
+rolemenu-none = There are no role menus, make one with `rolemenu create <buttons|select> <title>`.
+rolemenu-not-posted = not posted
+rolemenu-list-entry = **#{ $menu }** { $title } ({ $kind }, { $roles ->
+        [one] one role
+       *[other] { $roles } roles
+    }): { $posted }
+rolemenu-which-kind = Should it be `buttons` or a `select`?
+rolemenu-which-title = Give it a title.
+rolemenu-created = Made menu #{ $menu }. Add roles with `rolemenu add { $menu } <role> [label]`, then `rolemenu post { $menu }`.
+rolemenu-which-menu = Tell me which menu, by number.
+rolemenu-not-found = There's no menu #{ $menu }.
+rolemenu-which-role = Tell me which role.
+rolemenu-full = A menu can't have more than { $max } roles.
+rolemenu-role-added = { $role } is on menu #{ $menu }.
+rolemenu-role-not-on = { $role } isn't on that menu.
+rolemenu-role-removed = Took { $role } off menu #{ $menu }.
+rolemenu-which-count = How many roles can a member pick? 0 to { $max }, 0 for no limit.
+rolemenu-max-set = { $count ->
+        [0] Members can pick as many roles as they like
+        [one] Members can pick one role
+       *[other] Members can pick up to { $count } roles
+    } from menu #{ $menu }.
+rolemenu-required-set = Only members with { $role } can use menu #{ $menu }.
+rolemenu-required-cleared = Anyone can use menu #{ $menu }.
+rolemenu-which-channel = Tell me which channel.
+rolemenu-foreign-channel = { $channel } isn't a channel in this server.
+rolemenu-empty = Menu #{ $menu } has no roles yet.
+rolemenu-posted = Posted menu #{ $menu } in { $channel }.
+rolemenu-deleted = Deleted menu #{ $menu }.
+rolemenu-refresh-failed = I couldn't update the posted menu, post it again with `rolemenu post`.
+rolemenu-footer-max = Pick up to { $max }
+rolemenu-footer-required = Needs a role to use
+rolemenu-placeholder = Pick your roles
+rolemenu-gone = That menu doesn't exist anymore.
+rolemenu-needs-role = You need { $role } to use this menu.
+rolemenu-max = You can only have { $max } of these roles, remove one first.
+rolemenu-added = **Added**: { $roles }
+rolemenu-removed = **Removed**: { $roles }
+rolemenu-failed = **Couldn't change**: { $roles }
+rolemenu-unchanged = Nothing changed.
+
+## info
+info-no-user = I couldn't find that user.
+info-username = Username
+info-display-name = Display name
+info-id = ID
+info-created = Account created
+info-joined = Joined
+info-not-member = Not in this server
+info-boosting = Boosting
+info-boosting-since = Since { $since }
+info-not-boosting = No
+info-roles = Roles ({ $count })
+info-no-roles = None
+info-permissions = Key permissions
+info-no-key-permissions = None
+info-bot = Bot account
+info-server-avatar = { $user }'s server avatar
+info-global-avatar = { $user }'s avatar
+
+## owner
+owner-only = Only the bot's owners can use this.
+owner-shutting-down = Shutting down.
+owner-reloaded = Reloaded the config.
+owner-reloaded-synced = Slash commands were registered again.
+owner-reloaded-restart = These changes need a restart: { $changes }
+owner-reload-failed = Kept the old config.
+owner-synced = Registered { $commands } commands and { $admin } admin commands.
+owner-guilds = **{ $count } guilds** (page { $page }/{ $pages })
+owner-guilds-entry = { $name } (`{ $id }`) - { $members } members
+owner-which-guild = Tell me which guild, by id.
+owner-not-in-guild = I'm not in that guild.
+owner-left = Left { $name } (`{ $id }`).
+blacklist-unknown-subcommand = Unknown subcommand `{ $name }`, try `list`, `add <user|guild> <id> [duration] [reason]` or `remove <user|guild> <id>`.
+blacklist-which-kind = Is that a `user` or a `guild`?
+blacklist-which-id = Give me an id.
+blacklist-bad-duration = `{ $duration }` isn't a duration, try `30m` or `7d`.
+blacklist-owner = Owners can't be blacklisted.
+blacklist-added = Blacklisted { $kind } `{ $id }` permanently.
+blacklist-added-for = Blacklisted { $kind } `{ $id }` for { $duration }.
+blacklist-left = Left it too.
+blacklist-removed = Removed { $kind } `{ $id }` from the blacklist.
+blacklist-not-listed = { $kind } `{ $id }` isn't blacklisted.
+blacklist-empty = The blacklist is empty.
+blacklist-count = **{ $count } blacklisted**
+blacklist-entry = { $kind } `{ $id }` - { $reason } - { $expires }
+blacklist-no-reason = no reason
+blacklist-permanent = permanent
+blacklist-time-left = { $duration } left
+blacklist-more = ...and { $count } more
+shardinfo-count = **{ $running } of { $total } shards running here**
+shardinfo-entry = `{ $id }` { $state } - { $latency } - { $guilds } guilds - { $reconnects } reconnects, { $resumes } resumes, { $invalid } invalid sessions
+shardinfo-unhealthy = (unhealthy)
+shardinfo-latency = { $ms }ms
+shardinfo-no-latency = no latency yet
+
+## novelty
+
+uwu-broken = Something broke!
@@ -0,0 +1,677 @@
+## Shared
+
+not-in-guild = Esto solo funciona en un servidor.
+need-manage-guild = Necesitas el permiso de Gestionar servidor para esto.
+blacklisted = No puedes usar este bot.
+command-disabled = Este comando está desactivado en este servidor.
+command-failed = Error al ejecutar un comando: { $error }
+mention-prefix = Mi prefijo es { $prefix }, pero también puedes mencionarme.
+cooldown = Más despacio, podrás volver a usar `{ $command }` en { $seconds ->
+        [one] un segundo
+       *[other] { $seconds } segundos
+    }.
+
+## admin: modules
+
+modules-unknown-subcommand = Subcomando desconocido `{ $name }`, prueba con `list`, `enable`, `disable` o `config`.
+modules-heading = **módulos**
+modules-line = `{ $name }` ({ $state ->
+        [always] siempre activo
+        [on] activo
+       *[off] inactivo
+    }) - { $description }
+modules-disabled-commands = **comandos desactivados**: { $commands }
+modules-which = Dime qué módulo o comando.
+modules-always-on = `{ $name }` siempre está activo.
+modules-module-toggled = { $enabled ->
+        [true] Módulo `{ $name }` activado.
+       *[false] Módulo `{ $name }` desactivado.
+    }
+modules-part-of-always-on = `{ $name }` es parte de `{ $module }`, que siempre está activo.
+modules-command-toggled = { $enabled ->
+        [true] Comando `{ $name }` activado.
+       *[false] Comando `{ $name }` desactivado.
+    }
+modules-not-found = No hay ningún módulo ni comando llamado `{ $name }`.
+
+config-which-module = Dime qué módulo, mira `modules list`.
+config-no-settings = `{ $module }` no tiene ajustes.
+config-heading = **ajustes de { $module }**
+config-line = `{ $key }` = { $value } - { $description }
+config-not-set = sin definir
+config-unknown-key = `{ $module }` no tiene el ajuste `{ $key }`.
+config-reset = `{ $key }` restablecido.
+config-set = `{ $key }` ahora es { $value }.
+config-invalid = No se pudo cambiar `{ $key }`: { $error }
+config-foreign-channel = { $value } no es un canal de este servidor
+config-foreign-role = { $value } no es un rol de este servidor
+config-not-bool = `{ $input }` no es verdadero ni falso
+config-not-number = `{ $input }` no es un número
+config-empty = el valor no puede estar vacío
+config-not-channel = `{ $input }` no es un canal
+config-not-role = `{ $input }` no es un rol
+config-not-duration = `{ $input }` no es una duración, prueba `30m` o `1h30m`
+config-not-choice = `{ $input }` no es ninguno de { $choices }
+
+module-admin = activa o desactiva módulos y comandos, y elige qué bots pueden usar comandos
+module-system = ping, estadísticas e información del servidor
+module-moderation = advierte, expulsa, banea y aísla miembros, con un registro de casos
+module-logging = registra mensajes editados y eliminados, cambios de miembros, y cambios de canales, hilos y roles
+module-welcome = mensajes de bienvenida y despedida
+module-autorole = da roles a los miembros al entrar
+module-rolemenu = menús de botones o listas con los que los miembros eligen sus propios roles
+module-info = información de usuarios y avatares
+module-novelty = juguetes de texto tontos
+
+setting-admin-bot_policy = ignorar todos los bots, o responder solo a los añadidos con `bots allow`
+setting-admin-allow_webhooks = responder a comandos enviados por webhooks
+setting-admin-language = idioma de las respuestas a comandos de texto, y a comandos de barra cuando el de Discord no está disponible
+setting-moderation-dm_targets = avisar por MD a los miembros cuando se les advierte, expulsa, banea o aísla
+setting-moderation-log_channel = canal donde se publica cada caso
+setting-moderation-warn_limit = cada tantas advertencias se aplica `warn_action`. 0 lo desactiva
+setting-moderation-warn_action = qué pasa al llegar a `warn_limit` advertencias: timeout, kick o ban
+setting-moderation-warn_timeout = cuánto dura el aislamiento al llegar a `warn_limit` advertencias
+setting-logging-message_log = canal donde se publican los mensajes editados y eliminados
+setting-logging-member_log = canal donde se publican entradas, salidas y cambios de apodo, roles y perfil
+setting-logging-server_log = canal donde se publican los canales, hilos y roles creados, eliminados y modificados
+setting-logging-log_bots = registrar también los mensajes de bots y webhooks
+setting-welcome-welcome_channel = canal donde se da la bienvenida a los miembros nuevos, desactivado si no está definido
+setting-welcome-welcome_message = la bienvenida, con {"{"}user{"}"}, {"{"}user.mention{"}"}, {"{"}guild{"}"}, {"{"}member_count{"}"} y {"{"}account_age{"}"}
+setting-welcome-goodbye_channel = canal donde se despide a los miembros que se van, desactivado si no está definido
+setting-welcome-goodbye_message = la despedida, con los mismos marcadores que welcome_message
+setting-welcome-embed = enviar los mensajes como embeds con el avatar del miembro
+setting-welcome-dm_message = enviar también esto por MD a los miembros nuevos, con los mismos marcadores. desactivado si no está definido
+setting-autorole-delay = esperar esto después de que alguien entre antes de dar los roles, como `10m`
+
+## admin: bots
+
+bots-unknown-subcommand = Subcomando desconocido `{ $name }`, prueba con `show`, `policy`, `webhooks`, `allow` o `remove`.
+bots-policy-line = **política de bots**: `{ $policy }`
+bots-webhooks-line = { $enabled ->
+        [true] **webhooks**: se responden
+       *[false] **webhooks**: se ignoran
+    }
+bots-allowed-line = **bots permitidos**: { $bots }
+bots-allowlist-inactive = Los bots permitidos se ignoran hasta que la política sea `allowlist`.
+bots-pick-policy = Elige una política: { $policies }.
+bots-policy-set = La política de bots ahora es `{ $policy }`.
+bots-which-webhooks = ¿Activar (`on`) o desactivar (`off`) los webhooks?
+bots-webhooks-set = { $enabled ->
+        [true] Se responderá a los comandos enviados por webhooks.
+       *[false] Se ignorarán los comandos enviados por webhooks.
+    }
+bots-which-bot = Dime qué bot, con una mención o su id.
+bots-not-a-bot = { $bot } no es un bot.
+bots-allowed = { $bot } puede usar comandos cuando la política es `allowlist`.
+bots-removed = { $bot } ya no puede usar comandos.
+
+## system
+
+hi = hola, { $name }. Esto es lo que sé de ti:
+
+ping-sending = ¡¡¡ping!!!
+ping-ack = voy
+ping-pong = ¡pong!
+ping-http = http: { $ms }ms
+ping-shard = shard { $id } - ws promedio: { $avg }ms en { $window }s - latidos: { $beats } - { $state }
+
+stats-system = sistema
+stats-system-value = funcionando en { $os } { $release } ({ $host })
+stats-cpu = cpu
+stats-cpu-value = { $cores } núcleos, { $usage }% de uso
+stats-memory = memoria
+stats-memory-value = { $used } MB usados, { $total } GB en total
+stats-cache = caché
+stats-cache-value = { $guilds } servidores, { $users } usuarios, { $channels } canales
+stats-shards = shards
+stats-shards-value = { $running } de { $total } funcionando aquí, { $ready } listos
+stats-footer = funcionando en un caballito
+
+guild-owner = propietario
+guild-channels = canales
+guild-channels-value = { $count } (incluidas las categorías)
+guild-members = miembros
+guild-members-unknown = no se pudo obtener
+guild-emojis = emojis y stickers
+guild-emojis-value = { $emojis } emojis
+    { $stickers } stickers
+guild-features = características
+
+## moderation
+
+mod-no-permission = Necesitas el permiso { $command ->
+        [kick] Expulsar miembros
+        [ban] Banear miembros
+        [unban] Banear miembros
+        [purge] Gestionar mensajes
+       *[other] Aislar temporalmente a miembros
+    } para esto.
+mod-which-user = Dime a quién, con una mención o su id.
+mod-self = No puedes hacerte eso a ti mismo.
+mod-me = No puedo hacerme eso a mí mismo.
+mod-owner = Nadie puede hacerle eso al propietario del servidor.
+mod-not-member = { $user } no está en este servidor.
+mod-above-you = El rol más alto de { $user } está a la altura del tuyo o por encima.
+mod-above-me = El rol más alto de { $user } está a la altura del mío o por encima, así que no puedo.
+mod-which-duration = ¿Por cuánto tiempo? Por ejemplo `10m`, `1h30m` o `7d`.
+mod-bad-duration = `{ $duration }` no es una duración, prueba con `10m`, `1h30m` o `7d`.
+mod-timeout-too-long = Los aislamientos duran 28 días como mucho.
+mod-delete-too-long = Solo se pueden borrar mensajes de hasta 7 días atrás.
+mod-not-banned = { $user } no está baneado.
+mod-kicked = { $user } expulsado.
+mod-banned = { $user } baneado.
+mod-banned-deleted = { $user } baneado, y borrados sus mensajes de { $window } atrás.
+mod-unbanned = Ban de { $user } retirado.
+mod-timed-out = { $user } aislado durante { $duration }.
+mod-untimed-out = Aislamiento de { $user } retirado.
+mod-reason = **motivo**: { $reason }
+mod-dm-failed = No se le pudo avisar por MD.
+mod-dm-kicked = Te han expulsado de **{ $guild }**.
+mod-dm-banned = Te han baneado de **{ $guild }**.
+mod-dm-undone = Olvídalo, no se llegó a hacer: sigues en **{ $guild }**.
+mod-dm-timed-out = Te han aislado en **{ $guild }** durante { $duration }.
+mod-dm-untimed-out = Se ha retirado tu aislamiento en **{ $guild }**.
+mod-warned = { $user } advertido.
+mod-case = (caso { $case })
+mod-dm-warned = Te han advertido en **{ $guild }**.
+mod-escalation-reason = Llegó a { $warns } advertencias
+mod-escalated = Es un múltiplo del límite de advertencias, así que se le ha { $action ->
+        [kick] expulsado
+        [ban] baneado
+       *[timeout] aislado durante { $duration }
+    } (caso { $case }).
+mod-escalation-failed = Ha llegado al límite de advertencias, pero no se pudo actuar: { $error }
+
+## moderation: cases
+
+cases-none = { $user } no tiene casos.
+cases-heading = **{ $count ->
+        [one] un caso
+       *[other] { $count } casos
+    } de { $user }**
+cases-line = `#{ $case }` { $action ->
+        [warn] advertencia
+        [kick] expulsión
+        [ban] ban
+        [unban] fin del ban
+        [timeout] aislamiento
+       *[untimeout] fin del aislamiento
+    } { $when } - { $reason }
+cases-more = ...y { $count } más antiguos.
+case-which = Dime qué caso, por su número.
+case-not-found = No hay ningún caso { $case }.
+case-which-reason = ¿Cuál debería ser el motivo?
+case-reason-set = Motivo del caso { $case } actualizado.
+case-deleted = Caso { $case } borrado.
+case-title = Caso { $case } | { $action ->
+        [warn] advertencia
+        [kick] expulsión
+        [ban] ban
+        [unban] fin del ban
+        [timeout] aislamiento
+       *[untimeout] fin del aislamiento
+    }
+case-user = usuario
+case-moderator = moderador
+case-duration = duración
+case-reason = motivo
+case-no-reason = sin motivo
+
+## moderation: purge
+
+purge-which-count = Dime cuántos mensajes recientes revisar, hasta { $max }.
+purge-unknown-filter = Filtro desconocido `{ $filter }`, prueba con un usuario, `bots`, `attachments`, `links`, `before:<id>`, `after:<id>` o `contains:<texto>`.
+purge-bad-message = `{ $id }` no es el id ni el enlace de un mensaje.
+purge-done = { $deleted ->
+        [one] Borrado un mensaje
+       *[other] Borrados { $deleted } mensajes
+    } de los últimos { $scanned }.
+purge-old = { $count ->
+        [one] Uno tenía
+       *[other] { $count } tenían
+    } más de dos semanas, así que se borraron de uno en uno.
+purge-authors = **de**: { $authors }
+
+## logging
+
+log-deleted-title = Mensaje eliminado
+log-edited-title = Mensaje editado
+log-bulk-title = Mensajes purgados
+log-bulk-description = { $count ->
+        [one] Se eliminó un mensaje
+       *[other] Se eliminaron { $count } mensajes
+    } en { $channel }, { $cached } de ellos desde la caché. El registro va adjunto.
+log-not-cached = *no está en la caché, se envió antes de que el bot lo viera*
+log-no-content = *sin texto*
+log-channel = canal
+log-author = autor
+log-attachments = adjuntos
+log-before = antes
+log-after = después
+log-jump = Ir al mensaje
+log-message-id = Mensaje { $id }
+log-user-id = Usuario { $id }
+log-joined-title = Miembro nuevo
+log-left-title = Miembro se fue
+log-member-updated-title = Miembro actualizado
+log-account-age = antigüedad de la cuenta
+log-time-in-server = tiempo en el servidor
+log-roles = roles
+log-no-roles = ninguno
+log-member-not-cached = *no está en la caché*
+log-nickname = apodo
+log-no-nickname = *ninguno*
+log-roles-added = roles añadidos
+log-roles-removed = roles quitados
+log-username = nombre de usuario
+log-avatar = avatar
+log-avatar-changed = cambiado, el nuevo está a la derecha
+log-channel-id = Canal { $id }
+log-role-id = Rol { $id }
+log-channel-created-title = Canal creado
+log-channel-deleted-title = Canal eliminado
+log-channel-updated-title = Canal actualizado
+log-thread-created-title = Hilo creado
+log-thread-deleted-title = Hilo eliminado
+log-thread-updated-title = Hilo actualizado
+log-thread-not-cached = Un hilo en { $parent } que no estaba en la caché.
+log-role-created-title = Rol creado
+log-role-deleted-title = Rol eliminado
+log-role-updated-title = Rol actualizado
+log-not-cached-short = *no está en la caché*
+log-parent = categoría o canal
+log-name = nombre
+log-position = posición
+log-slowmode = modo lento
+log-off = desactivado
+log-archived = archivado
+log-locked = bloqueado
+log-overwrites = permisos del canal
+log-allowed = permitido { $permissions }
+log-denied = denegado { $permissions }
+log-inherited = vuelve a heredar { $permissions }
+log-color = color
+log-hoist = mostrar por separado
+log-mentionable = mencionable
+log-permissions = permisos
+log-granted = permisos concedidos
+log-revoked = permisos retirados
+log-no-permissions = ninguno
+log-yes = sí
+log-no = no
+logignore-unknown-subcommand = Subcomando desconocido `{ $name }`, prueba con `list`, `add` o `remove`.
+logignore-which = Dime qué canal.
+logignore-none = Se registran todos los canales.
+logignore-list = Sin registrar: { $channels }
+logignore-added = { $channel } ya no se registrará, ni sus hilos.
+logignore-removed = { $channel } vuelve a registrarse.
+
+## welcome
+
+welcome-unknown-subcommand = Subcomando desconocido `{ $name }`, prueba con `test`.
+welcome-unknown-message = No hay ningún mensaje `{ $name }`, prueba con `welcome`, `goodbye` o `dm`.
+welcome-dm-off = No hay bienvenida por MD, configúrala con `modules config welcome dm_message <texto>`.
+
+## autorole
+
+autorole-unknown-subcommand = Subcomando desconocido `{ $name }`, prueba con `list`, `add` o `remove`.
+autorole-which = Dime qué rol.
+autorole-none = No se da ningún rol al entrar.
+autorole-list-empty = ninguno
+autorole-list-humans = **Miembros**: { $roles }
+autorole-list-bots = **Bots**: { $roles }
+autorole-added = { $bots ->
+        [true] Los bots recibirán
+       *[other] Los miembros recibirán
+    } { $role } al entrar.
+autorole-removed = { $bots ->
+        [true] Los bots ya no recibirán
+       *[other] Los miembros ya no recibirán
+    } { $role } al entrar.
+autorole-everyone = Todos tienen ya { $role }.
+autorole-unknown-role = No encuentro { $role }.
+autorole-foreign-role = { $role } no es un rol de este servidor.
+autorole-managed = { $role } pertenece a una integración, así que nadie puede darlo.
+autorole-no-permission = Necesito Gestionar roles para dar { $role }.
+autorole-too-high = { $role } no está por debajo de mi rol más alto, así que no puedo darlo.
+autorole-dangerous = { $role } tiene Administrador, Gestionar servidor, Gestionar roles, Banear miembros o Expulsar miembros, así que no lo daré.
+autorole-not-allowed = Necesitas Gestionar roles para dar { $role }.
+autorole-above-you = { $role } no está por debajo de tu rol más alto, así que no puedes darlo.
+
+## rolemenu
+
+rolemenu-unknown-subcommand = Subcomando desconocido `{ $name }`, prueba con `list`, `create`, `add`, `remove`, `max`, `require`, `post` o `delete`.
+rolemenu-none = No hay menús de roles, crea uno con `rolemenu create <buttons|select> <título>`.
+rolemenu-not-posted = sin publicar
+rolemenu-list-entry = **#{ $menu }** { $title } ({ $kind }, { $roles ->
+        [one] un rol
+       *[other] { $roles } roles
+    }): { $posted }
+rolemenu-which-kind = ¿Debe ser `buttons` o `select`?
+rolemenu-which-title = Dale un título.
+rolemenu-created = Creado el menú #{ $menu }. Añade roles con `rolemenu add { $menu } <rol> [etiqueta]` y luego `rolemenu post { $menu }`.
+rolemenu-which-menu = Dime qué menú, por número.
+rolemenu-not-found = No existe el menú #{ $menu }.
+rolemenu-which-role = Dime qué rol.
+rolemenu-full = Un menú no puede tener más de { $max } roles.
+rolemenu-role-added = { $role } está en el menú #{ $menu }.
+rolemenu-role-not-on = { $role } no está en ese menú.
+rolemenu-role-removed = Quitado { $role } del menú #{ $menu }.
+rolemenu-which-count = ¿Cuántos roles puede elegir un miembro? De 0 a { $max }, 0 para no limitar.
+rolemenu-max-set = { $count ->
+        [0] Los miembros pueden elegir tantos roles como quieran
+        [one] Los miembros pueden elegir un rol
+       *[other] Los miembros pueden elegir hasta { $count } roles
+    } del menú #{ $menu }.
+rolemenu-required-set = Solo los miembros con { $role } pueden usar el menú #{ $menu }.
+rolemenu-required-cleared = Cualquiera puede usar el menú #{ $menu }.
+rolemenu-which-channel = Dime qué canal.
+rolemenu-foreign-channel = { $channel } no es un canal de este servidor.
+rolemenu-empty = El menú #{ $menu } aún no tiene roles.
+rolemenu-posted = Publicado el menú #{ $menu } en { $channel }.
+rolemenu-deleted = Eliminado el menú #{ $menu }.
+rolemenu-refresh-failed = No pude actualizar el menú publicado, vuelve a publicarlo con `rolemenu post`.
+rolemenu-footer-max = Elige hasta { $max }
+rolemenu-footer-required = Necesitas un rol para usarlo
+rolemenu-placeholder = Elige tus roles
+rolemenu-gone = Ese menú ya no existe.
+rolemenu-needs-role = Necesitas { $role } para usar este menú.
+rolemenu-max = Solo puedes tener { $max } de estos roles, quita uno primero.
+rolemenu-added = **Añadidos**: { $roles }
+rolemenu-removed = **Quitados**: { $roles }
+rolemenu-failed = **No se pudieron cambiar**: { $roles }
+rolemenu-unchanged = No ha cambiado nada.
+
+## info
+info-no-user = No encontré a ese usuario.
+info-username = Nombre de usuario
+info-display-name = Nombre visible
+info-id = ID
+info-created = Cuenta creada
+info-joined = Se unió
+info-not-member = No está en este servidor
+info-boosting = Mejorando el servidor
+info-boosting-since = Desde { $since }
+info-not-boosting = No
+info-roles = Roles ({ $count })
+info-no-roles = Ninguno
+info-permissions = Permisos clave
+info-no-key-permissions = Ninguno
+info-bot = Cuenta de bot
+info-server-avatar = Avatar de servidor de { $user }
+info-global-avatar = Avatar de { $user }
+
+## owner
+owner-only = Solo los dueños del bot pueden usar esto.
+owner-shutting-down = Apagando.
+owner-reloaded = Configuración recargada.
+owner-reloaded-synced = Los comandos de barra se registraron de nuevo.
+owner-reloaded-restart = Estos cambios necesitan reiniciar: { $changes }
+owner-reload-failed = Se mantuvo la configuración anterior.
+owner-synced = Registrados { $commands } comandos y { $admin } comandos de administración.
+owner-guilds = **{ $count } servidores** (página { $page }/{ $pages })
+owner-guilds-entry = { $name } (`{ $id }`) - { $members } miembros
+owner-which-guild = Dime qué servidor, por id.
+owner-not-in-guild = No estoy en ese servidor.
+owner-left = Salí de { $name } (`{ $id }`).
+blacklist-unknown-subcommand = Subcomando desconocido `{ $name }`, prueba con `list`, `add <user|guild> <id> [duración] [motivo]` o `remove <user|guild> <id>`.
+blacklist-which-kind = ¿Es un `user` o un `guild`?
+blacklist-which-id = Dame un id.
+blacklist-bad-duration = `{ $duration }` no es una duración, prueba con `30m` o `7d`.
+blacklist-owner = Los dueños no pueden estar en la lista negra.
+blacklist-added = { $kind } `{ $id }` está en la lista negra para siempre.
+blacklist-added-for = { $kind } `{ $id }` está en la lista negra durante { $duration }.
+blacklist-left = También salí de él.
+blacklist-removed = { $kind } `{ $id }` ya no está en la lista negra.
+blacklist-not-listed = { $kind } `{ $id }` no está en la lista negra.
+blacklist-empty = La lista negra está vacía.
+blacklist-count = **{ $count } en la lista negra**
+blacklist-entry = { $kind } `{ $id }` - { $reason } - { $expires }
+blacklist-no-reason = sin motivo
+blacklist-permanent = permanente
+blacklist-time-left = quedan { $duration }
+blacklist-more = ...y { $count } más
+shardinfo-count = **{ $running } de { $total } shards funcionando aquí**
+shardinfo-entry = `{ $id }` { $state } - { $latency } - { $guilds } servidores - { $reconnects } reconexiones, { $resumes } reanudaciones, { $invalid } sesiones inválidas
+shardinfo-unhealthy = (con problemas)
+shardinfo-latency = { $ms }ms
+shardinfo-no-latency = sin latencia todavía
+
+## novelty
+
+uwu-broken = ¡Algo se rompió!
+
+## Command definitions
+
+cmd-modules =
+    .description = Gestiona los módulos del bot en este servidor
+cmd-modules-list =
+    .description = Lista los módulos y si están activos
+cmd-modules-enable =
+    .description = Activa un módulo o comando
+cmd-modules-enable-name =
+    .description = Nombre del módulo o comando
+cmd-modules-disable =
+    .description = Desactiva un módulo o comando
+cmd-modules-disable-name =
+    .description = Nombre del módulo o comando
+cmd-modules-config =
+    .description = Muestra o cambia los ajustes de un módulo
+cmd-modules-config-module =
+    .description = Nombre del módulo
+cmd-modules-config-key =
+    .description = Ajuste a mostrar o cambiar
+cmd-modules-config-value =
+    .description = Nuevo valor, o `reset`
+
+cmd-bots =
+    .description = Elige qué bots y webhooks pueden usar comandos
+cmd-bots-show =
+    .description = Muestra la política de bots actual
+cmd-bots-policy =
+    .description = Ignora todos los bots, o solo los permitidos
+cmd-bots-policy-mode =
+    .description = Qué hacer con los bots
+cmd-bots-webhooks =
+    .description = Responde a comandos enviados por webhooks
+cmd-bots-webhooks-enabled =
+    .description = Si se responden
+cmd-bots-allow =
+    .description = Deja que un bot use comandos
+cmd-bots-allow-bot =
+    .description = El bot
+cmd-bots-remove =
+    .description = Deja de permitir que un bot use comandos
+cmd-bots-remove-bot =
+    .description = El bot
+
+cmd-warn =
+    .description = Advierte a un miembro
+cmd-warn-user =
+    .description = El miembro
+cmd-warn-reason =
+    .description = Por qué, para el registro de auditoría
+cmd-kick =
+    .description = Expulsa a un miembro
+cmd-kick-user =
+    .description = El miembro
+cmd-kick-reason =
+    .description = Por qué, para el registro de auditoría
+cmd-ban =
+    .description = Banea a un usuario
+cmd-ban-user =
+    .description = El miembro
+cmd-ban-delete_messages =
+    .description = Borra sus mensajes de hasta este tiempo atrás, como 1h o 7d
+cmd-ban-reason =
+    .description = Por qué, para el registro de auditoría
+cmd-unban =
+    .description = Retira un ban
+cmd-unban-user =
+    .description = El usuario baneado, por su id
+cmd-unban-reason =
+    .description = Por qué, para el registro de auditoría
+cmd-timeout =
+    .description = Aísla temporalmente a un miembro
+cmd-timeout-user =
+    .description = El miembro
+cmd-timeout-duration =
+    .description = Durante cuánto tiempo, como 10m o 1h30m
+cmd-timeout-reason =
+    .description = Por qué, para el registro de auditoría
+cmd-untimeout =
+    .description = Retira el aislamiento de un miembro
+cmd-untimeout-user =
+    .description = El miembro
+cmd-untimeout-reason =
+    .description = Por qué, para el registro de auditoría
+
+cmd-cases =
+    .description = Lista los casos de un usuario
+cmd-cases-user =
+    .description = El usuario
+cmd-case =
+    .description = Casos de moderación
+cmd-case-show =
+    .description = Muestra un caso
+cmd-case-show-number =
+    .description = Número del caso
+cmd-case-reason =
+    .description = Cambia el motivo de un caso
+cmd-case-reason-number =
+    .description = Número del caso
+cmd-case-reason-reason =
+    .description = El nuevo motivo
+cmd-case-delete =
+    .description = Borra un caso
+cmd-case-delete-number =
+    .description = Número del caso
+
+cmd-purge =
+    .description = Borra mensajes recientes
+cmd-purge-count =
+    .description = Cuántos mensajes recientes revisar
+cmd-purge-user =
+    .description = Solo mensajes de este usuario
+cmd-purge-bots =
+    .description = Solo mensajes de bots y webhooks
+cmd-purge-contains =
+    .description = Solo mensajes que contengan este texto
+cmd-purge-attachments =
+    .description = Solo mensajes con archivos adjuntos
+cmd-purge-links =
+    .description = Solo mensajes con enlaces
+cmd-purge-before =
+    .description = Solo mensajes anteriores a este, por id o enlace
+cmd-purge-after =
+    .description = Solo mensajes posteriores a este, por id o enlace
+
+cmd-logignore =
+    .description = Canales que no se registran
+cmd-logignore-list =
+    .description = Muestra los canales ignorados
+cmd-logignore-add =
+    .description = Deja de registrar un canal
+cmd-logignore-add-channel =
+    .description = El canal
+cmd-logignore-remove =
+    .description = Vuelve a registrar un canal
+cmd-logignore-remove-channel =
+    .description = El canal
+
+cmd-welcome =
+    .description = Mensajes de bienvenida y despedida
+cmd-welcome-test =
+    .description = Prueba un mensaje contigo como miembro
+cmd-welcome-test-message =
+    .description = Qué mensaje, la bienvenida por defecto
+
+cmd-autorole =
+    .description = Roles que reciben los miembros al entrar
+cmd-autorole-list =
+    .description = Muestra los roles que se dan al entrar
+cmd-autorole-add =
+    .description = Da un rol al entrar
+cmd-autorole-add-role =
+    .description = El rol
+cmd-autorole-add-bots =
+    .description = Para bots en vez de personas
+cmd-autorole-remove =
+    .description = Deja de dar un rol al entrar
+cmd-autorole-remove-role =
+    .description = El rol
+cmd-autorole-remove-bots =
+    .description = Para bots en vez de personas
+
+cmd-rolemenu =
+    .description = Menús con los que los miembros eligen sus propios roles
+cmd-rolemenu-list =
+    .description = Muestra los menús de este servidor
+cmd-rolemenu-create =
+    .description = Crea un menú nuevo
+cmd-rolemenu-create-kind =
+    .description = Botones o una lista desplegable
+cmd-rolemenu-create-title =
+    .description = Se muestra encima del menú
+cmd-rolemenu-add =
+    .description = Añade un rol, o cambia su etiqueta
+cmd-rolemenu-add-menu =
+    .description = El número del menú
+cmd-rolemenu-add-role =
+    .description = El rol
+cmd-rolemenu-add-label =
+    .description = Se muestra en vez del nombre del rol
+cmd-rolemenu-remove =
+    .description = Quita un rol de un menú
+cmd-rolemenu-remove-menu =
+    .description = El número del menú
+cmd-rolemenu-remove-role =
+    .description = El rol
+cmd-rolemenu-max =
+    .description = Limita cuántos de los roles puede tener un miembro
+cmd-rolemenu-max-menu =
+    .description = El número del menú
+cmd-rolemenu-max-count =
+    .description = 0 para no limitar
+cmd-rolemenu-require =
+    .description = Solo deja usar el menú a miembros con un rol
+cmd-rolemenu-require-menu =
+    .description = El número del menú
+cmd-rolemenu-require-role =
+    .description = El rol
+cmd-rolemenu-post =
+    .description = Publica el menú, o muévelo
+cmd-rolemenu-post-menu =
+    .description = El número del menú
+cmd-rolemenu-post-channel =
+    .description = Dónde, aquí por defecto
+cmd-rolemenu-delete =
+    .description = Elimina un menú
+cmd-rolemenu-delete-menu =
+    .description = El número del menú
+
+cmd-userinfo =
+    .description = Muestra información sobre un usuario
+cmd-userinfo-user =
+    .description = Tú si lo dejas vacío
+cmd-userinfo-size =
+    .description = Tamaño de la imagen en píxeles
+cmd-avatar =
+    .description = Muestra los avatares de servidor y global de un usuario
+cmd-avatar-user =
+    .description = Tú si lo dejas vacío
+cmd-avatar-size =
+    .description = Tamaño de la imagen en píxeles
+
+cmd-ping =
+    .description = Muestra el ping actual a Discord
+cmd-stats =
+    .description = Estadísticas del servidor
+cmd-server =
+    .description = Estadísticas del servidor
+cmd-server-info =
+    .description = Información sobre el servidor
+cmd-uwu =
+    .description = Uwuifica un texto
+cmd-uwu-text =
+    .description = El texto que quieres procesar
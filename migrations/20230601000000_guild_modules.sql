@@ -0,0 +1,19 @@
+CREATE TABLE IF NOT EXISTS disabled_modules (
+    guild_id INTEGER NOT NULL,
+    module TEXT NOT NULL,
+    PRIMARY KEY (guild_id, module)
+);
+
+CREATE TABLE IF NOT EXISTS disabled_commands (
+    guild_id INTEGER NOT NULL,
+    command TEXT NOT NULL,
+    PRIMARY KEY (guild_id, command)
+);
+
+CREATE TABLE IF NOT EXISTS module_settings (
+    guild_id INTEGER NOT NULL,
+    module TEXT NOT NULL,
+    key TEXT NOT NULL,
+    value TEXT NOT NULL,
+    PRIMARY KEY (guild_id, module, key)
+);
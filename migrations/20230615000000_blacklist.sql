@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS blacklist (
+    kind TEXT NOT NULL,
+    id INTEGER NOT NULL,
+    added_at INTEGER NOT NULL,
+    PRIMARY KEY (kind, id)
+);
@@ -0,0 +1,2 @@
+ALTER TABLE blacklist ADD COLUMN reason TEXT;
+ALTER TABLE blacklist ADD COLUMN expires_at INTEGER;
@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS allowed_bots (
+    guild_id INTEGER NOT NULL,
+    bot_id INTEGER NOT NULL,
+    PRIMARY KEY (guild_id, bot_id)
+);
@@ -0,0 +1,20 @@
+CREATE TABLE IF NOT EXISTS mod_cases (
+    guild_id INTEGER NOT NULL,
+    case_id INTEGER NOT NULL,
+    action TEXT NOT NULL,
+    user_id INTEGER NOT NULL,
+    moderator_id INTEGER NOT NULL,
+    reason TEXT,
+    duration INTEGER,
+    created_at INTEGER NOT NULL,
+    log_channel_id INTEGER,
+    log_message_id INTEGER,
+    PRIMARY KEY (guild_id, case_id)
+);
+
+CREATE INDEX IF NOT EXISTS mod_cases_user ON mod_cases (guild_id, user_id);
+
+CREATE TABLE IF NOT EXISTS mod_case_numbers (
+    guild_id INTEGER PRIMARY KEY NOT NULL,
+    last_case INTEGER NOT NULL
+);
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS autoroles (
+    guild_id INTEGER NOT NULL,
+    role_id INTEGER NOT NULL,
+    bots INTEGER NOT NULL,
+    PRIMARY KEY (guild_id, role_id, bots)
+);
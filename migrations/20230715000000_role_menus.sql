@@ -0,0 +1,19 @@
+CREATE TABLE IF NOT EXISTS role_menus (
+    menu_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    guild_id INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    title TEXT NOT NULL,
+    max_selections INTEGER,
+    required_role_id INTEGER,
+    channel_id INTEGER,
+    message_id INTEGER
+);
+
+CREATE INDEX IF NOT EXISTS role_menus_guild ON role_menus (guild_id);
+
+CREATE TABLE IF NOT EXISTS role_menu_roles (
+    menu_id INTEGER NOT NULL,
+    role_id INTEGER NOT NULL,
+    label TEXT,
+    PRIMARY KEY (menu_id, role_id)
+);
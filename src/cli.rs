@@ -0,0 +1,107 @@
+use std::{path::PathBuf, sync::Arc};
+
+use clap::{Parser, Subcommand};
+use twilight_http::Client;
+use twilight_model::id::{marker::GuildMarker, Id};
+
+use crate::{cmd::OshiroResult, config::Config, db, slash};
+
+#[derive(Parser)]
+#[command(name = "oshiro", version, about = "a discord bot")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+}
+
+#[derive(Subcommand)]
+pub enum CliCommand {
+    /// Connect to the gateway and run the bot (the default)
+    Run,
+    /// Manage slash command registrations
+    #[command(subcommand)]
+    Commands(CommandsCommand),
+    /// Check the configuration
+    #[command(subcommand)]
+    Config(ConfigCommand),
+    /// Manage the database
+    #[command(subcommand)]
+    Db(DbCommand),
+    /// Feed a recording of gateway events through the handlers against a mock API
+    Replay {
+        /// A file written with `gateway.record`
+        path: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CommandsCommand {
+    /// Register slash commands, to `bot.dev_guild` if it's set and globally otherwise, and the
+    /// owner commands to `bot.admin_guild`
+    Sync {
+        /// Only register the public commands, to this guild
+        #[arg(long)]
+        guild: Option<Id<GuildMarker>>,
+    },
+    /// Print the JSON that would be registered
+    Dump,
+    /// Remove every registered slash command
+    Clear {
+        /// Clear this guild's commands instead of the global ones
+        #[arg(long)]
+        guild: Option<Id<GuildMarker>>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// Load and validate the config, reporting every problem
+    Check,
+}
+
+#[derive(Subcommand)]
+pub enum DbCommand {
+    /// Create the database if needed and apply any pending migrations
+    Migrate,
+}
+
+/// Print the slash command definitions. Doesn't need a config or a token.
+pub fn dump_commands() -> OshiroResult<()> {
+    println!("{}", serde_json::to_string_pretty(&slash::definitions())?);
+    Ok(())
+}
+
+pub async fn commands(config: Arc<Config>, command: CommandsCommand) -> OshiroResult<()> {
+    let http = Client::new(config.bot.token.clone());
+    let app_id = http.current_user_application().await?.model().await?.id;
+
+    match command {
+        CommandsCommand::Sync { guild: Some(guild) } => {
+            let definitions = slash::definitions();
+            slash::register(&http, app_id, Some(guild), &definitions).await?;
+            println!("registered {} commands", definitions.len());
+        }
+        CommandsCommand::Sync { guild: None } => {
+            slash::sync(&http, app_id, &config).await?;
+            println!(
+                "registered {} commands and {} admin commands",
+                slash::definitions().len(),
+                slash::admin_definitions().len()
+            );
+        }
+        CommandsCommand::Clear { guild } => {
+            slash::register(&http, app_id, guild, &[]).await?;
+            println!("cleared commands");
+        }
+        CommandsCommand::Dump => dump_commands()?,
+    }
+    Ok(())
+}
+
+pub async fn migrate(config: Arc<Config>) -> OshiroResult<()> {
+    db::connect(&config.storage.path).await?;
+    println!(
+        "database at {} is up to date",
+        config.storage.path.display()
+    );
+    Ok(())
+}
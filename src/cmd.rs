@@ -1,11 +1,27 @@
-use std::{collections::HashMap, error::Error, pin::Pin, sync::Arc};
+use std::{collections::HashMap, error::Error, pin::Pin, sync::Arc, time::Instant};
 
 use futures::Future;
+use sqlx::SqlitePool;
 use tokio::sync::Mutex;
-use twilight_model::application::interaction::Interaction;
+use twilight_model::{
+    application::interaction::Interaction,
+    channel::message::Embed,
+    guild::Permissions,
+    id::{
+        marker::{ChannelMarker, GuildMarker, UserMarker},
+        Id,
+    },
+};
 
+use crate::config::CommandsConfig;
 use crate::ctx::OshiroContext;
-use crate::slash::message;
+use crate::db;
+use crate::i18n;
+use crate::listener::ListenerRegistry;
+use crate::metrics;
+use crate::module::Module;
+use crate::slash::{self, ephemeral_message};
+use crate::t;
 
 pub type OshiroResult<T = ()> = Result<T, Box<dyn Error + Send + Sync>>;
 
@@ -33,9 +49,162 @@ pub struct CommandContext {
     pub stripped: Option<String>,
     pub slash: Option<Interaction>,
 }
+
+impl CommandContext {
+    pub fn guild_id(&self) -> Option<Id<GuildMarker>> {
+        match &self.slash {
+            Some(slash) => slash.guild_id,
+            None => self.msg.as_ref().and_then(|m| m.guild_id),
+        }
+    }
+
+    pub fn channel_id(&self) -> Option<Id<ChannelMarker>> {
+        match &self.slash {
+            Some(slash) => slash.channel.as_ref().map(|c| c.id),
+            None => self.msg.as_ref().map(|m| m.channel_id),
+        }
+    }
+
+    /// The user that ran the command. Slash commands in guilds only carry the member.
+    pub fn author_id(&self) -> Option<Id<UserMarker>> {
+        match &self.slash {
+            Some(slash) => slash
+                .member
+                .as_ref()
+                .and_then(|m| m.user.as_ref())
+                .or(slash.user.as_ref())
+                .map(|u| u.id),
+            None => self.msg.as_ref().map(|m| m.author.id),
+        }
+    }
+
+    /// Whether the user that ran the command is in `bot.owners`
+    pub async fn is_owner(&self) -> bool {
+        match self.author_id() {
+            Some(id) => self.oshiro.lock().await.config.bot.owners.contains(&id),
+            None => false,
+        }
+    }
+
+    /// The locale to answer in: the user's Discord language for slash commands, then the guild's
+    /// `language` setting, then the guild's own locale, then English
+    pub async fn locale(&self) -> &'static str {
+        if let Some(locale) = self
+            .slash
+            .as_ref()
+            .and_then(|s| s.locale.as_deref())
+            .and_then(i18n::supported)
+        {
+            return locale;
+        }
+        let guild_id = match self.guild_id() {
+            Some(g) => g,
+            None => return i18n::FALLBACK,
+        };
+        let (db, cached) = {
+            let oshi = self.oshiro.lock().await;
+            let cached = oshi
+                .cache
+                .guild(guild_id)
+                .map(|g| g.preferred_locale().to_string());
+            (oshi.db.clone(), cached)
+        };
+        let preferred = self
+            .slash
+            .as_ref()
+            .and_then(|s| s.guild_locale.clone())
+            .or(cached);
+        i18n::guild_locale(&db, guild_id, preferred.as_deref()).await
+    }
+
+    pub async fn db(&self) -> SqlitePool {
+        self.oshiro.lock().await.db.clone()
+    }
+
+    pub async fn framework(&self) -> Arc<CommandFramework> {
+        Arc::clone(&self.oshiro.lock().await.framework)
+    }
+
+    /// Reply with plain text, works for both text and slash commands
+    pub async fn reply(&self, content: &str) -> OshiroResult<()> {
+        self.oshiro
+            .lock()
+            .await
+            .send_msg(
+                content.to_string(),
+                None,
+                self.msg.clone(),
+                self.slash.as_ref(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Like `reply`, but only the invoking user sees it when used as a slash command
+    pub async fn reply_ephemeral(&self, content: &str) -> OshiroResult<()> {
+        self.oshiro
+            .lock()
+            .await
+            .send_msg(
+                content.to_string(),
+                Some(ephemeral_message(content)),
+                self.msg.clone(),
+                self.slash.as_ref(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Reply with embeds, works for both text and slash commands
+    pub async fn reply_embeds(&self, embeds: Vec<Embed>) -> OshiroResult<()> {
+        let oshi = self.oshiro.lock().await;
+        match (&self.slash, &self.msg) {
+            (Some(interaction), _) => {
+                oshi.interaction()
+                    .create_response(interaction.id, &interaction.token, &slash::embed(embeds))
+                    .await?;
+            }
+            (None, Some(msg)) => {
+                oshi.http
+                    .create_message(msg.channel_id)
+                    .embeds(&embeds)?
+                    .await?;
+            }
+            (None, None) => {}
+        }
+        Ok(())
+    }
+
+    /// Whether the invoking member has a permission guild-wide
+    pub async fn has_permission(&self, permission: Permissions) -> bool {
+        if let Some(slash) = &self.slash {
+            return slash
+                .member
+                .as_ref()
+                .and_then(|m| m.permissions)
+                .map_or(false, |p| p.contains(permission));
+        }
+        let (guild_id, user_id) = match (self.guild_id(), self.author_id()) {
+            (Some(g), Some(u)) => (g, u),
+            _ => return false,
+        };
+        self.oshiro
+            .lock()
+            .await
+            .cache
+            .permissions()
+            .root(user_id, guild_id)
+            .map_or(false, |p| p.contains(permission))
+    }
+}
+
 #[derive(Default, Clone)]
 pub struct CommandFramework {
     commands: HashMap<String, Arc<CommandInstance>>,
+    modules: Vec<Arc<dyn Module>>,
+    /// Module name for every text and slash command name
+    command_modules: HashMap<String, &'static str>,
+    listeners: ListenerRegistry,
 }
 
 #[macro_use]
@@ -50,11 +219,22 @@ pub mod macros {
     /// ```
     macro_rules! cmd {
         ($framework: ident, $func: ident, $name: expr, $desc: expr) => {{
-            $framework.add_command(Arc::new(CommandInstance {
+            $framework.add_command(Arc::new($crate::command!($func, $name, $desc)))
+        }};
+    }
+
+    #[macro_export]
+    /// Builds a CommandInstance, for modules to hand to the CommandFramework.
+    /// ```
+    /// command!(function, "name", "description");
+    /// ```
+    macro_rules! command {
+        ($func: path, $name: expr, $desc: expr) => {{
+            $crate::cmd::CommandInstance {
                 name: format!("{}", $name),
                 description: format!("{}", $desc),
                 exec: Box::new(move |ctx| Box::pin($func(ctx))),
-            }))
+            }
         }};
     }
 }
@@ -62,23 +242,108 @@ pub mod macros {
 impl CommandFramework {
     /// Make a CommandFramework
     pub async fn create() -> OshiroResult<Self> {
-        let uwu = crate::commands::novelty::uwu;
-        let ping = crate::commands::system::ping;
         let mut f = CommandFramework::default();
-        cmd!(
-            f,
-            hi_echo,
-            "hi".to_string(),
-            "will respond with hi".to_string()
-        )
-        .await?;
-        cmd!(f, ping, "ping", "just 4 fun").await?;
-        cmd!(f, uwu, "uwu", "uwuifies strings xD").await?;
-        let guild_info = crate::commands::system::guild_info;
-        cmd!(f, guild_info, "guild_info", "get info about the guild").await?;
+        for module in crate::commands::modules() {
+            f.add_module(module).await?;
+        }
         Ok(f)
     }
 
+    /// Add a module along with its text commands and listeners.
+    pub async fn add_module(&mut self, module: Arc<dyn Module>) -> OshiroResult<()> {
+        for cmd in module.commands() {
+            self.command_modules.insert(cmd.name.clone(), module.name());
+            self.add_command(Arc::new(cmd)).await?;
+        }
+        for name in module
+            .slash_commands()
+            .into_keys()
+            .chain(module.admin_slash_commands().into_keys())
+        {
+            self.command_modules.insert(name, module.name());
+        }
+        for listener in module.listeners() {
+            self.listeners.add(module.name(), listener);
+        }
+        self.modules.push(module);
+        Ok(())
+    }
+
+    pub fn modules(&self) -> &[Arc<dyn Module>] {
+        &self.modules
+    }
+
+    pub fn module(&self, name: &str) -> Option<&Arc<dyn Module>> {
+        self.modules.iter().find(|m| m.name() == name)
+    }
+
+    /// The module a text or (top level) slash command belongs to
+    pub fn module_of(&self, command: &str) -> Option<&Arc<dyn Module>> {
+        self.command_modules
+            .get(command)
+            .and_then(|name| self.module(name))
+    }
+
+    /// Whether a module is switched on in a guild, going by `commands.disabled_modules` and
+    /// then the guild's own choice. Only the global switch applies in DMs.
+    pub async fn module_enabled(
+        &self,
+        db: &SqlitePool,
+        config: &CommandsConfig,
+        guild_id: Option<Id<GuildMarker>>,
+        module: &dyn Module,
+    ) -> OshiroResult<bool> {
+        if !module.can_disable() {
+            return Ok(true);
+        }
+        if config.disabled_modules.iter().any(|m| m == module.name()) {
+            return Ok(false);
+        }
+        let guild_id = match guild_id {
+            Some(g) => g,
+            None => return Ok(true),
+        };
+        Ok(!db::disabled_modules(db, guild_id)
+            .await?
+            .iter()
+            .any(|m| m == module.name()))
+    }
+
+    /// Whether a command may run in a guild, checking both its module and the command itself,
+    /// globally and then in the guild
+    pub async fn command_enabled(
+        &self,
+        db: &SqlitePool,
+        config: &CommandsConfig,
+        guild_id: Option<Id<GuildMarker>>,
+        command: &str,
+    ) -> OshiroResult<bool> {
+        let module = match self.module_of(command) {
+            Some(m) if m.can_disable() => m,
+            _ => return Ok(true),
+        };
+        if config.disabled_commands.iter().any(|c| c == command)
+            || !self
+                .module_enabled(db, config, guild_id, module.as_ref())
+                .await?
+        {
+            return Ok(false);
+        }
+        let guild_id = match guild_id {
+            Some(g) => g,
+            None => return Ok(true),
+        };
+        Ok(!db::disabled_commands(db, guild_id)
+            .await?
+            .iter()
+            .any(|c| c == command))
+    }
+
+    /// Event listeners from every module
+    pub fn listeners(&self) -> &ListenerRegistry {
+        &self.listeners
+    }
+
     /// Add a command. Internally used in the "cmd" macro.
     pub async fn add_command(&mut self, cmd: Arc<CommandInstance>) -> OshiroResult<()> {
         self.commands.insert(cmd.name.clone(), cmd);
@@ -106,6 +371,30 @@ impl CommandFramework {
                     return Ok(());
                 }
             };
+            let (db, config, cooldowns) = {
+                let oshi = ctx.lock().await;
+                (
+                    oshi.db.clone(),
+                    Arc::clone(&oshi.config),
+                    Arc::clone(&oshi.cooldowns),
+                )
+            };
+            let owner = config.bot.owners.contains(&msg.author.id);
+            if !owner && db::is_blacklisted(&db, msg.author.id, msg.guild_id).await? {
+                tracing::debug!(
+                    "Ignoring {} from blacklisted {}",
+                    possible_cmd,
+                    msg.author.id
+                );
+                return Ok(());
+            }
+            if !self
+                .command_enabled(&db, &config.commands, msg.guild_id, possible_cmd)
+                .await?
+            {
+                tracing::debug!("{} is disabled in {:?}", possible_cmd, msg.guild_id);
+                return Ok(());
+            }
             let cctx = CommandContext {
                 command_type: CommandType::TEXT,
                 oshiro: Arc::clone(&ctx),
@@ -118,45 +407,25 @@ impl CommandFramework {
                 ),
                 slash: None,
             };
-            (v.exec)(cctx).await?;
+            let cooldown = config.commands.cooldown(possible_cmd);
+            if !owner {
+                if let Some(left) = cooldowns.hit(possible_cmd, msg.author.id, cooldown) {
+                    let locale = cctx.locale().await;
+                    return cctx
+                        .reply(&t!(
+                            locale,
+                            "cooldown",
+                            command = possible_cmd,
+                            seconds = left.as_secs() + 1
+                        ))
+                        .await;
+                }
+            }
+            let started = Instant::now();
+            let result = (v.exec)(cctx).await;
+            metrics::command(possible_cmd, "text", started.elapsed(), result.is_ok());
+            result?;
         }
         Ok(())
     }
 }
-
-async fn hi_echo(ctx: CommandContext) -> OshiroResult<()> {
-    if ctx.command_type == CommandType::TEXT {
-        let msg = ctx.msg.expect("is text");
-
-        let a = msg.author;
-        ctx.oshiro
-            .lock()
-            .await
-            .http
-            .create_message(msg.channel_id)
-            .content(&format!(
-                "hello, {}. Here's some information about you:\n```{}```",
-                a.name,
-                simd_json::to_string_pretty(&a)?
-            ))?
-            .await?;
-    } else {
-        let slash = ctx.slash.expect("is slash command");
-        let user = slash.user.expect("slash command has user");
-        ctx.oshiro
-            .lock()
-            .await
-            .interaction()
-            .create_response(
-                slash.id,
-                &slash.token,
-                &message(&format!(
-                    "hello, {}. Here's some information about you:\n```{}```",
-                    user.name,
-                    simd_json::to_string_pretty(&user)?
-                )),
-            )
-            .await?;
-    }
-    Ok(())
-}
@@ -0,0 +1,719 @@
+use std::{collections::HashMap, sync::Arc};
+
+use tokio::sync::Mutex;
+use twilight_model::{
+    application::{
+        command::CommandType as SlashCommandType,
+        interaction::application_command::CommandOptionValue,
+    },
+    channel::Message,
+    guild::Permissions,
+    id::{
+        marker::{GuildMarker, UserMarker},
+        Id,
+    },
+};
+use twilight_util::builder::command::{
+    BooleanBuilder, CommandBuilder, StringBuilder, SubCommandBuilder, UserBuilder,
+};
+
+use crate::{
+    cmd::{CommandContext, CommandInstance, OshiroResult},
+    command,
+    ctx::OshiroContext,
+    db,
+    helper::{channel_in_guild, parse_user, role_in_guild},
+    i18n,
+    module::{self, ConfigField, ConfigKind, Module},
+    slash::{self, CommandGroup, CommandWrapper},
+    t,
+};
+
+/// Server admin tools: modules and the bot policy. Can't be disabled, otherwise there'd be no
+/// way to turn it back on.
+pub struct Admin;
+
+impl Module for Admin {
+    fn name(&self) -> &'static str {
+        "admin"
+    }
+
+    fn description(&self) -> &'static str {
+        "turn modules and commands on or off, and choose which bots can use commands"
+    }
+
+    fn can_disable(&self) -> bool {
+        false
+    }
+
+    fn commands(&self) -> Vec<CommandInstance> {
+        vec![
+            command!(
+                modules_text,
+                "modules",
+                "list, enable, disable or configure modules"
+            ),
+            command!(
+                bots_text,
+                "bots",
+                "choose which bots and webhooks can use commands"
+            ),
+        ]
+    }
+
+    fn config_schema(&self) -> Vec<ConfigField> {
+        vec![
+            ConfigField {
+                key: "bot_policy",
+                description: "ignore all bots, or only answer those added with `bots allow`",
+                kind: ConfigKind::Choice(BOT_POLICIES),
+                default: Some("ignore"),
+            },
+            ConfigField {
+                key: "allow_webhooks",
+                description: "answer commands sent through webhooks",
+                kind: ConfigKind::Bool,
+                default: Some("false"),
+            },
+            ConfigField {
+                key: "language",
+                description: "language for text command replies, and for slash commands when \
+                              the user's own isn't available",
+                kind: ConfigKind::Choice(i18n::LOCALES),
+                default: None,
+            },
+        ]
+    }
+
+    fn slash_commands(&self) -> HashMap<String, CommandWrapper> {
+        let command = CommandBuilder::new(
+            "modules",
+            "Manage the bot's modules in this server",
+            SlashCommandType::ChatInput,
+        )
+        .dm_permission(false)
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .option(SubCommandBuilder::new(
+            "list",
+            "List modules and whether they're on",
+        ))
+        .option(
+            SubCommandBuilder::new("enable", "Turn on a module or command")
+                .option(StringBuilder::new("name", "Module or command name").required(true)),
+        )
+        .option(
+            SubCommandBuilder::new("disable", "Turn off a module or command")
+                .option(StringBuilder::new("name", "Module or command name").required(true)),
+        )
+        .option(
+            SubCommandBuilder::new("config", "Show or change a module's settings")
+                .option(StringBuilder::new("module", "Module name").required(true))
+                .option(StringBuilder::new("key", "Setting to show or change"))
+                .option(StringBuilder::new("value", "New value, or `reset`")),
+        )
+        .build();
+
+        let mut subcommands: HashMap<String, CommandGroup> = HashMap::new();
+        subcommands.insert(
+            "list".to_string(),
+            CommandGroup::Command(Box::new(move |ctx| Box::pin(modules_list(ctx)))),
+        );
+        subcommands.insert(
+            "enable".to_string(),
+            CommandGroup::Command(Box::new(move |ctx| Box::pin(modules_enable(ctx)))),
+        );
+        subcommands.insert(
+            "disable".to_string(),
+            CommandGroup::Command(Box::new(move |ctx| Box::pin(modules_disable(ctx)))),
+        );
+        subcommands.insert(
+            "config".to_string(),
+            CommandGroup::Command(Box::new(move |ctx| Box::pin(modules_config(ctx)))),
+        );
+
+        let bots = CommandBuilder::new(
+            "bots",
+            "Choose which bots and webhooks can use commands",
+            SlashCommandType::ChatInput,
+        )
+        .dm_permission(false)
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .option(SubCommandBuilder::new(
+            "show",
+            "Show the current bot policy",
+        ))
+        .option(
+            SubCommandBuilder::new("policy", "Ignore all bots, or only allowed ones").option(
+                StringBuilder::new("mode", "What to do with bots")
+                    .required(true)
+                    .choices(BOT_POLICIES.iter().map(|p| (*p, *p))),
+            ),
+        )
+        .option(
+            SubCommandBuilder::new("webhooks", "Answer commands sent through webhooks")
+                .option(BooleanBuilder::new("enabled", "Whether to answer them").required(true)),
+        )
+        .option(
+            SubCommandBuilder::new("allow", "Let a bot use commands")
+                .option(UserBuilder::new("bot", "The bot").required(true)),
+        )
+        .option(
+            SubCommandBuilder::new("remove", "Stop letting a bot use commands")
+                .option(UserBuilder::new("bot", "The bot").required(true)),
+        )
+        .build();
+
+        let mut bots_subcommands: HashMap<String, CommandGroup> = HashMap::new();
+        bots_subcommands.insert(
+            "show".to_string(),
+            CommandGroup::Command(Box::new(move |ctx| Box::pin(bots_show_slash(ctx)))),
+        );
+        bots_subcommands.insert(
+            "policy".to_string(),
+            CommandGroup::Command(Box::new(move |ctx| Box::pin(bots_policy_slash(ctx)))),
+        );
+        bots_subcommands.insert(
+            "webhooks".to_string(),
+            CommandGroup::Command(Box::new(move |ctx| Box::pin(bots_webhooks_slash(ctx)))),
+        );
+        bots_subcommands.insert(
+            "allow".to_string(),
+            CommandGroup::Command(Box::new(move |ctx| Box::pin(bots_allow_slash(ctx)))),
+        );
+        bots_subcommands.insert(
+            "remove".to_string(),
+            CommandGroup::Command(Box::new(move |ctx| Box::pin(bots_remove_slash(ctx)))),
+        );
+
+        let mut commands = HashMap::new();
+        commands.insert(
+            "modules".to_string(),
+            CommandWrapper {
+                command,
+                function: None,
+                subcommands: Some(subcommands),
+            },
+        );
+        commands.insert(
+            "bots".to_string(),
+            CommandWrapper {
+                command: bots,
+                function: None,
+                subcommands: Some(bots_subcommands),
+            },
+        );
+        commands
+    }
+}
+
+/// Values of the `bot_policy` setting
+const BOT_POLICIES: &[&str] = &["ignore", "allowlist"];
+
+/// Whether a message from a bot or webhook may run commands, going by `bot.good_bots` and the
+/// guild's bot policy. Our own messages never get here.
+pub async fn bot_message_allowed(
+    ctx: &Arc<Mutex<OshiroContext>>,
+    msg: &Message,
+) -> OshiroResult<bool> {
+    let (db, good_bots) = {
+        let oshi = ctx.lock().await;
+        (oshi.db.clone(), oshi.config.bot.good_bots.clone())
+    };
+    if good_bots.contains(&msg.author.id) {
+        return Ok(true);
+    }
+    let guild_id = match msg.guild_id {
+        Some(g) => g,
+        None => return Ok(false),
+    };
+
+    if msg.webhook_id.is_some() {
+        let allowed = module::setting(&db, guild_id, &Admin, "allow_webhooks").await?;
+        return Ok(allowed.as_deref() == Some("true"));
+    }
+    let policy = module::setting(&db, guild_id, &Admin, "bot_policy").await?;
+    if policy.as_deref() != Some("allowlist") {
+        return Ok(false);
+    }
+    Ok(db::allowed_bots(&db, guild_id)
+        .await?
+        .contains(&msg.author.id))
+}
+
+/// Checks the command was used in a guild by someone allowed to manage it
+pub async fn check_admin(ctx: &CommandContext) -> OshiroResult<bool> {
+    if ctx.guild_id().is_none() {
+        ctx.reply_ephemeral(&t!(ctx.locale().await, "not-in-guild"))
+            .await?;
+        return Ok(false);
+    }
+    if !ctx.has_permission(Permissions::MANAGE_GUILD).await {
+        ctx.reply_ephemeral(&t!(ctx.locale().await, "need-manage-guild"))
+            .await?;
+        return Ok(false);
+    }
+    Ok(true)
+}
+
+async fn modules_text(ctx: CommandContext) -> OshiroResult {
+    if !check_admin(&ctx).await? {
+        return Ok(());
+    }
+    let stripped = ctx.stripped.clone().unwrap_or_default();
+    let args: Vec<&str> = stripped.split_whitespace().collect();
+    let out = match args.first().copied() {
+        None | Some("list") => list(&ctx).await?,
+        Some("enable") => toggle(&ctx, args.get(1).copied(), true).await?,
+        Some("disable") => toggle(&ctx, args.get(1).copied(), false).await?,
+        Some("config") => {
+            let value = if args.len() > 3 {
+                Some(args[3..].join(" "))
+            } else {
+                None
+            };
+            configure(
+                &ctx,
+                args.get(1).copied(),
+                args.get(2).copied(),
+                value.as_deref(),
+            )
+            .await?
+        }
+        Some(other) => t!(
+            ctx.locale().await,
+            "modules-unknown-subcommand",
+            name = other
+        ),
+    };
+    ctx.reply(&out).await
+}
+
+async fn modules_list(ctx: CommandContext) -> OshiroResult {
+    if !check_admin(&ctx).await? {
+        return Ok(());
+    }
+    let out = list(&ctx).await?;
+    ctx.reply_ephemeral(&out).await
+}
+
+async fn modules_enable(ctx: CommandContext) -> OshiroResult {
+    if !check_admin(&ctx).await? {
+        return Ok(());
+    }
+    let name = ctx
+        .slash
+        .as_ref()
+        .and_then(|s| slash::option_string(s, "name"));
+    let out = toggle(&ctx, name.as_deref(), true).await?;
+    ctx.reply_ephemeral(&out).await
+}
+
+async fn modules_disable(ctx: CommandContext) -> OshiroResult {
+    if !check_admin(&ctx).await? {
+        return Ok(());
+    }
+    let name = ctx
+        .slash
+        .as_ref()
+        .and_then(|s| slash::option_string(s, "name"));
+    let out = toggle(&ctx, name.as_deref(), false).await?;
+    ctx.reply_ephemeral(&out).await
+}
+
+async fn modules_config(ctx: CommandContext) -> OshiroResult {
+    if !check_admin(&ctx).await? {
+        return Ok(());
+    }
+    let slash = ctx.slash.as_ref().expect("slash command");
+    let module = slash::option_string(slash, "module");
+    let key = slash::option_string(slash, "key");
+    let value = slash::option_string(slash, "value");
+    let out = configure(&ctx, module.as_deref(), key.as_deref(), value.as_deref()).await?;
+    ctx.reply_ephemeral(&out).await
+}
+
+async fn bots_text(ctx: CommandContext) -> OshiroResult {
+    if !check_admin(&ctx).await? {
+        return Ok(());
+    }
+    let stripped = ctx.stripped.clone().unwrap_or_default();
+    let args: Vec<&str> = stripped.split_whitespace().collect();
+    let value = args.get(1).copied();
+    let out = match args.first().copied() {
+        None | Some("show") => bots_show(&ctx).await?,
+        Some("policy") => bots_policy(&ctx, value).await?,
+        Some("webhooks") => bots_webhooks(&ctx, value).await?,
+        Some("allow") => bots_allow(&ctx, value.and_then(parse_user), true).await?,
+        Some("remove") => bots_allow(&ctx, value.and_then(parse_user), false).await?,
+        Some(other) => t!(ctx.locale().await, "bots-unknown-subcommand", name = other),
+    };
+    ctx.reply(&out).await
+}
+
+async fn bots_show_slash(ctx: CommandContext) -> OshiroResult {
+    if !check_admin(&ctx).await? {
+        return Ok(());
+    }
+    let out = bots_show(&ctx).await?;
+    ctx.reply_ephemeral(&out).await
+}
+
+async fn bots_policy_slash(ctx: CommandContext) -> OshiroResult {
+    if !check_admin(&ctx).await? {
+        return Ok(());
+    }
+    let mode = ctx
+        .slash
+        .as_ref()
+        .and_then(|s| slash::option_string(s, "mode"));
+    let out = bots_policy(&ctx, mode.as_deref()).await?;
+    ctx.reply_ephemeral(&out).await
+}
+
+async fn bots_webhooks_slash(ctx: CommandContext) -> OshiroResult {
+    if !check_admin(&ctx).await? {
+        return Ok(());
+    }
+    let enabled = match ctx.slash.as_ref().and_then(|s| slash::option(s, "enabled")) {
+        Some(CommandOptionValue::Boolean(b)) => Some(b.to_string()),
+        _ => None,
+    };
+    let out = bots_webhooks(&ctx, enabled.as_deref()).await?;
+    ctx.reply_ephemeral(&out).await
+}
+
+async fn bots_allow_slash(ctx: CommandContext) -> OshiroResult {
+    if !check_admin(&ctx).await? {
+        return Ok(());
+    }
+    let out = bots_allow(&ctx, slash_bot(&ctx), true).await?;
+    ctx.reply_ephemeral(&out).await
+}
+
+async fn bots_remove_slash(ctx: CommandContext) -> OshiroResult {
+    if !check_admin(&ctx).await? {
+        return Ok(());
+    }
+    let out = bots_allow(&ctx, slash_bot(&ctx), false).await?;
+    ctx.reply_ephemeral(&out).await
+}
+
+fn slash_bot(ctx: &CommandContext) -> Option<Id<UserMarker>> {
+    match ctx.slash.as_ref().and_then(|s| slash::option(s, "bot")) {
+        Some(CommandOptionValue::User(id)) => Some(id),
+        _ => None,
+    }
+}
+
+async fn bots_show(ctx: &CommandContext) -> OshiroResult<String> {
+    let guild_id = ctx.guild_id().expect("checked by check_admin");
+    let db = ctx.db().await;
+    let policy = module::setting(&db, guild_id, &Admin, "bot_policy")
+        .await?
+        .unwrap_or_default();
+    let webhooks = module::setting(&db, guild_id, &Admin, "allow_webhooks")
+        .await?
+        .unwrap_or_default();
+    let allowed = db::allowed_bots(&db, guild_id).await?;
+    let locale = ctx.locale().await;
+
+    let mut lines = vec![
+        t!(locale, "bots-policy-line", policy = policy.as_str()),
+        t!(locale, "bots-webhooks-line", enabled = webhooks),
+    ];
+    if !allowed.is_empty() {
+        let bots = allowed
+            .iter()
+            .map(|b| format!("<@{}>", b))
+            .collect::<Vec<_>>()
+            .join(", ");
+        lines.push(t!(locale, "bots-allowed-line", bots = bots));
+    }
+    if policy != "allowlist" && !allowed.is_empty() {
+        lines.push(t!(locale, "bots-allowlist-inactive"));
+    }
+    Ok(lines.join("\n"))
+}
+
+async fn bots_policy(ctx: &CommandContext, value: Option<&str>) -> OshiroResult<String> {
+    let guild_id = ctx.guild_id().expect("checked by check_admin");
+    let locale = ctx.locale().await;
+    let policy = match value.map(|v| ConfigKind::Choice(BOT_POLICIES).parse(v)) {
+        Some(Ok(p)) => p,
+        Some(Err(e)) => return Ok(e.render(locale)),
+        None => {
+            return Ok(t!(
+                locale,
+                "bots-pick-policy",
+                policies = BOT_POLICIES.join(", ")
+            ))
+        }
+    };
+    db::set_module_setting(
+        &ctx.db().await,
+        guild_id,
+        "admin",
+        "bot_policy",
+        Some(&policy),
+    )
+    .await?;
+    Ok(t!(locale, "bots-policy-set", policy = policy))
+}
+
+async fn bots_webhooks(ctx: &CommandContext, value: Option<&str>) -> OshiroResult<String> {
+    let guild_id = ctx.guild_id().expect("checked by check_admin");
+    let locale = ctx.locale().await;
+    let enabled = match value.map(|v| ConfigKind::Bool.parse(v)) {
+        Some(Ok(e)) => e,
+        Some(Err(e)) => return Ok(e.render(locale)),
+        None => return Ok(t!(locale, "bots-which-webhooks")),
+    };
+    db::set_module_setting(
+        &ctx.db().await,
+        guild_id,
+        "admin",
+        "allow_webhooks",
+        Some(&enabled),
+    )
+    .await?;
+    Ok(t!(locale, "bots-webhooks-set", enabled = enabled))
+}
+
+async fn bots_allow(
+    ctx: &CommandContext,
+    bot: Option<Id<UserMarker>>,
+    allowed: bool,
+) -> OshiroResult<String> {
+    let guild_id = ctx.guild_id().expect("checked by check_admin");
+    let locale = ctx.locale().await;
+    let bot = match bot {
+        Some(b) => b,
+        None => return Ok(t!(locale, "bots-which-bot")),
+    };
+    let mention = format!("<@{}>", bot);
+    let is_bot = ctx.oshiro.lock().await.cache.user(bot).map(|u| u.bot);
+    if is_bot == Some(false) {
+        return Ok(t!(locale, "bots-not-a-bot", bot = mention));
+    }
+    db::set_bot_allowed(&ctx.db().await, guild_id, bot, allowed).await?;
+    Ok(if allowed {
+        t!(locale, "bots-allowed", bot = mention)
+    } else {
+        t!(locale, "bots-removed", bot = mention)
+    })
+}
+
+async fn list(ctx: &CommandContext) -> OshiroResult<String> {
+    let guild_id = ctx.guild_id().expect("checked by check_admin");
+    let framework = ctx.framework().await;
+    let db = ctx.db().await;
+    let disabled_modules = db::disabled_modules(&db, guild_id).await?;
+    let disabled_commands = db::disabled_commands(&db, guild_id).await?;
+    let locale = ctx.locale().await;
+
+    let mut lines = vec![t!(locale, "modules-heading")];
+    for m in framework.modules() {
+        let state = if !m.can_disable() {
+            "always"
+        } else if disabled_modules.iter().any(|d| d == m.name()) {
+            "off"
+        } else {
+            "on"
+        };
+        let description = i18n::lookup(locale, &format!("module-{}", m.name()))
+            .unwrap_or_else(|| m.description().to_string());
+        lines.push(t!(
+            locale,
+            "modules-line",
+            name = m.name(),
+            state = state,
+            description = description
+        ));
+    }
+    if !disabled_commands.is_empty() {
+        let commands = disabled_commands
+            .iter()
+            .map(|c| format!("`{}`", c))
+            .collect::<Vec<_>>()
+            .join(", ");
+        lines.push(String::new());
+        lines.push(t!(locale, "modules-disabled-commands", commands = commands));
+    }
+    Ok(lines.join("\n"))
+}
+
+/// Turn a module, or failing that a single command, on or off
+async fn toggle(ctx: &CommandContext, name: Option<&str>, enabled: bool) -> OshiroResult<String> {
+    let guild_id = ctx.guild_id().expect("checked by check_admin");
+    let locale = ctx.locale().await;
+    let name = match name {
+        Some(n) => n.to_lowercase(),
+        None => return Ok(t!(locale, "modules-which")),
+    };
+    let framework = ctx.framework().await;
+    let db = ctx.db().await;
+    let state = enabled.to_string();
+
+    if let Some(module) = framework.module(&name) {
+        if !module.can_disable() {
+            return Ok(t!(locale, "modules-always-on", name = name));
+        }
+        db::set_module_enabled(&db, guild_id, module.name(), enabled).await?;
+        return Ok(t!(
+            locale,
+            "modules-module-toggled",
+            name = name,
+            enabled = state
+        ));
+    }
+
+    match framework.module_of(&name) {
+        Some(module) if !module.can_disable() => Ok(t!(
+            locale,
+            "modules-part-of-always-on",
+            name = name,
+            module = module.name()
+        )),
+        Some(_) => {
+            db::set_command_enabled(&db, guild_id, &name, enabled).await?;
+            Ok(t!(
+                locale,
+                "modules-command-toggled",
+                name = name,
+                enabled = state
+            ))
+        }
+        None => Ok(t!(locale, "modules-not-found", name = name)),
+    }
+}
+
+/// Show a module's settings, show one setting, or change one
+async fn configure(
+    ctx: &CommandContext,
+    module: Option<&str>,
+    key: Option<&str>,
+    value: Option<&str>,
+) -> OshiroResult<String> {
+    let guild_id = ctx.guild_id().expect("checked by check_admin");
+    let framework = ctx.framework().await;
+    let db = ctx.db().await;
+    let locale = ctx.locale().await;
+
+    let module = match module.and_then(|m| framework.module(&m.to_lowercase())) {
+        Some(m) => m,
+        None => return Ok(t!(locale, "config-which-module")),
+    };
+    let schema = module.config_schema();
+    if schema.is_empty() {
+        return Ok(t!(locale, "config-no-settings", module = module.name()));
+    }
+    let line = |field: &ConfigField, value: Option<&str>| {
+        let description = i18n::lookup(locale, &format!("setting-{}-{}", module.name(), field.key))
+            .unwrap_or_else(|| field.description.to_string());
+        t!(
+            locale,
+            "config-line",
+            key = field.key,
+            value = value.map_or(t!(locale, "config-not-set"), |v| field.kind.display(v)),
+            description = description
+        )
+    };
+
+    let key = match key {
+        Some(k) => k.to_lowercase(),
+        None => {
+            let stored = db::module_settings(&db, guild_id, module.name()).await?;
+            let mut lines = vec![t!(locale, "config-heading", module = module.name())];
+            lines.extend(schema.iter().map(|f| {
+                let current = stored
+                    .iter()
+                    .find(|(k, _)| k == f.key)
+                    .map(|(_, v)| v.as_str())
+                    .or(f.default);
+                line(f, current)
+            }));
+            return Ok(lines.join("\n"));
+        }
+    };
+
+    let field = match schema.iter().find(|f| f.key == key) {
+        Some(f) => f,
+        None => {
+            return Ok(t!(
+                locale,
+                "config-unknown-key",
+                module = module.name(),
+                key = key
+            ))
+        }
+    };
+
+    match value {
+        None => {
+            let current = module::setting(&db, guild_id, module.as_ref(), &key).await?;
+            Ok(line(field, current.as_deref()))
+        }
+        Some(v) if v.eq_ignore_ascii_case("reset") => {
+            db::set_module_setting(&db, guild_id, module.name(), &key, None).await?;
+            Ok(t!(locale, "config-reset", key = key))
+        }
+        Some(v) => match field.kind.parse(v) {
+            Ok(parsed) => {
+                if let Some(foreign) = foreign(ctx, guild_id, field.kind, &parsed).await {
+                    return Ok(t!(
+                        locale,
+                        "config-invalid",
+                        key = key,
+                        error = t!(locale, foreign, value = field.kind.display(&parsed))
+                    ));
+                }
+                db::set_module_setting(&db, guild_id, module.name(), &key, Some(&parsed)).await?;
+                Ok(t!(
+                    locale,
+                    "config-set",
+                    key = key,
+                    value = field.kind.display(&parsed)
+                ))
+            }
+            Err(e) => Ok(t!(
+                locale,
+                "config-invalid",
+                key = key,
+                error = e.render(locale)
+            )),
+        },
+    }
+}
+
+/// Channel and role settings only take ids, so make sure they're from this guild. Returns the
+/// message id to explain why not.
+async fn foreign(
+    ctx: &CommandContext,
+    guild_id: Id<GuildMarker>,
+    kind: ConfigKind,
+    value: &str,
+) -> Option<&'static str> {
+    let (http, cache) = {
+        let oshi = ctx.oshiro.lock().await;
+        (Arc::clone(&oshi.http), Arc::clone(&oshi.cache))
+    };
+    match kind {
+        ConfigKind::Channel => {
+            let channel = value.parse().ok()?;
+            if channel_in_guild(&http, &cache, channel, guild_id).await {
+                None
+            } else {
+                Some("config-foreign-channel")
+            }
+        }
+        ConfigKind::Role => {
+            let role = value.parse().ok()?;
+            if role_in_guild(&cache, role, guild_id) {
+                None
+            } else {
+                Some("config-foreign-role")
+            }
+        }
+        _ => None,
+    }
+}
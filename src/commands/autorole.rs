@@ -0,0 +1,429 @@
+use std::{collections::HashMap, sync::Arc};
+
+use tokio::sync::Mutex;
+use twilight_cache_inmemory::InMemoryCache;
+use twilight_http::{request::AuditLogReason, Client};
+use twilight_model::{
+    application::{
+        command::CommandType as SlashCommandType,
+        interaction::application_command::CommandOptionValue,
+    },
+    gateway::payload::incoming::{MemberAdd, MemberUpdate},
+    guild::Permissions,
+    id::{
+        marker::{GuildMarker, RoleMarker, UserMarker},
+        Id,
+    },
+};
+use twilight_util::builder::command::{
+    BooleanBuilder, CommandBuilder, RoleBuilder, SubCommandBuilder,
+};
+
+use crate::{
+    cmd::{CommandContext, CommandInstance, OshiroResult},
+    command,
+    commands::{
+        admin::check_admin,
+        moderation::{bot_id, highest_role, not_found},
+    },
+    ctx::OshiroContext,
+    db,
+    helper::parse_duration,
+    listener,
+    listener::Listener,
+    module::{self, ConfigField, ConfigKind, Module},
+    slash::{self, CommandGroup, CommandWrapper},
+    snapshot::Snapshot,
+    t,
+};
+
+/// Gives members roles as they join, with separate lists for humans and bots. Members going
+/// through membership screening get theirs once they've passed it.
+pub struct Autorole;
+
+impl Module for Autorole {
+    fn name(&self) -> &'static str {
+        "autorole"
+    }
+
+    fn description(&self) -> &'static str {
+        "give members roles when they join"
+    }
+
+    fn commands(&self) -> Vec<CommandInstance> {
+        vec![command!(
+            autorole_text,
+            "autorole",
+            "list, add or remove roles given on join"
+        )]
+    }
+
+    fn slash_commands(&self) -> HashMap<String, CommandWrapper> {
+        let role = || RoleBuilder::new("role", "The role").required(true);
+        let bots = || BooleanBuilder::new("bots", "For bots instead of humans");
+        let command = CommandBuilder::new(
+            "autorole",
+            "Roles given to members when they join",
+            SlashCommandType::ChatInput,
+        )
+        .dm_permission(false)
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .option(SubCommandBuilder::new(
+            "list",
+            "Show the roles given on join",
+        ))
+        .option(
+            SubCommandBuilder::new("add", "Give a role on join")
+                .option(role())
+                .option(bots()),
+        )
+        .option(
+            SubCommandBuilder::new("remove", "Stop giving a role on join")
+                .option(role())
+                .option(bots()),
+        )
+        .build();
+
+        let mut subcommands: HashMap<String, CommandGroup> = HashMap::new();
+        subcommands.insert(
+            "list".to_string(),
+            CommandGroup::Command(Box::new(move |ctx| Box::pin(autorole_list_slash(ctx)))),
+        );
+        subcommands.insert(
+            "add".to_string(),
+            CommandGroup::Command(Box::new(move |ctx| Box::pin(autorole_add_slash(ctx)))),
+        );
+        subcommands.insert(
+            "remove".to_string(),
+            CommandGroup::Command(Box::new(move |ctx| Box::pin(autorole_remove_slash(ctx)))),
+        );
+
+        let mut commands = HashMap::new();
+        commands.insert(
+            "autorole".to_string(),
+            CommandWrapper {
+                command,
+                function: None,
+                subcommands: Some(subcommands),
+            },
+        );
+        commands
+    }
+
+    fn listeners(&self) -> Vec<Listener> {
+        vec![
+            listener!(MemberAdd, "autorole_joined", member_joined),
+            listener!(MemberUpdate, "autorole_screened", member_screened, snapshot),
+        ]
+    }
+
+    fn config_schema(&self) -> Vec<ConfigField> {
+        vec![ConfigField {
+            key: "delay",
+            description: "wait this long after someone joins before giving the roles, like `10m`",
+            kind: ConfigKind::Duration,
+            default: None,
+        }]
+    }
+}
+
+/// Members that still have to pass membership screening get their roles from
+/// `member_screened` instead
+async fn member_joined(member: Box<MemberAdd>, ctx: Arc<Mutex<OshiroContext>>) -> OshiroResult {
+    if member.pending {
+        return Ok(());
+    }
+    assign(&ctx, member.guild_id, member.user.id, member.user.bot).await
+}
+
+/// Screening is over once `pending` goes from true to false. Without the member cached from
+/// before there's no telling whether this update is that change, so it's skipped rather than
+/// handing roles out again to someone who may have had them taken away.
+async fn member_screened(
+    member: Box<MemberUpdate>,
+    before: Arc<Snapshot>,
+    ctx: Arc<Mutex<OshiroContext>>,
+) -> OshiroResult {
+    let was_pending = before.member.as_ref().map_or(false, |m| m.pending());
+    if !was_pending || member.pending {
+        return Ok(());
+    }
+    assign(&ctx, member.guild_id, member.user.id, member.user.bot).await
+}
+
+async fn assign(
+    ctx: &Arc<Mutex<OshiroContext>>,
+    guild_id: Id<GuildMarker>,
+    user: Id<UserMarker>,
+    bot: bool,
+) -> OshiroResult {
+    let db = ctx.lock().await.db.clone();
+    let roles = db::autoroles(&db, guild_id, bot).await?;
+    if roles.is_empty() {
+        return Ok(());
+    }
+    let delay = module::setting(&db, guild_id, &Autorole, "delay")
+        .await?
+        .and_then(|d| parse_duration(&d));
+    if let Some(delay) = delay {
+        tokio::time::sleep(delay).await;
+    }
+
+    // the hierarchy can change during the delay, so it's checked just before
+    let (http, cache) = {
+        let oshi = ctx.lock().await;
+        (Arc::clone(&oshi.http), Arc::clone(&oshi.cache))
+    };
+    for role in roles {
+        if let Some(problem) = cant_grant(&http, &cache, guild_id, role).await? {
+            tracing::warn!("Skipping autorole {} in {}: {}", role, guild_id, problem);
+            continue;
+        }
+        let result = http
+            .add_guild_member_role(guild_id, user, role)
+            .reason("autorole")?
+            .await;
+        match result {
+            Ok(_) => {}
+            // they left in the meantime
+            Err(e) if not_found(&e) => return Ok(()),
+            Err(e) => tracing::warn!(
+                "Could not give autorole {} to {} in {}: {}",
+                role,
+                user,
+                guild_id,
+                e
+            ),
+        }
+    }
+    Ok(())
+}
+
+/// Why the bot can't give a role out, as a message id, or `None` if it can. The bot needs
+/// Manage Roles, and like with any member its highest role has to be above the role.
+pub async fn cant_grant(
+    http: &Client,
+    cache: &InMemoryCache,
+    guild_id: Id<GuildMarker>,
+    role: Id<RoleMarker>,
+) -> OshiroResult<Option<&'static str>> {
+    if role.get() == guild_id.get() {
+        return Ok(Some("autorole-everyone"));
+    }
+    let (position, managed) = match cache.role(role) {
+        Some(r) if r.guild_id() != guild_id => return Ok(Some("autorole-foreign-role")),
+        Some(r) => (r.resource().position, r.resource().managed),
+        None => return Ok(Some("autorole-unknown-role")),
+    };
+    if managed {
+        return Ok(Some("autorole-managed"));
+    }
+    let bot = bot_id(http, cache).await?;
+    let can_manage = cache
+        .permissions()
+        .root(bot, guild_id)
+        .map_or(false, |p| p.contains(Permissions::MANAGE_ROLES));
+    if !can_manage {
+        return Ok(Some("autorole-no-permission"));
+    }
+    match highest_role(http, cache, guild_id, bot).await? {
+        Some(highest) if highest > position => Ok(None),
+        _ => Ok(Some("autorole-too-high")),
+    }
+}
+
+/// Permissions a role can't carry to be handed out by the bot, since anyone who gets it
+/// could take the guild over
+const DANGEROUS: &[Permissions] = &[
+    Permissions::ADMINISTRATOR,
+    Permissions::MANAGE_GUILD,
+    Permissions::MANAGE_ROLES,
+    Permissions::BAN_MEMBERS,
+    Permissions::KICK_MEMBERS,
+];
+
+/// Why whoever ran the command may not have the bot hand out a role, as a message id, or
+/// `None` if they may. Setting up a role to be given out is as good as giving it, so like
+/// giving it by hand it takes Manage Roles and a highest role above it, unless they own the
+/// guild. Roles with `DANGEROUS` permissions are refused for everyone.
+pub async fn cant_hand_out(
+    ctx: &CommandContext,
+    guild_id: Id<GuildMarker>,
+    role: Id<RoleMarker>,
+) -> OshiroResult<Option<&'static str>> {
+    let (http, cache) = {
+        let oshi = ctx.oshiro.lock().await;
+        (Arc::clone(&oshi.http), Arc::clone(&oshi.cache))
+    };
+    let (position, permissions) = match cache.role(role) {
+        Some(r) if r.guild_id() != guild_id => return Ok(Some("autorole-foreign-role")),
+        Some(r) => (r.resource().position, r.resource().permissions),
+        None => return Ok(Some("autorole-unknown-role")),
+    };
+    if DANGEROUS.iter().any(|p| permissions.contains(*p)) {
+        return Ok(Some("autorole-dangerous"));
+    }
+    let invoker = match ctx.author_id() {
+        Some(u) => u,
+        None => return Ok(Some("autorole-not-allowed")),
+    };
+    let cached_owner = cache.guild(guild_id).map(|g| g.owner_id());
+    let owner = match cached_owner {
+        Some(o) => o,
+        None => http.guild(guild_id).await?.model().await?.owner_id,
+    };
+    if owner == invoker {
+        return Ok(None);
+    }
+    if !ctx.has_permission(Permissions::MANAGE_ROLES).await {
+        return Ok(Some("autorole-not-allowed"));
+    }
+    match highest_role(&http, &cache, guild_id, invoker).await? {
+        Some(highest) if highest > position => Ok(None),
+        _ => Ok(Some("autorole-above-you")),
+    }
+}
+
+async fn autorole_text(ctx: CommandContext) -> OshiroResult {
+    if !check_admin(&ctx).await? {
+        return Ok(());
+    }
+    let stripped = ctx.stripped.clone().unwrap_or_default();
+    let args: Vec<&str> = stripped.split_whitespace().collect();
+    let role = args
+        .get(1)
+        .and_then(|r| ConfigKind::Role.parse(r).ok())
+        .and_then(|r| r.parse().ok());
+    let bots = args
+        .get(2)
+        .map_or(false, |a| a.eq_ignore_ascii_case("bots"));
+    let out = match args.first().copied() {
+        None | Some("list") => autorole_list(&ctx).await?,
+        Some("add") => autorole_set(&ctx, role, bots, true).await?,
+        Some("remove") => autorole_set(&ctx, role, bots, false).await?,
+        Some(other) => t!(
+            ctx.locale().await,
+            "autorole-unknown-subcommand",
+            name = other
+        ),
+    };
+    ctx.reply(&out).await
+}
+
+async fn autorole_list_slash(ctx: CommandContext) -> OshiroResult {
+    if !check_admin(&ctx).await? {
+        return Ok(());
+    }
+    let out = autorole_list(&ctx).await?;
+    ctx.reply_ephemeral(&out).await
+}
+
+async fn autorole_add_slash(ctx: CommandContext) -> OshiroResult {
+    if !check_admin(&ctx).await? {
+        return Ok(());
+    }
+    let (role, bots) = slash_args(&ctx);
+    let out = autorole_set(&ctx, role, bots, true).await?;
+    ctx.reply_ephemeral(&out).await
+}
+
+async fn autorole_remove_slash(ctx: CommandContext) -> OshiroResult {
+    if !check_admin(&ctx).await? {
+        return Ok(());
+    }
+    let (role, bots) = slash_args(&ctx);
+    let out = autorole_set(&ctx, role, bots, false).await?;
+    ctx.reply_ephemeral(&out).await
+}
+
+fn slash_args(ctx: &CommandContext) -> (Option<Id<RoleMarker>>, bool) {
+    let slash = match &ctx.slash {
+        Some(s) => s,
+        None => return (None, false),
+    };
+    let role = match slash::option(slash, "role") {
+        Some(CommandOptionValue::Role(id)) => Some(id),
+        _ => None,
+    };
+    let bots = matches!(
+        slash::option(slash, "bots"),
+        Some(CommandOptionValue::Boolean(true))
+    );
+    (role, bots)
+}
+
+/// Both lists, with a line for every role that can't be given out right now
+async fn autorole_list(ctx: &CommandContext) -> OshiroResult<String> {
+    let guild_id = ctx.guild_id().expect("checked by check_admin");
+    let locale = ctx.locale().await;
+    let (db, http, cache) = {
+        let oshi = ctx.oshiro.lock().await;
+        (
+            oshi.db.clone(),
+            Arc::clone(&oshi.http),
+            Arc::clone(&oshi.cache),
+        )
+    };
+    let humans = db::autoroles(&db, guild_id, false).await?;
+    let bots = db::autoroles(&db, guild_id, true).await?;
+    if humans.is_empty() && bots.is_empty() {
+        return Ok(t!(locale, "autorole-none"));
+    }
+    let mentions = |roles: &[Id<RoleMarker>]| {
+        if roles.is_empty() {
+            t!(locale, "autorole-list-empty")
+        } else {
+            let mentions: Vec<String> = roles.iter().map(|r| format!("<@&{}>", r)).collect();
+            mentions.join(", ")
+        }
+    };
+    let mut lines = vec![
+        t!(locale, "autorole-list-humans", roles = mentions(&humans)),
+        t!(locale, "autorole-list-bots", roles = mentions(&bots)),
+    ];
+    for role in humans.iter().chain(&bots) {
+        if let Some(problem) = cant_grant(&http, &cache, guild_id, *role).await? {
+            lines.push(t!(locale, problem, role = format!("<@&{}>", role)));
+        }
+    }
+    Ok(lines.join("\n"))
+}
+
+/// Adding a role checks that the invoker may hand it out and the bot can actually give it
+async fn autorole_set(
+    ctx: &CommandContext,
+    role: Option<Id<RoleMarker>>,
+    bots: bool,
+    enabled: bool,
+) -> OshiroResult<String> {
+    let guild_id = ctx.guild_id().expect("checked by check_admin");
+    let locale = ctx.locale().await;
+    let role = match role {
+        Some(r) => r,
+        None => return Ok(t!(locale, "autorole-which")),
+    };
+    let (db, http, cache) = {
+        let oshi = ctx.oshiro.lock().await;
+        (
+            oshi.db.clone(),
+            Arc::clone(&oshi.http),
+            Arc::clone(&oshi.cache),
+        )
+    };
+    let mention = format!("<@&{}>", role);
+    if enabled {
+        if let Some(problem) = cant_hand_out(ctx, guild_id, role).await? {
+            return Ok(t!(locale, problem, role = mention));
+        }
+        if let Some(problem) = cant_grant(&http, &cache, guild_id, role).await? {
+            return Ok(t!(locale, problem, role = mention));
+        }
+    }
+    db::set_autorole(&db, guild_id, role, bots, enabled).await?;
+    let bots = if bots { "true" } else { "false" };
+    Ok(if enabled {
+        t!(locale, "autorole-added", role = mention, bots = bots)
+    } else {
+        t!(locale, "autorole-removed", role = mention, bots = bots)
+    })
+}
@@ -0,0 +1,480 @@
+use std::{cmp::Reverse, collections::HashMap, sync::Arc};
+
+use twilight_cache_inmemory::InMemoryCache;
+use twilight_http::Client;
+use twilight_model::{
+    application::{
+        command::CommandType as SlashCommandType,
+        interaction::{application_command::CommandOptionValue, InteractionData},
+    },
+    channel::message::Embed,
+    guild::Permissions,
+    id::{
+        marker::{GuildMarker, RoleMarker, UserMarker},
+        Id,
+    },
+    user::User,
+    util::{ImageHash, Timestamp},
+};
+use twilight_util::{
+    builder::{
+        command::{CommandBuilder, IntegerBuilder, UserBuilder},
+        embed::{EmbedBuilder, EmbedFieldBuilder, EmbedFooterBuilder, ImageSource},
+    },
+    snowflake::Snowflake,
+};
+
+use crate::{
+    cmd::{CommandContext, CommandInstance, OshiroResult},
+    command,
+    commands::{logging::permission_names, moderation::not_found},
+    helper::{
+        cdn_default_avatar, cdn_size, hash_to_cdn_member_avatar, hash_to_cdn_user_asset,
+        parse_user, truncate, valid_cdn_size, UserAssetType,
+    },
+    module::Module,
+    slash::{self, CommandWrapper},
+    t,
+};
+
+/// Image size asked from the cdn when none is given
+const DEFAULT_SIZE: u16 = 1024;
+
+/// Sizes offered by the slash commands, the cdn takes any power of two from 16 to 4096
+const SIZES: &[i64] = &[64, 128, 256, 512, 1024, 2048, 4096];
+
+/// Embed field values are capped at this many characters
+const FIELD_MAX: usize = 1024;
+
+/// The permissions `userinfo` calls out, roughly the ones that make someone a moderator
+const KEY_PERMISSIONS: &[Permissions] = &[
+    Permissions::ADMINISTRATOR,
+    Permissions::MANAGE_GUILD,
+    Permissions::MANAGE_ROLES,
+    Permissions::MANAGE_CHANNELS,
+    Permissions::MANAGE_MESSAGES,
+    Permissions::MANAGE_WEBHOOKS,
+    Permissions::MANAGE_NICKNAMES,
+    Permissions::KICK_MEMBERS,
+    Permissions::BAN_MEMBERS,
+    Permissions::MODERATE_MEMBERS,
+    Permissions::MENTION_EVERYONE,
+    Permissions::VIEW_AUDIT_LOG,
+];
+
+/// Looks people up: account and membership details, and their avatars
+pub struct Info;
+
+impl Module for Info {
+    fn name(&self) -> &'static str {
+        "info"
+    }
+
+    fn description(&self) -> &'static str {
+        "user info and avatars"
+    }
+
+    fn commands(&self) -> Vec<CommandInstance> {
+        vec![
+            command!(userinfo, "userinfo", "show info about a user, or yourself"),
+            command!(
+                avatar,
+                "avatar",
+                "show a user's server and global avatars, or yours"
+            ),
+        ]
+    }
+
+    fn slash_commands(&self) -> HashMap<String, CommandWrapper> {
+        let user = || UserBuilder::new("user", "Yourself if left out");
+        let size = || {
+            IntegerBuilder::new("size", "Image size in pixels")
+                .choices(SIZES.iter().map(|s| (s.to_string(), *s)))
+        };
+        let userinfo_command = CommandBuilder::new(
+            "userinfo",
+            "Show info about a user",
+            SlashCommandType::ChatInput,
+        )
+        .option(user())
+        .option(size())
+        .build();
+        // context menu commands have no description and are named like a button
+        let context_command = CommandBuilder::new("User info", "", SlashCommandType::User).build();
+        let avatar_command = CommandBuilder::new(
+            "avatar",
+            "Show a user's server and global avatars",
+            SlashCommandType::ChatInput,
+        )
+        .option(user())
+        .option(size())
+        .build();
+
+        let mut commands = HashMap::new();
+        commands.insert(
+            "userinfo".to_string(),
+            CommandWrapper {
+                command: userinfo_command,
+                function: Some(Box::new(move |ctx| Box::pin(userinfo(ctx)))),
+                subcommands: None,
+            },
+        );
+        commands.insert(
+            "User info".to_string(),
+            CommandWrapper {
+                command: context_command,
+                function: Some(Box::new(move |ctx| Box::pin(userinfo(ctx)))),
+                subcommands: None,
+            },
+        );
+        commands.insert(
+            "avatar".to_string(),
+            CommandWrapper {
+                command: avatar_command,
+                function: Some(Box::new(move |ctx| Box::pin(avatar(ctx)))),
+                subcommands: None,
+            },
+        );
+        commands
+    }
+}
+
+/// The parts of a member `userinfo` and `avatar` need, whether it came from the cache or
+/// over http
+struct MemberInfo {
+    nick: Option<String>,
+    avatar: Option<ImageHash>,
+    joined_at: Timestamp,
+    premium_since: Option<Timestamp>,
+    roles: Vec<Id<RoleMarker>>,
+}
+
+/// Who to look up and how big the images should be
+struct Args {
+    user: Option<Id<UserMarker>>,
+    size: u16,
+}
+
+/// For the user context menu the user is the one right-clicked, otherwise it's the `user`
+/// option or the first mention or id, and then whoever ran the command. Text commands take
+/// a size among their words, like `avatar @someone 4096`.
+fn args(ctx: &CommandContext) -> Args {
+    let slash = match &ctx.slash {
+        Some(s) => s,
+        None => {
+            let stripped = ctx.stripped.clone().unwrap_or_default();
+            let mut user = None;
+            let mut size = DEFAULT_SIZE;
+            for word in stripped.split_whitespace() {
+                match word.parse::<u64>() {
+                    Ok(s) if valid_cdn_size(s) => size = s as u16,
+                    _ => user = user.or(Some(parse_user(word))),
+                }
+            }
+            return Args {
+                // a word that isn't a user means nobody, rather than the author
+                user: user.unwrap_or_else(|| ctx.author_id()),
+                size,
+            };
+        }
+    };
+    let target = match &slash.data {
+        Some(InteractionData::ApplicationCommand(data)) => data.target_id.map(|t| t.cast()),
+        _ => None,
+    };
+    let user = target.or(match slash::option(slash, "user") {
+        Some(CommandOptionValue::User(id)) => Some(id),
+        _ => None,
+    });
+    let size = match slash::option(slash, "size") {
+        Some(CommandOptionValue::Integer(s)) if valid_cdn_size(s as u64) => s as u16,
+        _ => DEFAULT_SIZE,
+    };
+    Args {
+        user: user.or_else(|| ctx.author_id()),
+        size,
+    }
+}
+
+/// The full user over http, since only that carries the banner and accent color
+async fn fetch_user(http: &Client, user: Id<UserMarker>) -> OshiroResult<Option<User>> {
+    match http.user(user).await {
+        Ok(r) => Ok(Some(r.model().await?)),
+        Err(e) if not_found(&e) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// The member from the cache, or over http if it isn't there. `None` if they aren't in
+/// the guild.
+async fn fetch_member(
+    http: &Client,
+    cache: &InMemoryCache,
+    guild_id: Id<GuildMarker>,
+    user: Id<UserMarker>,
+) -> OshiroResult<Option<MemberInfo>> {
+    if let Some(m) = cache.member(guild_id, user) {
+        return Ok(Some(MemberInfo {
+            nick: m.nick().map(str::to_string),
+            avatar: m.avatar(),
+            joined_at: m.joined_at(),
+            premium_since: m.premium_since(),
+            roles: m.roles().to_vec(),
+        }));
+    }
+    match http.guild_member(guild_id, user).await {
+        Ok(r) => {
+            let m = r.model().await?;
+            Ok(Some(MemberInfo {
+                nick: m.nick,
+                avatar: m.avatar,
+                joined_at: m.joined_at,
+                premium_since: m.premium_since,
+                roles: m.roles,
+            }))
+        }
+        Err(e) if not_found(&e) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Looks up the user, and the member too when used in a guild
+async fn lookup(
+    ctx: &CommandContext,
+    args: &Args,
+) -> OshiroResult<Option<(User, Option<MemberInfo>)>> {
+    let user = match args.user {
+        Some(u) => u,
+        None => return Ok(None),
+    };
+    let (http, cache) = {
+        let oshi = ctx.oshiro.lock().await;
+        (Arc::clone(&oshi.http), Arc::clone(&oshi.cache))
+    };
+    let user = match fetch_user(&http, user).await? {
+        Some(u) => u,
+        None => return Ok(None),
+    };
+    let member = match ctx.guild_id() {
+        Some(guild_id) => fetch_member(&http, &cache, guild_id, user.id).await?,
+        None => None,
+    };
+    Ok(Some((user, member)))
+}
+
+async fn userinfo(ctx: CommandContext) -> OshiroResult {
+    let locale = ctx.locale().await;
+    let args = args(&ctx);
+    let (user, member) = match lookup(&ctx, &args).await? {
+        Some(found) => found,
+        None => return ctx.reply_ephemeral(&t!(locale, "info-no-user")).await,
+    };
+    let cache = Arc::clone(&ctx.oshiro.lock().await.cache);
+
+    let username = if user.discriminator == 0 {
+        user.name.clone()
+    } else {
+        format!("{}#{:04}", user.name, user.discriminator)
+    };
+    let display_name = member
+        .as_ref()
+        .and_then(|m| m.nick.clone())
+        .or_else(|| user.global_name.clone())
+        .unwrap_or_else(|| user.name.clone());
+    let created = user.id.timestamp() / 1000;
+
+    let mut embed = EmbedBuilder::new()
+        .title(display_name.as_str())
+        .description(format!("<@{}>", user.id))
+        .thumbnail(ImageSource::url(avatar_url(
+            &user,
+            member.as_ref(),
+            ctx.guild_id(),
+            args.size,
+        ))?)
+        .field(EmbedFieldBuilder::new(t!(locale, "info-username"), username).inline())
+        .field(EmbedFieldBuilder::new(t!(locale, "info-display-name"), display_name).inline())
+        .field(EmbedFieldBuilder::new(t!(locale, "info-id"), user.id.to_string()).inline())
+        .field(
+            EmbedFieldBuilder::new(
+                t!(locale, "info-created"),
+                format!("<t:{0}:F> (<t:{0}:R>)", created),
+            )
+            .inline(),
+        );
+    if let Some(color) = user.accent_color {
+        embed = embed.color(color);
+    }
+    if user.bot {
+        embed = embed.footer(EmbedFooterBuilder::new(t!(locale, "info-bot")));
+    }
+
+    match (&member, ctx.guild_id()) {
+        (Some(member), Some(guild_id)) => {
+            embed = embed.field(
+                EmbedFieldBuilder::new(
+                    t!(locale, "info-joined"),
+                    format!("<t:{0}:F> (<t:{0}:R>)", member.joined_at.as_secs()),
+                )
+                .inline(),
+            );
+            let boosting = match member.premium_since {
+                Some(since) => t!(
+                    locale,
+                    "info-boosting-since",
+                    since = format!("<t:{}:R>", since.as_secs())
+                ),
+                None => t!(locale, "info-not-boosting"),
+            };
+            embed =
+                embed.field(EmbedFieldBuilder::new(t!(locale, "info-boosting"), boosting).inline());
+
+            let roles = sorted_roles(&cache, &member.roles);
+            let role_list = if roles.is_empty() {
+                t!(locale, "info-no-roles")
+            } else {
+                let mentions: Vec<String> = roles.iter().map(|r| format!("<@&{}>", r)).collect();
+                truncate(&mentions.join(" "), FIELD_MAX)
+            };
+            embed = embed.field(EmbedFieldBuilder::new(
+                t!(locale, "info-roles", count = roles.len()),
+                role_list,
+            ));
+
+            let permissions = key_permissions(&cache, guild_id, user.id, &member.roles);
+            let permissions = if permissions.is_empty() {
+                t!(locale, "info-no-key-permissions")
+            } else {
+                permission_names(locale, permissions)
+            };
+            embed = embed.field(EmbedFieldBuilder::new(
+                t!(locale, "info-permissions"),
+                permissions,
+            ));
+        }
+        (None, Some(_)) => {
+            embed = embed.field(EmbedFieldBuilder::new(
+                t!(locale, "info-joined"),
+                t!(locale, "info-not-member"),
+            ));
+        }
+        _ => {}
+    }
+
+    if let Some(banner) = user.banner {
+        let url = hash_to_cdn_user_asset(
+            UserAssetType::Banner,
+            &user.id.to_string(),
+            &banner.to_string(),
+        );
+        embed = embed.image(ImageSource::url(cdn_size(&url, args.size))?);
+    }
+
+    ctx.reply_embeds(vec![embed.validate()?.build()]).await
+}
+
+async fn avatar(ctx: CommandContext) -> OshiroResult {
+    let locale = ctx.locale().await;
+    let args = args(&ctx);
+    let (user, member) = match lookup(&ctx, &args).await? {
+        Some(found) => found,
+        None => return ctx.reply_ephemeral(&t!(locale, "info-no-user")).await,
+    };
+
+    let mut embeds = Vec::new();
+    // the server one first, it's what others see in this guild
+    if let (Some(hash), Some(guild_id)) = (member.as_ref().and_then(|m| m.avatar), ctx.guild_id()) {
+        let url = hash_to_cdn_member_avatar(
+            &guild_id.to_string(),
+            &user.id.to_string(),
+            &hash.to_string(),
+        );
+        embeds.push(avatar_embed(
+            t!(locale, "info-server-avatar", user = user.name.as_str()),
+            cdn_size(&url, args.size),
+        )?);
+    }
+    embeds.push(avatar_embed(
+        t!(locale, "info-global-avatar", user = user.name.as_str()),
+        avatar_url(&user, None, None, args.size),
+    )?);
+    ctx.reply_embeds(embeds).await
+}
+
+/// An embed with the avatar as its image, the title linking to the full size file
+fn avatar_embed(title: String, url: String) -> OshiroResult<Embed> {
+    Ok(EmbedBuilder::new()
+        .title(title)
+        .url(url.as_str())
+        .image(ImageSource::url(url)?)
+        .validate()?
+        .build())
+}
+
+/// The avatar others see: the server one if the member has it, then their own, then the
+/// default one
+fn avatar_url(
+    user: &User,
+    member: Option<&MemberInfo>,
+    guild_id: Option<Id<GuildMarker>>,
+    size: u16,
+) -> String {
+    if let (Some(hash), Some(guild_id)) = (member.and_then(|m| m.avatar), guild_id) {
+        let url = hash_to_cdn_member_avatar(
+            &guild_id.to_string(),
+            &user.id.to_string(),
+            &hash.to_string(),
+        );
+        return cdn_size(&url, size);
+    }
+    match user.avatar {
+        Some(hash) => cdn_size(
+            &hash_to_cdn_user_asset(
+                UserAssetType::Avatar,
+                &user.id.to_string(),
+                &hash.to_string(),
+            ),
+            size,
+        ),
+        // the default avatars only come in one size
+        None => cdn_default_avatar(user.id.get(), user.discriminator),
+    }
+}
+
+/// Highest first, the way Discord lists them. Roles missing from the cache go last.
+fn sorted_roles(cache: &InMemoryCache, roles: &[Id<RoleMarker>]) -> Vec<Id<RoleMarker>> {
+    let mut roles: Vec<(Option<i64>, Id<RoleMarker>)> = roles
+        .iter()
+        .map(|r| (cache.role(*r).map(|c| c.resource().position), *r))
+        .collect();
+    roles.sort_by_key(|(position, id)| (Reverse(*position), *id));
+    roles.into_iter().map(|(_, id)| id).collect()
+}
+
+/// The `KEY_PERMISSIONS` the member has guild-wide. Administrator implies the rest, so it's
+/// shown alone, and the owner counts as one.
+fn key_permissions(
+    cache: &InMemoryCache,
+    guild_id: Id<GuildMarker>,
+    user: Id<UserMarker>,
+    roles: &[Id<RoleMarker>],
+) -> Permissions {
+    let owner = cache
+        .guild(guild_id)
+        .map_or(false, |g| g.owner_id() == user);
+    // @everyone has the guild's id
+    let granted = roles
+        .iter()
+        .copied()
+        .chain(std::iter::once(guild_id.cast()))
+        .filter_map(|r| cache.role(r).map(|c| c.resource().permissions))
+        .fold(Permissions::empty(), |all, p| all | p);
+    if owner || granted.contains(Permissions::ADMINISTRATOR) {
+        return Permissions::ADMINISTRATOR;
+    }
+    KEY_PERMISSIONS
+        .iter()
+        .copied()
+        .filter(|p| granted.contains(*p))
+        .fold(Permissions::empty(), |all, p| all | p)
+}
@@ -0,0 +1,1416 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use once_cell::sync::Lazy;
+use tokio::sync::Mutex;
+use twilight_cache_inmemory::{model::CachedMessage, InMemoryCache};
+use twilight_model::{
+    application::{
+        command::CommandType as SlashCommandType,
+        interaction::application_command::CommandOptionValue,
+    },
+    channel::{
+        message::Embed,
+        permission_overwrite::{PermissionOverwrite, PermissionOverwriteType},
+        Channel,
+    },
+    gateway::payload::incoming::{
+        ChannelCreate, ChannelDelete, ChannelUpdate, GuildDelete, MemberAdd, MemberRemove,
+        MemberUpdate, MessageDelete, MessageDeleteBulk, MessageUpdate, RoleCreate, RoleDelete,
+        RoleUpdate, ThreadCreate, ThreadDelete, ThreadUpdate,
+    },
+    guild::{Permissions, Role},
+    http::attachment::Attachment,
+    id::{
+        marker::{
+            ChannelMarker, GenericMarker, GuildMarker, MessageMarker, RoleMarker, UserMarker,
+        },
+        Id,
+    },
+    user::User,
+    util::{ImageHash, Timestamp},
+};
+use twilight_util::{
+    builder::{
+        command::{ChannelBuilder, CommandBuilder, SubCommandBuilder},
+        embed::{EmbedBuilder, EmbedFieldBuilder, EmbedFooterBuilder, ImageSource},
+    },
+    snowflake::Snowflake,
+};
+
+use crate::{
+    cmd::{CommandContext, CommandInstance, OshiroResult},
+    command,
+    commands::admin::check_admin,
+    ctx::OshiroContext,
+    db,
+    helper::{channel_in_guild, format_duration, hash_to_cdn_user_asset, truncate, UserAssetType},
+    i18n, listener,
+    listener::Listener,
+    module::{self, ConfigField, ConfigKind, Module},
+    slash::{self, CommandGroup, CommandWrapper},
+    snapshot::Snapshot,
+    t,
+};
+
+/// Longest embed description
+const DESCRIPTION_MAX: usize = 4096;
+/// Longest embed field value
+const FIELD_MAX: usize = 1024;
+
+/// How long a profile change is kept for the other guilds' member updates about it, which
+/// come in right after the first
+const CHANGE_KEPT: Duration = Duration::from_secs(60);
+
+/// Usernames and avatars as each guild with a member log last saw them. Users are cached once
+/// for every guild, so by the time the second guild's member update for a change comes in the
+/// cache already has the new name. Members are forgotten when they leave or the bot does.
+static PROFILES: Lazy<std::sync::Mutex<Profiles>> = Lazy::new(Default::default);
+
+#[derive(Default)]
+struct Profiles {
+    seen: HashMap<(Id<GuildMarker>, Id<UserMarker>), Profile>,
+    /// Each user's latest change, from and to, for guilds that hadn't seen them before it.
+    /// Dropped after `CHANGE_KEPT`.
+    changed: HashMap<Id<UserMarker>, (Profile, Profile, Instant)>,
+}
+
+#[derive(Clone, PartialEq)]
+struct Profile {
+    name: String,
+    avatar: Option<ImageHash>,
+}
+
+impl Profile {
+    fn of(user: &User) -> Self {
+        Profile {
+            name: user.name.clone(),
+            avatar: user.avatar,
+        }
+    }
+}
+
+/// Posts edited and deleted messages to a log channel, using what the message cache had
+/// before the change. Bulk deletions are attached as a text file.
+///
+/// Joins, leaves and member changes go to a separate channel, and so do channel, thread and
+/// role changes, diffed against the cached version. Discord only sends `USER_UPDATE`
+/// for the bot's own account, other users' username and avatar changes come as member updates.
+pub struct Logging;
+
+impl Module for Logging {
+    fn name(&self) -> &'static str {
+        "logging"
+    }
+
+    fn description(&self) -> &'static str {
+        "log edited and deleted messages, member changes, and channel, thread and role changes"
+    }
+
+    fn commands(&self) -> Vec<CommandInstance> {
+        vec![command!(
+            logignore_text,
+            "logignore",
+            "list, add or remove channels left out of the logs"
+        )]
+    }
+
+    fn slash_commands(&self) -> HashMap<String, CommandWrapper> {
+        let channel = || ChannelBuilder::new("channel", "The channel").required(true);
+        let command = CommandBuilder::new(
+            "logignore",
+            "Channels left out of the logs",
+            SlashCommandType::ChatInput,
+        )
+        .dm_permission(false)
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .option(SubCommandBuilder::new("list", "Show the ignored channels"))
+        .option(SubCommandBuilder::new("add", "Stop logging a channel").option(channel()))
+        .option(SubCommandBuilder::new("remove", "Log a channel again").option(channel()))
+        .build();
+
+        let mut subcommands: HashMap<String, CommandGroup> = HashMap::new();
+        subcommands.insert(
+            "list".to_string(),
+            CommandGroup::Command(Box::new(move |ctx| Box::pin(logignore_list_slash(ctx)))),
+        );
+        subcommands.insert(
+            "add".to_string(),
+            CommandGroup::Command(Box::new(move |ctx| Box::pin(logignore_add_slash(ctx)))),
+        );
+        subcommands.insert(
+            "remove".to_string(),
+            CommandGroup::Command(Box::new(move |ctx| Box::pin(logignore_remove_slash(ctx)))),
+        );
+
+        let mut commands = HashMap::new();
+        commands.insert(
+            "logignore".to_string(),
+            CommandWrapper {
+                command,
+                function: None,
+                subcommands: Some(subcommands),
+            },
+        );
+        commands
+    }
+
+    fn listeners(&self) -> Vec<Listener> {
+        vec![
+            listener!(MessageDelete, "message_deleted", message_deleted, snapshot),
+            listener!(
+                MessageDeleteBulk,
+                "messages_deleted",
+                messages_deleted,
+                snapshot
+            ),
+            listener!(MessageUpdate, "message_edited", message_edited, snapshot),
+            listener!(MemberAdd, "member_joined", member_joined),
+            listener!(MemberRemove, "member_left", member_left, snapshot),
+            listener!(MemberUpdate, "member_updated", member_updated, snapshot),
+            listener!(GuildDelete, "guild_left", guild_left),
+            listener!(ChannelCreate, "channel_created", channel_created),
+            listener!(ChannelDelete, "channel_deleted", channel_deleted),
+            listener!(ChannelUpdate, "channel_updated", channel_updated, snapshot),
+            listener!(ThreadCreate, "thread_created", thread_created),
+            listener!(ThreadDelete, "thread_deleted", thread_deleted, snapshot),
+            listener!(ThreadUpdate, "thread_updated", thread_updated, snapshot),
+            listener!(RoleCreate, "role_created", role_created),
+            listener!(RoleDelete, "role_deleted", role_deleted, snapshot),
+            listener!(RoleUpdate, "role_updated", role_updated, snapshot),
+        ]
+    }
+
+    fn config_schema(&self) -> Vec<ConfigField> {
+        vec![
+            ConfigField {
+                key: "message_log",
+                description: "channel edited and deleted messages are posted to",
+                kind: ConfigKind::Channel,
+                default: None,
+            },
+            ConfigField {
+                key: "member_log",
+                description:
+                    "channel joins, leaves, and nickname, role and profile changes are posted to",
+                kind: ConfigKind::Channel,
+                default: None,
+            },
+            ConfigField {
+                key: "server_log",
+                description:
+                    "channel created, deleted and changed channels, threads and roles are posted to",
+                kind: ConfigKind::Channel,
+                default: None,
+            },
+            ConfigField {
+                key: "log_bots",
+                description: "log messages from bots and webhooks too",
+                kind: ConfigKind::Bool,
+                default: Some("false"),
+            },
+        ]
+    }
+}
+
+async fn message_deleted(
+    event: MessageDelete,
+    before: Arc<Snapshot>,
+    ctx: Arc<Mutex<OshiroContext>>,
+) -> OshiroResult {
+    let guild_id = match event.guild_id {
+        Some(g) => g,
+        None => return Ok(()),
+    };
+    let (log, locale) = match message_log(&ctx, guild_id, event.channel_id).await? {
+        Some(l) => l,
+        None => return Ok(()),
+    };
+    let cache = Arc::clone(&ctx.lock().await.cache);
+    let message = before.message(event.id);
+    if let Some(m) = message {
+        if !log_author(&ctx, &cache, guild_id, m).await? {
+            return Ok(());
+        }
+    }
+
+    let mut embed = EmbedBuilder::new()
+        .title(t!(locale, "log-deleted-title"))
+        .color(0xe74c3c)
+        .description(match message {
+            Some(m) if !m.content().is_empty() => truncate(m.content(), DESCRIPTION_MAX),
+            Some(_) => t!(locale, "log-no-content"),
+            None => t!(locale, "log-not-cached"),
+        })
+        .field(
+            EmbedFieldBuilder::new(
+                t!(locale, "log-channel"),
+                format!("<#{}>", event.channel_id),
+            )
+            .inline(),
+        );
+    if let Some(m) = message {
+        embed = embed.field(
+            EmbedFieldBuilder::new(t!(locale, "log-author"), author(&cache, m.author())).inline(),
+        );
+        if !m.attachments().is_empty() {
+            let names: Vec<&str> = m
+                .attachments()
+                .iter()
+                .map(|a| a.filename.as_str())
+                .collect();
+            embed = embed.field(EmbedFieldBuilder::new(
+                t!(locale, "log-attachments"),
+                truncate(&names.join("\n"), FIELD_MAX),
+            ));
+        }
+    }
+    let embed = finish(locale, embed, event.id)?;
+    post(&ctx, guild_id, log, embed, None).await
+}
+
+async fn messages_deleted(
+    event: MessageDeleteBulk,
+    before: Arc<Snapshot>,
+    ctx: Arc<Mutex<OshiroContext>>,
+) -> OshiroResult {
+    let guild_id = match event.guild_id {
+        Some(g) => g,
+        None => return Ok(()),
+    };
+    let (log, locale) = match message_log(&ctx, guild_id, event.channel_id).await? {
+        Some(l) => l,
+        None => return Ok(()),
+    };
+    let cache = Arc::clone(&ctx.lock().await.cache);
+
+    let mut ids = event.ids.clone();
+    ids.sort();
+    let mut lines = Vec::with_capacity(ids.len());
+    for id in &ids {
+        lines.push(match before.message(*id) {
+            Some(m) => {
+                let name = cache
+                    .user(m.author())
+                    .map_or_else(|| m.author().to_string(), |u| u.name.clone());
+                let mut line = format!(
+                    "[{}] {} ({}): {}",
+                    m.timestamp().iso_8601(),
+                    name,
+                    m.author(),
+                    m.content()
+                );
+                for a in m.attachments() {
+                    line.push_str(&format!("\n    {}", a.url));
+                }
+                line
+            }
+            None => format!("[{}] {}", id, t!(locale, "log-not-cached")),
+        });
+    }
+
+    let embed = EmbedBuilder::new()
+        .title(t!(locale, "log-bulk-title"))
+        .color(0xe74c3c)
+        .description(t!(
+            locale,
+            "log-bulk-description",
+            count = ids.len(),
+            cached = before.messages.len(),
+            channel = format!("<#{}>", event.channel_id)
+        ))
+        .timestamp(Timestamp::from_secs(chrono::Utc::now().timestamp())?)
+        .validate()?
+        .build();
+    let file = Attachment::from_bytes(
+        format!("deleted-{}.txt", event.channel_id),
+        lines.join("\n").into_bytes(),
+        0,
+    );
+    post(&ctx, guild_id, log, embed, Some(file)).await
+}
+
+async fn message_edited(
+    event: Box<MessageUpdate>,
+    before: Arc<Snapshot>,
+    ctx: Arc<Mutex<OshiroContext>>,
+) -> OshiroResult {
+    let guild_id = match event.guild_id {
+        Some(g) => g,
+        None => return Ok(()),
+    };
+    // embeds being filled in for links also count as updates, only content changes matter
+    let after = match &event.content {
+        Some(c) => c,
+        None => return Ok(()),
+    };
+    let message = before.message(event.id);
+    if message.map_or(false, |m| m.content() == after) {
+        return Ok(());
+    }
+    let (log, locale) = match message_log(&ctx, guild_id, event.channel_id).await? {
+        Some(l) => l,
+        None => return Ok(()),
+    };
+    let cache = Arc::clone(&ctx.lock().await.cache);
+    if let Some(m) = message {
+        if !log_author(&ctx, &cache, guild_id, m).await? {
+            return Ok(());
+        }
+    }
+    let author_id = message
+        .map(|m| m.author())
+        .or_else(|| event.author.as_ref().map(|a| a.id));
+
+    let mut embed = EmbedBuilder::new()
+        .title(t!(locale, "log-edited-title"))
+        .color(0xe67e22)
+        .description(format!(
+            "[{}](https://discord.com/channels/{}/{}/{})",
+            t!(locale, "log-jump"),
+            guild_id,
+            event.channel_id,
+            event.id
+        ))
+        .field(
+            EmbedFieldBuilder::new(
+                t!(locale, "log-channel"),
+                format!("<#{}>", event.channel_id),
+            )
+            .inline(),
+        );
+    if let Some(author_id) = author_id {
+        embed = embed.field(
+            EmbedFieldBuilder::new(t!(locale, "log-author"), author(&cache, author_id)).inline(),
+        );
+    }
+    let before_text = match message {
+        Some(m) if !m.content().is_empty() => truncate(m.content(), FIELD_MAX),
+        Some(_) => t!(locale, "log-no-content"),
+        None => t!(locale, "log-not-cached"),
+    };
+    let after_text = if after.is_empty() {
+        t!(locale, "log-no-content")
+    } else {
+        truncate(after, FIELD_MAX)
+    };
+    embed = embed
+        .field(EmbedFieldBuilder::new(
+            t!(locale, "log-before"),
+            before_text,
+        ))
+        .field(EmbedFieldBuilder::new(t!(locale, "log-after"), after_text));
+    let embed = finish(locale, embed, event.id)?;
+    post(&ctx, guild_id, log, embed, None).await
+}
+
+async fn member_joined(member: Box<MemberAdd>, ctx: Arc<Mutex<OshiroContext>>) -> OshiroResult {
+    let (log, locale) = match log_channel(&ctx, member.guild_id, "member_log").await? {
+        Some(l) => l,
+        None => return Ok(()),
+    };
+    PROFILES
+        .lock()
+        .unwrap()
+        .seen
+        .insert((member.guild_id, member.user.id), Profile::of(&member.user));
+    let user = &member.user;
+    let mut embed = EmbedBuilder::new()
+        .title(t!(locale, "log-joined-title"))
+        .color(0x2ecc71)
+        .description(format!("<@{}> ({})", user.id, user.name))
+        .field(
+            EmbedFieldBuilder::new(
+                t!(locale, "log-account-age"),
+                format_duration(since_ms(user.id.timestamp())),
+            )
+            .inline(),
+        );
+    if let Some(avatar) = user.avatar {
+        embed = embed.thumbnail(ImageSource::url(avatar_url(user.id, avatar))?);
+    }
+    let embed = finish_now(embed, t!(locale, "log-user-id", id = user.id.to_string()))?;
+    post(&ctx, member.guild_id, log, embed, None).await
+}
+
+async fn member_left(
+    member: MemberRemove,
+    before: Arc<Snapshot>,
+    ctx: Arc<Mutex<OshiroContext>>,
+) -> OshiroResult {
+    PROFILES
+        .lock()
+        .unwrap()
+        .seen
+        .remove(&(member.guild_id, member.user.id));
+    let (log, locale) = match log_channel(&ctx, member.guild_id, "member_log").await? {
+        Some(l) => l,
+        None => return Ok(()),
+    };
+    let user = &member.user;
+    let mut embed = EmbedBuilder::new()
+        .title(t!(locale, "log-left-title"))
+        .color(0xe74c3c)
+        .description(format!("<@{}> ({})", user.id, user.name));
+    match &before.member {
+        Some(m) => {
+            embed = embed
+                .field(
+                    EmbedFieldBuilder::new(
+                        t!(locale, "log-time-in-server"),
+                        format_duration(since_ms(m.joined_at().as_micros() / 1000)),
+                    )
+                    .inline(),
+                )
+                .field(EmbedFieldBuilder::new(
+                    t!(locale, "log-roles"),
+                    roles(locale, m.roles()),
+                ));
+        }
+        None => {
+            embed = embed.field(EmbedFieldBuilder::new(
+                t!(locale, "log-roles"),
+                t!(locale, "log-member-not-cached"),
+            ));
+        }
+    }
+    if let Some(avatar) = user.avatar {
+        embed = embed.thumbnail(ImageSource::url(avatar_url(user.id, avatar))?);
+    }
+    let embed = finish_now(embed, t!(locale, "log-user-id", id = user.id.to_string()))?;
+    post(&ctx, member.guild_id, log, embed, None).await
+}
+
+/// Nickname, role, username and avatar changes. Member updates also come for timeouts,
+/// boosting and the like. Nicknames and roles are compared with the cached member, so without
+/// it those are skipped; usernames and avatars with what this guild saw last.
+async fn member_updated(
+    member: Box<MemberUpdate>,
+    before: Arc<Snapshot>,
+    ctx: Arc<Mutex<OshiroContext>>,
+) -> OshiroResult {
+    // checked first so profiles are only remembered for guilds that log them
+    let (log, locale) = match log_channel(&ctx, member.guild_id, "member_log").await? {
+        Some(l) => l,
+        None => return Ok(()),
+    };
+    let user = &member.user;
+    let old_profile = last_profile(member.guild_id, user, before.user.as_ref());
+    let old = before.member.as_ref();
+    let nick_changed = old.map_or(false, |o| o.nick() != member.nick.as_deref());
+    let old_roles = old.map_or(member.roles.as_slice(), |o| o.roles());
+    let added: Vec<Id<RoleMarker>> = member
+        .roles
+        .iter()
+        .filter(|r| !old_roles.contains(r))
+        .copied()
+        .collect();
+    let removed: Vec<Id<RoleMarker>> = old_roles
+        .iter()
+        .filter(|r| !member.roles.contains(r))
+        .copied()
+        .collect();
+    let name_changed = old_profile.as_ref().map_or(false, |p| p.name != user.name);
+    let avatar_changed = old_profile
+        .as_ref()
+        .map_or(false, |p| p.avatar != user.avatar);
+    if !nick_changed && added.is_empty() && removed.is_empty() && !name_changed && !avatar_changed {
+        return Ok(());
+    }
+
+    let mut embed = EmbedBuilder::new()
+        .title(t!(locale, "log-member-updated-title"))
+        .color(0x3498db)
+        .description(format!("<@{}> ({})", user.id, user.name));
+    if nick_changed {
+        let none = || t!(locale, "log-no-nickname");
+        embed = embed.field(EmbedFieldBuilder::new(
+            t!(locale, "log-nickname"),
+            format!(
+                "{} → {}",
+                old.and_then(|o| o.nick()).map_or_else(none, str::to_string),
+                member.nick.clone().unwrap_or_else(none)
+            ),
+        ));
+    }
+    if !added.is_empty() {
+        embed = embed.field(EmbedFieldBuilder::new(
+            t!(locale, "log-roles-added"),
+            roles(locale, &added),
+        ));
+    }
+    if !removed.is_empty() {
+        embed = embed.field(EmbedFieldBuilder::new(
+            t!(locale, "log-roles-removed"),
+            roles(locale, &removed),
+        ));
+    }
+    if let Some(old_profile) = old_profile.filter(|_| name_changed) {
+        embed = embed.field(EmbedFieldBuilder::new(
+            t!(locale, "log-username"),
+            format!("{} → {}", old_profile.name, user.name),
+        ));
+    }
+    if avatar_changed {
+        embed = embed.field(EmbedFieldBuilder::new(
+            t!(locale, "log-avatar"),
+            t!(locale, "log-avatar-changed"),
+        ));
+        if let Some(avatar) = user.avatar {
+            embed = embed.thumbnail(ImageSource::url(avatar_url(user.id, avatar))?);
+        }
+    }
+    let embed = finish_now(embed, t!(locale, "log-user-id", id = user.id.to_string()))?;
+    post(&ctx, member.guild_id, log, embed, None).await
+}
+
+/// The username and avatar this guild last saw, remembering the current ones for next time.
+/// A guild that hasn't seen the user yet goes by the user cache, unless another guild's update
+/// already put this very change in there.
+fn last_profile(guild_id: Id<GuildMarker>, user: &User, cached: Option<&User>) -> Option<Profile> {
+    let now = Profile::of(user);
+    let mut profiles = PROFILES.lock().unwrap();
+    profiles
+        .changed
+        .retain(|_, (_, _, at)| at.elapsed() < CHANGE_KEPT);
+    let last = match profiles.seen.insert((guild_id, user.id), now.clone()) {
+        Some(seen) => Some(seen),
+        None => match profiles.changed.get(&user.id) {
+            Some((from, to, _)) if *to == now => Some(from.clone()),
+            _ => cached.map(Profile::of),
+        },
+    };
+    if let Some(last) = last.as_ref().filter(|l| **l != now) {
+        profiles
+            .changed
+            .insert(user.id, (last.clone(), now, Instant::now()));
+    }
+    last
+}
+
+/// Forget the profiles a guild saw once the bot is out of it. An outage isn't leaving.
+async fn guild_left(guild: GuildDelete, _: Arc<Mutex<OshiroContext>>) -> OshiroResult {
+    if !guild.unavailable {
+        PROFILES
+            .lock()
+            .unwrap()
+            .seen
+            .retain(|(g, _), _| *g != guild.id);
+    }
+    Ok(())
+}
+
+async fn channel_created(
+    channel: Box<ChannelCreate>,
+    ctx: Arc<Mutex<OshiroContext>>,
+) -> OshiroResult {
+    structure_created(&ctx, &channel, "log-channel-created-title").await
+}
+
+async fn channel_deleted(
+    channel: Box<ChannelDelete>,
+    ctx: Arc<Mutex<OshiroContext>>,
+) -> OshiroResult {
+    structure_deleted(&ctx, &channel, "log-channel-deleted-title").await
+}
+
+async fn channel_updated(
+    channel: Box<ChannelUpdate>,
+    before: Arc<Snapshot>,
+    ctx: Arc<Mutex<OshiroContext>>,
+) -> OshiroResult {
+    structure_updated(
+        &ctx,
+        before.channel.as_ref(),
+        &channel,
+        "log-channel-updated-title",
+    )
+    .await
+}
+
+/// Thread creates also come when the bot is added to an existing private thread, only the
+/// ones marked as new are logged
+async fn thread_created(thread: Box<ThreadCreate>, ctx: Arc<Mutex<OshiroContext>>) -> OshiroResult {
+    if thread.newly_created != Some(true) {
+        return Ok(());
+    }
+    structure_created(&ctx, &thread, "log-thread-created-title").await
+}
+
+/// Thread deletes only carry ids, the name comes from the cache if it's there
+async fn thread_deleted(
+    thread: ThreadDelete,
+    before: Arc<Snapshot>,
+    ctx: Arc<Mutex<OshiroContext>>,
+) -> OshiroResult {
+    match &before.channel {
+        Some(c) => structure_deleted(&ctx, c, "log-thread-deleted-title").await,
+        None => {
+            let (log, locale) = match log_channel(&ctx, thread.guild_id, "server_log").await? {
+                Some(l) => l,
+                None => return Ok(()),
+            };
+            let embed = EmbedBuilder::new()
+                .title(t!(locale, "log-thread-deleted-title"))
+                .color(0xe74c3c)
+                .description(t!(
+                    locale,
+                    "log-thread-not-cached",
+                    parent = format!("<#{}>", thread.parent_id)
+                ));
+            let embed = finish_now(
+                embed,
+                t!(locale, "log-channel-id", id = thread.id.to_string()),
+            )?;
+            post(&ctx, thread.guild_id, log, embed, None).await
+        }
+    }
+}
+
+async fn thread_updated(
+    thread: Box<ThreadUpdate>,
+    before: Arc<Snapshot>,
+    ctx: Arc<Mutex<OshiroContext>>,
+) -> OshiroResult {
+    structure_updated(
+        &ctx,
+        before.channel.as_ref(),
+        &thread,
+        "log-thread-updated-title",
+    )
+    .await
+}
+
+async fn structure_created(
+    ctx: &Arc<Mutex<OshiroContext>>,
+    channel: &Channel,
+    title: &str,
+) -> OshiroResult {
+    let guild_id = match channel.guild_id {
+        Some(g) => g,
+        None => return Ok(()),
+    };
+    let (log, locale) = match log_channel(ctx, guild_id, "server_log").await? {
+        Some(l) => l,
+        None => return Ok(()),
+    };
+    let mut embed = EmbedBuilder::new()
+        .title(t!(locale, title))
+        .color(0x2ecc71)
+        .description(format!("<#{}> ({})", channel.id, channel_name(channel)));
+    if let Some(parent) = channel.parent_id {
+        embed = embed.field(
+            EmbedFieldBuilder::new(t!(locale, "log-parent"), format!("<#{}>", parent)).inline(),
+        );
+    }
+    let embed = finish_now(
+        embed,
+        t!(locale, "log-channel-id", id = channel.id.to_string()),
+    )?;
+    post(ctx, guild_id, log, embed, None).await
+}
+
+async fn structure_deleted(
+    ctx: &Arc<Mutex<OshiroContext>>,
+    channel: &Channel,
+    title: &str,
+) -> OshiroResult {
+    let guild_id = match channel.guild_id {
+        Some(g) => g,
+        None => return Ok(()),
+    };
+    let (log, locale) = match log_channel(ctx, guild_id, "server_log").await? {
+        Some(l) => l,
+        None => return Ok(()),
+    };
+    let mut embed = EmbedBuilder::new()
+        .title(t!(locale, title))
+        .color(0xe74c3c)
+        .description(channel_name(channel));
+    if let Some(parent) = channel.parent_id {
+        embed = embed.field(
+            EmbedFieldBuilder::new(t!(locale, "log-parent"), format!("<#{}>", parent)).inline(),
+        );
+    }
+    let embed = finish_now(
+        embed,
+        t!(locale, "log-channel-id", id = channel.id.to_string()),
+    )?;
+    post(ctx, guild_id, log, embed, None).await
+}
+
+/// Posts the fields that changed. Without the old version cached there's nothing to diff, and
+/// updates that only touch things not compared here are skipped.
+async fn structure_updated(
+    ctx: &Arc<Mutex<OshiroContext>>,
+    old: Option<&Channel>,
+    new: &Channel,
+    title: &str,
+) -> OshiroResult {
+    let (old, guild_id) = match (old, new.guild_id) {
+        (Some(o), Some(g)) => (o, g),
+        _ => return Ok(()),
+    };
+    let (log, locale) = match log_channel(ctx, guild_id, "server_log").await? {
+        Some(l) => l,
+        None => return Ok(()),
+    };
+    let changes = channel_changes(locale, guild_id, old, new);
+    if changes.is_empty() {
+        return Ok(());
+    }
+    let mut embed = EmbedBuilder::new()
+        .title(t!(locale, title))
+        .color(0x3498db)
+        .description(format!("<#{}> ({})", new.id, channel_name(new)));
+    for (name, value) in changes {
+        embed = embed.field(EmbedFieldBuilder::new(name, truncate(&value, FIELD_MAX)));
+    }
+    let embed = finish_now(embed, t!(locale, "log-channel-id", id = new.id.to_string()))?;
+    post(ctx, guild_id, log, embed, None).await
+}
+
+async fn role_created(role: RoleCreate, ctx: Arc<Mutex<OshiroContext>>) -> OshiroResult {
+    let (log, locale) = match log_channel(&ctx, role.guild_id, "server_log").await? {
+        Some(l) => l,
+        None => return Ok(()),
+    };
+    let embed = EmbedBuilder::new()
+        .title(t!(locale, "log-role-created-title"))
+        .color(0x2ecc71)
+        .description(format!("<@&{}> ({})", role.role.id, role.role.name))
+        .field(EmbedFieldBuilder::new(
+            t!(locale, "log-permissions"),
+            permission_names(locale, role.role.permissions),
+        ));
+    let embed = finish_now(
+        embed,
+        t!(locale, "log-role-id", id = role.role.id.to_string()),
+    )?;
+    post(&ctx, role.guild_id, log, embed, None).await
+}
+
+async fn role_deleted(
+    role: RoleDelete,
+    before: Arc<Snapshot>,
+    ctx: Arc<Mutex<OshiroContext>>,
+) -> OshiroResult {
+    let (log, locale) = match log_channel(&ctx, role.guild_id, "server_log").await? {
+        Some(l) => l,
+        None => return Ok(()),
+    };
+    let embed = EmbedBuilder::new()
+        .title(t!(locale, "log-role-deleted-title"))
+        .color(0xe74c3c)
+        .description(match &before.role {
+            Some(r) => r.name.clone(),
+            None => t!(locale, "log-not-cached-short"),
+        });
+    let embed = finish_now(
+        embed,
+        t!(locale, "log-role-id", id = role.role_id.to_string()),
+    )?;
+    post(&ctx, role.guild_id, log, embed, None).await
+}
+
+async fn role_updated(
+    role: RoleUpdate,
+    before: Arc<Snapshot>,
+    ctx: Arc<Mutex<OshiroContext>>,
+) -> OshiroResult {
+    let old = match &before.role {
+        Some(r) => r,
+        None => return Ok(()),
+    };
+    let (log, locale) = match log_channel(&ctx, role.guild_id, "server_log").await? {
+        Some(l) => l,
+        None => return Ok(()),
+    };
+    let changes = role_changes(locale, old, &role.role);
+    if changes.is_empty() {
+        return Ok(());
+    }
+    let mut embed = EmbedBuilder::new()
+        .title(t!(locale, "log-role-updated-title"))
+        .color(0x3498db)
+        .description(format!("<@&{}> ({})", role.role.id, role.role.name));
+    for (name, value) in changes {
+        embed = embed.field(EmbedFieldBuilder::new(name, truncate(&value, FIELD_MAX)));
+    }
+    let embed = finish_now(
+        embed,
+        t!(locale, "log-role-id", id = role.role.id.to_string()),
+    )?;
+    post(&ctx, role.guild_id, log, embed, None).await
+}
+
+/// Field name and `before → after` for everything compared that changed
+fn channel_changes(
+    locale: &str,
+    guild_id: Id<GuildMarker>,
+    old: &Channel,
+    new: &Channel,
+) -> Vec<(String, String)> {
+    let mut changes = Vec::new();
+    if old.name != new.name {
+        changes.push((
+            t!(locale, "log-name"),
+            format!("{} → {}", channel_name(old), channel_name(new)),
+        ));
+    }
+    if old.position != new.position {
+        let position = |p: Option<i32>| p.map_or_else(|| "-".to_string(), |p| p.to_string());
+        changes.push((
+            t!(locale, "log-position"),
+            format!("{} → {}", position(old.position), position(new.position)),
+        ));
+    }
+    if old.rate_limit_per_user != new.rate_limit_per_user {
+        let slowmode = |s: Option<u16>| match s {
+            Some(s) if s > 0 => format_duration(Duration::from_secs(s.into())),
+            _ => t!(locale, "log-off"),
+        };
+        changes.push((
+            t!(locale, "log-slowmode"),
+            format!(
+                "{} → {}",
+                slowmode(old.rate_limit_per_user),
+                slowmode(new.rate_limit_per_user)
+            ),
+        ));
+    }
+    let thread_state = |c: &Channel| c.thread_metadata.as_ref().map(|m| (m.archived, m.locked));
+    if let (Some((old_archived, old_locked)), Some((archived, locked))) =
+        (thread_state(old), thread_state(new))
+    {
+        if old_archived != archived {
+            changes.push((
+                t!(locale, "log-archived"),
+                yes_no(locale, old_archived, archived),
+            ));
+        }
+        if old_locked != locked {
+            changes.push((t!(locale, "log-locked"), yes_no(locale, old_locked, locked)));
+        }
+    }
+    let overwrites = overwrite_changes(
+        locale,
+        guild_id,
+        old.permission_overwrites.as_deref().unwrap_or_default(),
+        new.permission_overwrites.as_deref().unwrap_or_default(),
+    );
+    if !overwrites.is_empty() {
+        changes.push((t!(locale, "log-overwrites"), overwrites.join("\n")));
+    }
+    changes
+}
+
+/// One line per role or member whose overwrite changed, with what was allowed, denied or set
+/// back to inherit
+fn overwrite_changes(
+    locale: &str,
+    guild_id: Id<GuildMarker>,
+    old: &[PermissionOverwrite],
+    new: &[PermissionOverwrite],
+) -> Vec<String> {
+    let find = |list: &[PermissionOverwrite], id: Id<GenericMarker>| {
+        list.iter()
+            .find(|o| o.id == id)
+            .map_or((Permissions::empty(), Permissions::empty()), |o| {
+                (o.allow, o.deny)
+            })
+    };
+    let targets: BTreeSet<(Id<GenericMarker>, bool)> = old
+        .iter()
+        .chain(new)
+        .map(|o| (o.id, o.kind == PermissionOverwriteType::Member))
+        .collect();
+    let mut lines = Vec::new();
+    for (id, member) in targets {
+        let (old_allow, old_deny) = find(old, id);
+        let (allow, deny) = find(new, id);
+        if (old_allow, old_deny) == (allow, deny) {
+            continue;
+        }
+        let mut parts = Vec::new();
+        if !(allow - old_allow).is_empty() {
+            parts.push(t!(
+                locale,
+                "log-allowed",
+                permissions = permission_names(locale, allow - old_allow)
+            ));
+        }
+        if !(deny - old_deny).is_empty() {
+            parts.push(t!(
+                locale,
+                "log-denied",
+                permissions = permission_names(locale, deny - old_deny)
+            ));
+        }
+        let reset = (old_allow | old_deny) - (allow | deny);
+        if !reset.is_empty() {
+            parts.push(t!(
+                locale,
+                "log-inherited",
+                permissions = permission_names(locale, reset)
+            ));
+        }
+        let target = if member {
+            format!("<@{}>", id)
+        } else if id.get() == guild_id.get() {
+            "@everyone".to_string()
+        } else {
+            format!("<@&{}>", id)
+        };
+        lines.push(format!("{}: {}", target, parts.join("; ")));
+    }
+    lines
+}
+
+fn role_changes(locale: &str, old: &Role, new: &Role) -> Vec<(String, String)> {
+    let mut changes = Vec::new();
+    if old.name != new.name {
+        changes.push((
+            t!(locale, "log-name"),
+            format!("{} → {}", old.name, new.name),
+        ));
+    }
+    if old.color != new.color {
+        changes.push((
+            t!(locale, "log-color"),
+            format!("#{:06x} → #{:06x}", old.color, new.color),
+        ));
+    }
+    if old.hoist != new.hoist {
+        changes.push((
+            t!(locale, "log-hoist"),
+            yes_no(locale, old.hoist, new.hoist),
+        ));
+    }
+    if old.mentionable != new.mentionable {
+        changes.push((
+            t!(locale, "log-mentionable"),
+            yes_no(locale, old.mentionable, new.mentionable),
+        ));
+    }
+    if old.position != new.position {
+        changes.push((
+            t!(locale, "log-position"),
+            format!("{} → {}", old.position, new.position),
+        ));
+    }
+    let granted = new.permissions - old.permissions;
+    let revoked = old.permissions - new.permissions;
+    if !granted.is_empty() {
+        changes.push((t!(locale, "log-granted"), permission_names(locale, granted)));
+    }
+    if !revoked.is_empty() {
+        changes.push((t!(locale, "log-revoked"), permission_names(locale, revoked)));
+    }
+    changes
+}
+
+fn channel_name(channel: &Channel) -> String {
+    channel
+        .name
+        .clone()
+        .unwrap_or_else(|| channel.id.to_string())
+}
+
+fn yes_no(locale: &str, old: bool, new: bool) -> String {
+    let word = |b| {
+        if b {
+            t!(locale, "log-yes")
+        } else {
+            t!(locale, "log-no")
+        }
+    };
+    format!("{} → {}", word(old), word(new))
+}
+
+/// Each permission in the set by its flag name, like `SEND_MESSAGES, ATTACH_FILES`
+pub fn permission_names(locale: &str, permissions: Permissions) -> String {
+    let names: Vec<String> = (0..64)
+        .map(|bit| Permissions::from_bits_truncate(1 << bit))
+        .filter(|p| !p.is_empty() && permissions.contains(*p))
+        .map(|p| format!("{:?}", p))
+        .collect();
+    if names.is_empty() {
+        t!(locale, "log-no-permissions")
+    } else {
+        names.join(", ")
+    }
+}
+
+/// A log channel setting and the locale to post in, or `None` if it isn't set or isn't a channel
+/// of the guild
+async fn log_channel(
+    ctx: &Arc<Mutex<OshiroContext>>,
+    guild_id: Id<GuildMarker>,
+    key: &str,
+) -> OshiroResult<Option<(Id<ChannelMarker>, &'static str)>> {
+    let (db, http, cache, preferred) = {
+        let oshi = ctx.lock().await;
+        let preferred = oshi
+            .cache
+            .guild(guild_id)
+            .map(|g| g.preferred_locale().to_string());
+        (
+            oshi.db.clone(),
+            Arc::clone(&oshi.http),
+            Arc::clone(&oshi.cache),
+            preferred,
+        )
+    };
+    let log: Id<ChannelMarker> = match module::setting(&db, guild_id, &Logging, key)
+        .await?
+        .and_then(|c| c.parse().ok())
+    {
+        Some(l) => l,
+        None => return Ok(None),
+    };
+    // never post one guild's log into another's channel
+    if !channel_in_guild(&http, &cache, log, guild_id).await {
+        tracing::warn!("The {} of {} is set to a channel outside it", key, guild_id);
+        return Ok(None);
+    }
+    let locale = i18n::guild_locale(&db, guild_id, preferred.as_deref()).await;
+    Ok(Some((log, locale)))
+}
+
+/// The guild's message log channel and the locale to post in, or `None` if it has no log or
+/// `channel` shouldn't be logged: it's the log itself, or it or its thread's parent is ignored
+async fn message_log(
+    ctx: &Arc<Mutex<OshiroContext>>,
+    guild_id: Id<GuildMarker>,
+    channel: Id<ChannelMarker>,
+) -> OshiroResult<Option<(Id<ChannelMarker>, &'static str)>> {
+    let (log, locale) = match log_channel(ctx, guild_id, "message_log").await? {
+        Some(l) => l,
+        None => return Ok(None),
+    };
+    if log == channel {
+        return Ok(None);
+    }
+    let (db, parent) = {
+        let oshi = ctx.lock().await;
+        let parent = oshi.cache.channel(channel).and_then(|c| c.parent_id);
+        (oshi.db.clone(), parent)
+    };
+    let ignored = db::log_ignored_channels(&db, guild_id).await?;
+    if ignored.contains(&channel) || parent.map_or(false, |p| ignored.contains(&p)) {
+        return Ok(None);
+    }
+    Ok(Some((log, locale)))
+}
+
+/// Messages from bots and webhooks are only logged with `log_bots` on
+async fn log_author(
+    ctx: &Arc<Mutex<OshiroContext>>,
+    cache: &InMemoryCache,
+    guild_id: Id<GuildMarker>,
+    message: &CachedMessage,
+) -> OshiroResult<bool> {
+    let bot =
+        message.webhook_id().is_some() || cache.user(message.author()).map_or(false, |u| u.bot);
+    if !bot {
+        return Ok(true);
+    }
+    let db = ctx.lock().await.db.clone();
+    let setting = module::setting(&db, guild_id, &Logging, "log_bots").await?;
+    Ok(setting.as_deref() == Some("true"))
+}
+
+fn author(cache: &InMemoryCache, user: Id<UserMarker>) -> String {
+    match cache.user(user) {
+        Some(u) => format!("<@{}> ({})", user, u.name),
+        None => format!("<@{}>", user),
+    }
+}
+
+/// The message id in the footer and when it was sent as the timestamp
+fn finish(locale: &str, embed: EmbedBuilder, message: Id<MessageMarker>) -> OshiroResult<Embed> {
+    Ok(embed
+        .footer(EmbedFooterBuilder::new(t!(
+            locale,
+            "log-message-id",
+            id = message.to_string()
+        )))
+        .timestamp(Timestamp::from_micros(message.timestamp() * 1000)?)
+        .validate()?
+        .build())
+}
+
+/// What it's about in the footer and the current time as the timestamp
+fn finish_now(embed: EmbedBuilder, footer: String) -> OshiroResult<Embed> {
+    Ok(embed
+        .footer(EmbedFooterBuilder::new(footer))
+        .timestamp(Timestamp::from_secs(chrono::Utc::now().timestamp())?)
+        .validate()?
+        .build())
+}
+
+fn roles(locale: &str, roles: &[Id<RoleMarker>]) -> String {
+    if roles.is_empty() {
+        return t!(locale, "log-no-roles");
+    }
+    let mentions: Vec<String> = roles.iter().map(|r| format!("<@&{}>", r)).collect();
+    truncate(&mentions.join(" "), FIELD_MAX)
+}
+
+fn avatar_url(user: Id<UserMarker>, avatar: ImageHash) -> String {
+    hash_to_cdn_user_asset(
+        UserAssetType::Avatar,
+        &user.to_string(),
+        &avatar.to_string(),
+    )
+}
+
+/// How long ago a unix timestamp in milliseconds was
+fn since_ms(ms: i64) -> Duration {
+    let now = chrono::Utc::now().timestamp_millis();
+    Duration::from_millis((now - ms).max(0) as u64)
+}
+
+/// Post to a log channel. Failing to (the channel's gone, or we can't send there) is only
+/// logged, there's nobody to tell.
+async fn post(
+    ctx: &Arc<Mutex<OshiroContext>>,
+    guild_id: Id<GuildMarker>,
+    log: Id<ChannelMarker>,
+    embed: Embed,
+    file: Option<Attachment>,
+) -> OshiroResult {
+    let http = Arc::clone(&ctx.lock().await.http);
+    let embeds = [embed];
+    let files: Vec<Attachment> = file.into_iter().collect();
+    let result = http
+        .create_message(log)
+        .embeds(&embeds)?
+        .attachments(&files)?
+        .await;
+    if let Err(e) = result {
+        tracing::warn!("Could not post to the log in {}: {}", guild_id, e);
+    }
+    Ok(())
+}
+
+async fn logignore_text(ctx: CommandContext) -> OshiroResult {
+    if !check_admin(&ctx).await? {
+        return Ok(());
+    }
+    let stripped = ctx.stripped.clone().unwrap_or_default();
+    let args: Vec<&str> = stripped.split_whitespace().collect();
+    let channel = args
+        .get(1)
+        .and_then(|c| ConfigKind::Channel.parse(c).ok())
+        .and_then(|c| c.parse().ok());
+    let out = match args.first().copied() {
+        None | Some("list") => logignore_list(&ctx).await?,
+        Some("add") => logignore_set(&ctx, channel, true).await?,
+        Some("remove") => logignore_set(&ctx, channel, false).await?,
+        Some(other) => t!(
+            ctx.locale().await,
+            "logignore-unknown-subcommand",
+            name = other
+        ),
+    };
+    ctx.reply(&out).await
+}
+
+async fn logignore_list_slash(ctx: CommandContext) -> OshiroResult {
+    if !check_admin(&ctx).await? {
+        return Ok(());
+    }
+    let out = logignore_list(&ctx).await?;
+    ctx.reply_ephemeral(&out).await
+}
+
+async fn logignore_add_slash(ctx: CommandContext) -> OshiroResult {
+    if !check_admin(&ctx).await? {
+        return Ok(());
+    }
+    let out = logignore_set(&ctx, slash_channel(&ctx), true).await?;
+    ctx.reply_ephemeral(&out).await
+}
+
+async fn logignore_remove_slash(ctx: CommandContext) -> OshiroResult {
+    if !check_admin(&ctx).await? {
+        return Ok(());
+    }
+    let out = logignore_set(&ctx, slash_channel(&ctx), false).await?;
+    ctx.reply_ephemeral(&out).await
+}
+
+fn slash_channel(ctx: &CommandContext) -> Option<Id<ChannelMarker>> {
+    match ctx.slash.as_ref().and_then(|s| slash::option(s, "channel")) {
+        Some(CommandOptionValue::Channel(id)) => Some(id),
+        _ => None,
+    }
+}
+
+async fn logignore_list(ctx: &CommandContext) -> OshiroResult<String> {
+    let guild_id = ctx.guild_id().expect("checked by check_admin");
+    let ignored = db::log_ignored_channels(&ctx.db().await, guild_id).await?;
+    let locale = ctx.locale().await;
+    if ignored.is_empty() {
+        return Ok(t!(locale, "logignore-none"));
+    }
+    let channels: Vec<String> = ignored.iter().map(|c| format!("<#{}>", c)).collect();
+    Ok(t!(locale, "logignore-list", channels = channels.join(", ")))
+}
+
+async fn logignore_set(
+    ctx: &CommandContext,
+    channel: Option<Id<ChannelMarker>>,
+    ignored: bool,
+) -> OshiroResult<String> {
+    let guild_id = ctx.guild_id().expect("checked by check_admin");
+    let locale = ctx.locale().await;
+    let channel = match channel {
+        Some(c) => c,
+        None => return Ok(t!(locale, "logignore-which")),
+    };
+    db::set_log_ignored(&ctx.db().await, guild_id, channel, ignored).await?;
+    let mention = format!("<#{}>", channel);
+    Ok(if ignored {
+        t!(locale, "logignore-added", channel = mention)
+    } else {
+        t!(locale, "logignore-removed", channel = mention)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EN: &str = "en-US";
+
+    fn guild() -> Id<GuildMarker> {
+        Id::new(1)
+    }
+
+    fn overwrite(
+        id: u64,
+        kind: PermissionOverwriteType,
+        allow: Permissions,
+        deny: Permissions,
+    ) -> PermissionOverwrite {
+        PermissionOverwrite {
+            allow,
+            deny,
+            id: Id::new(id),
+            kind,
+        }
+    }
+
+    fn role(name: &str, permissions: Permissions) -> Role {
+        serde_json::from_value(serde_json::json!({
+            "id": "2",
+            "name": name,
+            "color": 0,
+            "hoist": false,
+            "icon": null,
+            "unicode_emoji": null,
+            "managed": false,
+            "mentionable": false,
+            "permissions": permissions.bits().to_string(),
+            "position": 1,
+            "flags": 0,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn unchanged_overwrites() {
+        let list = [overwrite(
+            2,
+            PermissionOverwriteType::Role,
+            Permissions::SEND_MESSAGES,
+            Permissions::empty(),
+        )];
+        assert!(overwrite_changes(EN, guild(), &list, &list).is_empty());
+        assert!(overwrite_changes(EN, guild(), &[], &[]).is_empty());
+    }
+
+    #[test]
+    fn allowed_denied_and_inherited() {
+        let old = [overwrite(
+            2,
+            PermissionOverwriteType::Role,
+            Permissions::SEND_MESSAGES,
+            Permissions::ATTACH_FILES,
+        )];
+        let new = [overwrite(
+            2,
+            PermissionOverwriteType::Role,
+            Permissions::SEND_MESSAGES | Permissions::EMBED_LINKS,
+            Permissions::ADD_REACTIONS,
+        )];
+        assert_eq!(
+            overwrite_changes(EN, guild(), &old, &new),
+            vec![
+                "<@&2>: allowed EMBED_LINKS; denied ADD_REACTIONS; back to inherited ATTACH_FILES"
+            ]
+        );
+    }
+
+    #[test]
+    fn added_and_removed_overwrites() {
+        let old = [overwrite(
+            3,
+            PermissionOverwriteType::Member,
+            Permissions::empty(),
+            Permissions::SEND_MESSAGES,
+        )];
+        let new = [overwrite(
+            1,
+            PermissionOverwriteType::Role,
+            Permissions::empty(),
+            Permissions::VIEW_CHANNEL,
+        )];
+        // sorted by target id, and the guild's id is @everyone
+        assert_eq!(
+            overwrite_changes(EN, guild(), &old, &new),
+            vec![
+                "@everyone: denied VIEW_CHANNEL",
+                "<@3>: back to inherited SEND_MESSAGES",
+            ]
+        );
+    }
+
+    #[test]
+    fn unchanged_role() {
+        let role = role("mods", Permissions::KICK_MEMBERS);
+        assert!(role_changes(EN, &role, &role).is_empty());
+    }
+
+    #[test]
+    fn changed_role() {
+        let old = role("mods", Permissions::KICK_MEMBERS | Permissions::BAN_MEMBERS);
+        let mut new = role(
+            "moderators",
+            Permissions::KICK_MEMBERS | Permissions::MANAGE_MESSAGES,
+        );
+        new.color = 0x3498db;
+        new.hoist = true;
+        new.position = 4;
+        assert_eq!(
+            role_changes(EN, &old, &new),
+            vec![
+                ("name".to_string(), "mods → moderators".to_string()),
+                ("color".to_string(), "#000000 → #3498db".to_string()),
+                ("shown separately".to_string(), "no → yes".to_string()),
+                ("position".to_string(), "1 → 4".to_string()),
+                (
+                    "permissions granted".to_string(),
+                    "MANAGE_MESSAGES".to_string()
+                ),
+                ("permissions revoked".to_string(), "BAN_MEMBERS".to_string()),
+            ]
+        );
+    }
+}
@@ -1,2 +1,48 @@
+use std::sync::Arc;
+
+use crate::{config::KnownModule, module::Module};
+
+pub mod admin;
+pub mod autorole;
+pub mod info;
+pub mod logging;
+pub mod moderation;
 pub mod novelty;
-pub mod system;
\ No newline at end of file
+pub mod owner;
+pub mod rolemenu;
+pub mod system;
+pub mod welcome;
+
+/// Every module the bot knows about
+pub fn modules() -> Vec<Arc<dyn Module>> {
+    vec![
+        Arc::new(admin::Admin),
+        Arc::new(owner::Owner),
+        Arc::new(system::System),
+        Arc::new(info::Info),
+        Arc::new(moderation::Moderation),
+        Arc::new(logging::Logging),
+        Arc::new(welcome::Welcome),
+        Arc::new(autorole::Autorole),
+        Arc::new(rolemenu::RoleMenus),
+        Arc::new(novelty::Novelty),
+    ]
+}
+
+/// Module and command names, for checking the config against
+pub fn known_modules() -> Vec<KnownModule> {
+    modules()
+        .iter()
+        .map(|m| KnownModule {
+            name: m.name(),
+            can_disable: m.can_disable(),
+            commands: m
+                .commands()
+                .into_iter()
+                .map(|c| c.name)
+                .chain(m.slash_commands().into_keys())
+                .chain(m.admin_slash_commands().into_keys())
+                .collect(),
+        })
+        .collect()
+}
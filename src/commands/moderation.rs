@@ -0,0 +1,1574 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use twilight_cache_inmemory::InMemoryCache;
+use twilight_http::{error::ErrorType, request::AuditLogReason, Client};
+use twilight_model::{
+    application::{
+        command::{Command, CommandType as SlashCommandType},
+        interaction::application_command::CommandOptionValue,
+    },
+    channel::message::{Embed, MessageFlags},
+    guild::Permissions,
+    http::interaction::{InteractionResponse, InteractionResponseType},
+    id::{
+        marker::{ChannelMarker, GuildMarker, MessageMarker, UserMarker},
+        Id,
+    },
+    util::Timestamp,
+};
+use twilight_util::{
+    builder::{
+        command::{
+            BooleanBuilder, CommandBuilder, IntegerBuilder, StringBuilder, SubCommandBuilder,
+            UserBuilder,
+        },
+        embed::{EmbedBuilder, EmbedFieldBuilder},
+        InteractionResponseDataBuilder,
+    },
+    snowflake::Snowflake,
+};
+
+use crate::{
+    cmd::{CommandContext, CommandInstance, OshiroResult},
+    command,
+    db::{self, ModCase},
+    helper::{
+        channel_in_guild, format_duration, parse_duration, parse_user, strip_prefix_ignore_case,
+    },
+    i18n,
+    module::{self, ConfigField, ConfigKind, Module},
+    slash::{self, CommandGroup, CommandWrapper},
+    t,
+};
+
+/// Discord won't time anyone out for longer than this
+const MAX_TIMEOUT: Duration = Duration::from_secs(60 * 60 * 24 * 28);
+/// Or delete more than this much of a banned user's history
+const MAX_BAN_DELETE: Duration = Duration::from_secs(60 * 60 * 24 * 7);
+/// Longest reason the audit log takes
+const AUDIT_REASON_MAX: usize = 512;
+/// Cases shown by `cases`, to stay under the message length limit
+const CASES_SHOWN: usize = 15;
+/// The most messages `purge` looks through
+const PURGE_MAX: i64 = 500;
+/// Authors named in `purge`'s report
+const PURGE_AUTHORS_SHOWN: usize = 10;
+/// Discord only bulk deletes messages younger than 14 days, with some slack for how long the
+/// purge itself takes
+const BULK_DELETE_AGE: Duration = Duration::from_secs(60 * 60 * 24 * 14 - 60 * 5);
+/// What `warn_action` can be set to
+const ESCALATIONS: &[&str] = &["timeout", "kick", "ban"];
+
+/// Warn, kick, ban and time out members. Every action is checked against the role hierarchy,
+/// lands in the audit log with the moderator and reason, and is saved as a numbered case.
+pub struct Moderation;
+
+impl Module for Moderation {
+    fn name(&self) -> &'static str {
+        "moderation"
+    }
+
+    fn description(&self) -> &'static str {
+        "warn, kick, ban and time out members, with a case log"
+    }
+
+    fn commands(&self) -> Vec<CommandInstance> {
+        vec![
+            command!(warn, "warn", "warn a member: warn <user> [reason]"),
+            command!(kick, "kick", "kick a member: kick <user> [reason]"),
+            command!(
+                ban,
+                "ban",
+                "ban a user: ban <user> [delete messages from, like 1d] [reason]"
+            ),
+            command!(unban, "unban", "lift a ban: unban <user id> [reason]"),
+            command!(
+                timeout,
+                "timeout",
+                "time out a member: timeout <user> <duration> [reason]"
+            ),
+            command!(
+                untimeout,
+                "untimeout",
+                "lift a member's timeout: untimeout <user> [reason]"
+            ),
+            command!(cases, "cases", "list a user's cases: cases <user>"),
+            command!(case_show, "case", "show a case: case <number>"),
+            command!(
+                case_reason,
+                "reason",
+                "change a case's reason: reason <number> <reason>"
+            ),
+            command!(case_delete, "delcase", "delete a case: delcase <number>"),
+            command!(
+                purge,
+                "purge",
+                "delete recent messages: purge <count> [user] [bots] [attachments] [links] \
+                 [before:<id>] [after:<id>] [contains:<text>]"
+            ),
+        ]
+    }
+
+    fn slash_commands(&self) -> HashMap<String, CommandWrapper> {
+        let mut commands = HashMap::new();
+        commands.insert(
+            "warn".to_string(),
+            CommandWrapper {
+                command: action_command(Action::Warn, "Warn a member", |c| c),
+                function: Some(Box::new(move |ctx| Box::pin(warn(ctx)))),
+                subcommands: None,
+            },
+        );
+        commands.insert(
+            "kick".to_string(),
+            CommandWrapper {
+                command: action_command(Action::Kick, "Kick a member", |c| c),
+                function: Some(Box::new(move |ctx| Box::pin(kick(ctx)))),
+                subcommands: None,
+            },
+        );
+        commands.insert(
+            "ban".to_string(),
+            CommandWrapper {
+                command: action_command(Action::Ban, "Ban a user", |c| {
+                    c.option(StringBuilder::new(
+                        "delete_messages",
+                        "Delete their messages from this far back, like 1h or 7d",
+                    ))
+                }),
+                function: Some(Box::new(move |ctx| Box::pin(ban(ctx)))),
+                subcommands: None,
+            },
+        );
+        commands.insert(
+            "unban".to_string(),
+            CommandWrapper {
+                command: action_command(Action::Unban, "Lift a ban", |c| c),
+                function: Some(Box::new(move |ctx| Box::pin(unban(ctx)))),
+                subcommands: None,
+            },
+        );
+        commands.insert(
+            "timeout".to_string(),
+            CommandWrapper {
+                command: action_command(Action::Timeout, "Time out a member", |c| {
+                    c.option(
+                        StringBuilder::new("duration", "How long for, like 10m or 1h30m")
+                            .required(true),
+                    )
+                }),
+                function: Some(Box::new(move |ctx| Box::pin(timeout(ctx)))),
+                subcommands: None,
+            },
+        );
+        commands.insert(
+            "untimeout".to_string(),
+            CommandWrapper {
+                command: action_command(Action::Untimeout, "Lift a member's timeout", |c| c),
+                function: Some(Box::new(move |ctx| Box::pin(untimeout(ctx)))),
+                subcommands: None,
+            },
+        );
+
+        commands.insert(
+            "cases".to_string(),
+            CommandWrapper {
+                command: CommandBuilder::new(
+                    "cases",
+                    "List a user's cases",
+                    SlashCommandType::ChatInput,
+                )
+                .dm_permission(false)
+                .default_member_permissions(Permissions::MODERATE_MEMBERS)
+                .option(UserBuilder::new("user", "The user").required(true))
+                .build(),
+                function: Some(Box::new(move |ctx| Box::pin(cases(ctx)))),
+                subcommands: None,
+            },
+        );
+        let number = || {
+            IntegerBuilder::new("number", "Case number")
+                .required(true)
+                .min_value(1)
+        };
+        let command = CommandBuilder::new("case", "Moderation cases", SlashCommandType::ChatInput)
+            .dm_permission(false)
+            .default_member_permissions(Permissions::MODERATE_MEMBERS)
+            .option(SubCommandBuilder::new("show", "Show a case").option(number()))
+            .option(
+                SubCommandBuilder::new("reason", "Change a case's reason")
+                    .option(number())
+                    .option(StringBuilder::new("reason", "The new reason").required(true)),
+            )
+            .option(SubCommandBuilder::new("delete", "Delete a case").option(number()))
+            .build();
+        let mut subcommands: HashMap<String, CommandGroup> = HashMap::new();
+        subcommands.insert(
+            "show".to_string(),
+            CommandGroup::Command(Box::new(move |ctx| Box::pin(case_show(ctx)))),
+        );
+        subcommands.insert(
+            "reason".to_string(),
+            CommandGroup::Command(Box::new(move |ctx| Box::pin(case_reason(ctx)))),
+        );
+        subcommands.insert(
+            "delete".to_string(),
+            CommandGroup::Command(Box::new(move |ctx| Box::pin(case_delete(ctx)))),
+        );
+        commands.insert(
+            "case".to_string(),
+            CommandWrapper {
+                command,
+                function: None,
+                subcommands: Some(subcommands),
+            },
+        );
+        commands.insert(
+            "purge".to_string(),
+            CommandWrapper {
+                command: CommandBuilder::new(
+                    "purge",
+                    "Delete recent messages",
+                    SlashCommandType::ChatInput,
+                )
+                .dm_permission(false)
+                .default_member_permissions(Permissions::MANAGE_MESSAGES)
+                .option(
+                    IntegerBuilder::new("count", "How many recent messages to look through")
+                        .required(true)
+                        .min_value(1)
+                        .max_value(PURGE_MAX),
+                )
+                .option(UserBuilder::new("user", "Only messages from this user"))
+                .option(BooleanBuilder::new(
+                    "bots",
+                    "Only messages from bots and webhooks",
+                ))
+                .option(StringBuilder::new(
+                    "contains",
+                    "Only messages containing this text",
+                ))
+                .option(BooleanBuilder::new(
+                    "attachments",
+                    "Only messages with attachments",
+                ))
+                .option(BooleanBuilder::new("links", "Only messages with links"))
+                .option(StringBuilder::new(
+                    "before",
+                    "Only messages before this one, by id or link",
+                ))
+                .option(StringBuilder::new(
+                    "after",
+                    "Only messages after this one, by id or link",
+                ))
+                .build(),
+                function: Some(Box::new(move |ctx| Box::pin(purge(ctx)))),
+                subcommands: None,
+            },
+        );
+        commands
+    }
+
+    fn config_schema(&self) -> Vec<ConfigField> {
+        vec![
+            ConfigField {
+                key: "dm_targets",
+                description: "DM members when they're warned, kicked, banned or timed out",
+                kind: ConfigKind::Bool,
+                default: Some("true"),
+            },
+            ConfigField {
+                key: "log_channel",
+                description: "channel every case is posted to",
+                kind: ConfigKind::Channel,
+                default: None,
+            },
+            ConfigField {
+                key: "warn_limit",
+                description: "every this many warnings, `warn_action` is taken. 0 turns it off",
+                kind: ConfigKind::Number,
+                default: Some("0"),
+            },
+            ConfigField {
+                key: "warn_action",
+                description: "what happens at `warn_limit` warnings: timeout, kick or ban",
+                kind: ConfigKind::Choice(ESCALATIONS),
+                default: Some("timeout"),
+            },
+            ConfigField {
+                key: "warn_timeout",
+                description: "how long the timeout at `warn_limit` warnings lasts",
+                kind: ConfigKind::Duration,
+                default: Some("1h"),
+            },
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Action {
+    Warn,
+    Kick,
+    Ban,
+    Unban,
+    Timeout,
+    Untimeout,
+}
+
+impl Action {
+    /// Also how the action is stored in cases
+    fn name(self) -> &'static str {
+        match self {
+            Action::Warn => "warn",
+            Action::Kick => "kick",
+            Action::Ban => "ban",
+            Action::Unban => "unban",
+            Action::Timeout => "timeout",
+            Action::Untimeout => "untimeout",
+        }
+    }
+
+    fn permission(self) -> Permissions {
+        match self {
+            Action::Kick => Permissions::KICK_MEMBERS,
+            Action::Ban | Action::Unban => Permissions::BAN_MEMBERS,
+            Action::Warn | Action::Timeout | Action::Untimeout => Permissions::MODERATE_MEMBERS,
+        }
+    }
+
+    /// The DM sent to the target, if they can still get one
+    fn dm(self) -> Option<&'static str> {
+        match self {
+            Action::Warn => Some("mod-dm-warned"),
+            Action::Kick => Some("mod-dm-kicked"),
+            Action::Ban => Some("mod-dm-banned"),
+            Action::Unban => None,
+            Action::Timeout => Some("mod-dm-timed-out"),
+            Action::Untimeout => Some("mod-dm-untimed-out"),
+        }
+    }
+}
+
+/// A moderation slash command: the user, whatever `options` adds, then the reason
+fn action_command(
+    action: Action,
+    description: &str,
+    options: impl FnOnce(CommandBuilder) -> CommandBuilder,
+) -> Command {
+    let user = if action == Action::Unban {
+        UserBuilder::new("user", "The banned user, by id")
+    } else {
+        UserBuilder::new("user", "The member")
+    };
+    let command = CommandBuilder::new(action.name(), description, SlashCommandType::ChatInput)
+        .dm_permission(false)
+        .default_member_permissions(action.permission())
+        .option(user.required(true));
+    options(command)
+        .option(StringBuilder::new("reason", "Why, for the audit log"))
+        .build()
+}
+
+async fn warn(ctx: CommandContext) -> OshiroResult {
+    let (guild_id, user) = match prepare(&ctx, Action::Warn).await? {
+        Some(t) => t,
+        None => return Ok(()),
+    };
+    let locale = ctx.locale().await;
+    let reason = reason(&ctx, 1);
+    let moderator = ctx.author_id().expect("commands always have an author");
+
+    let (case, dm) = carry_out(
+        &ctx,
+        guild_id,
+        user,
+        Action::Warn,
+        None,
+        &reason,
+        moderator,
+        &audit_reason(&ctx, &reason),
+    )
+    .await?;
+    let mut out = outcome(locale, Action::Warn, user, None, &case, &reason, dm);
+    let warns = db::count_cases(&ctx.db().await, guild_id, user, Action::Warn.name()).await?;
+    if let Some(escalated) = escalate(&ctx, guild_id, user, warns).await? {
+        out.push('\n');
+        out.push_str(&escalated);
+    }
+    ctx.reply(&out).await
+}
+
+async fn kick(ctx: CommandContext) -> OshiroResult {
+    let (guild_id, user) = match prepare(&ctx, Action::Kick).await? {
+        Some(t) => t,
+        None => return Ok(()),
+    };
+    let reason = reason(&ctx, 1);
+    run(&ctx, guild_id, user, Action::Kick, None, reason).await
+}
+
+async fn ban(ctx: CommandContext) -> OshiroResult {
+    let locale = ctx.locale().await;
+    // text commands take an optional deletion window before the reason, with a unit
+    let (window, reason) = match &ctx.slash {
+        Some(slash) => match slash::option_string(slash, "delete_messages") {
+            Some(w) => match parse_duration(&w) {
+                Some(w) => (Some(w), reason(&ctx, 0)),
+                None => {
+                    return ctx
+                        .reply_ephemeral(&t!(locale, "mod-bad-duration", duration = w))
+                        .await
+                }
+            },
+            None => (None, reason(&ctx, 0)),
+        },
+        // a bare number is more likely the start of the reason than minutes
+        None => match word(&ctx, 1, "")
+            .filter(|w| !w.chars().all(|c| c.is_ascii_digit()))
+            .and_then(|w| parse_duration(&w))
+        {
+            Some(w) => (Some(w), reason(&ctx, 2)),
+            None => (None, reason(&ctx, 1)),
+        },
+    };
+    if window.map_or(false, |w| w > MAX_BAN_DELETE) {
+        return ctx
+            .reply_ephemeral(&t!(locale, "mod-delete-too-long"))
+            .await;
+    }
+    let (guild_id, user) = match prepare(&ctx, Action::Ban).await? {
+        Some(t) => t,
+        None => return Ok(()),
+    };
+    run(&ctx, guild_id, user, Action::Ban, window, reason).await
+}
+
+async fn unban(ctx: CommandContext) -> OshiroResult {
+    let (guild_id, user) = match prepare(&ctx, Action::Unban).await? {
+        Some(t) => t,
+        None => return Ok(()),
+    };
+    let locale = ctx.locale().await;
+    let reason = reason(&ctx, 1);
+    let http = Arc::clone(&ctx.oshiro.lock().await.http);
+
+    match http.ban(guild_id, user).await {
+        Ok(_) => {}
+        Err(e) if not_found(&e) => {
+            return ctx
+                .reply_ephemeral(&t!(locale, "mod-not-banned", user = format!("<@{}>", user)))
+                .await
+        }
+        Err(e) => return Err(e.into()),
+    }
+    run(&ctx, guild_id, user, Action::Unban, None, reason).await
+}
+
+async fn timeout(ctx: CommandContext) -> OshiroResult {
+    let locale = ctx.locale().await;
+    let duration = match word(&ctx, 1, "duration") {
+        Some(d) => match parse_duration(&d) {
+            Some(d) => d,
+            None => {
+                return ctx
+                    .reply_ephemeral(&t!(locale, "mod-bad-duration", duration = d))
+                    .await
+            }
+        },
+        None => return ctx.reply_ephemeral(&t!(locale, "mod-which-duration")).await,
+    };
+    if duration > MAX_TIMEOUT {
+        return ctx
+            .reply_ephemeral(&t!(locale, "mod-timeout-too-long"))
+            .await;
+    }
+    let (guild_id, user) = match prepare(&ctx, Action::Timeout).await? {
+        Some(t) => t,
+        None => return Ok(()),
+    };
+    let reason = reason(&ctx, 2);
+    run(
+        &ctx,
+        guild_id,
+        user,
+        Action::Timeout,
+        Some(duration),
+        reason,
+    )
+    .await
+}
+
+async fn untimeout(ctx: CommandContext) -> OshiroResult {
+    let (guild_id, user) = match prepare(&ctx, Action::Untimeout).await? {
+        Some(t) => t,
+        None => return Ok(()),
+    };
+    let reason = reason(&ctx, 1);
+    run(&ctx, guild_id, user, Action::Untimeout, None, reason).await
+}
+
+/// Carry out a checked action for the invoking moderator and tell them how it went
+async fn run(
+    ctx: &CommandContext,
+    guild_id: Id<GuildMarker>,
+    user: Id<UserMarker>,
+    action: Action,
+    duration: Option<Duration>,
+    reason: Option<String>,
+) -> OshiroResult {
+    let locale = ctx.locale().await;
+    let moderator = ctx.author_id().expect("commands always have an author");
+    let (case, dm) = carry_out(
+        ctx,
+        guild_id,
+        user,
+        action,
+        duration,
+        &reason,
+        moderator,
+        &audit_reason(ctx, &reason),
+    )
+    .await?;
+    ctx.reply(&outcome(locale, action, user, duration, &case, &reason, dm))
+        .await
+}
+
+/// Take an action on Discord, DM the target about it and save it as a case. `duration` is how
+/// long a timeout lasts, or how far back a ban deletes messages.
+///
+/// Kicked and banned users are DMed first, while they still share a server with the bot, and
+/// DMed again if it then fails.
+#[allow(clippy::too_many_arguments)]
+async fn carry_out(
+    ctx: &CommandContext,
+    guild_id: Id<GuildMarker>,
+    user: Id<UserMarker>,
+    action: Action,
+    duration: Option<Duration>,
+    reason: &Option<String>,
+    moderator: Id<UserMarker>,
+    audit: &str,
+) -> OshiroResult<(ModCase, Option<bool>)> {
+    let http = Arc::clone(&ctx.oshiro.lock().await.http);
+    let dm_first = matches!(action, Action::Kick | Action::Ban);
+    let mut dm = None;
+    if dm_first {
+        dm = notify(ctx, guild_id, user, action.dm(), duration, reason).await?;
+    }
+
+    if let Err(e) = act(&http, guild_id, user, action, duration, audit).await {
+        // they were told it happened, so they're told it didn't
+        if dm == Some(true) {
+            notify(ctx, guild_id, user, Some("mod-dm-undone"), None, &None).await?;
+        }
+        return Err(e);
+    }
+
+    if !dm_first {
+        dm = notify(ctx, guild_id, user, action.dm(), duration, reason).await?;
+    }
+    let case = db::create_case(
+        &ctx.db().await,
+        guild_id,
+        action.name(),
+        user,
+        moderator,
+        reason.as_deref(),
+        duration.map(|d| d.as_secs() as i64),
+    )
+    .await?;
+    post_case(ctx, guild_id, &case).await?;
+    Ok((case, dm))
+}
+
+/// The action itself, on Discord
+async fn act(
+    http: &Client,
+    guild_id: Id<GuildMarker>,
+    user: Id<UserMarker>,
+    action: Action,
+    duration: Option<Duration>,
+    audit: &str,
+) -> OshiroResult {
+    match action {
+        Action::Warn => {}
+        Action::Kick => {
+            http.remove_guild_member(guild_id, user)
+                .reason(audit)?
+                .await?;
+        }
+        Action::Ban => {
+            let request = http.create_ban(guild_id, user).reason(audit)?;
+            match duration {
+                Some(d) => request.delete_message_seconds(d.as_secs() as u32)?.await?,
+                None => request.await?,
+            };
+        }
+        Action::Unban => {
+            http.delete_ban(guild_id, user).reason(audit)?.await?;
+        }
+        Action::Timeout => {
+            let secs = duration.map_or(0, |d| d.as_secs() as i64);
+            let until = Timestamp::from_secs(chrono::Utc::now().timestamp() + secs)?;
+            http.update_guild_member(guild_id, user)
+                .communication_disabled_until(Some(until))?
+                .reason(audit)?
+                .await?;
+        }
+        Action::Untimeout => {
+            http.update_guild_member(guild_id, user)
+                .communication_disabled_until(None)?
+                .reason(audit)?
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Take the guild's `warn_action` when a warning brings a user to a multiple of `warn_limit`.
+/// Returns what happened, for the reply to the warning.
+async fn escalate(
+    ctx: &CommandContext,
+    guild_id: Id<GuildMarker>,
+    user: Id<UserMarker>,
+    warns: i64,
+) -> OshiroResult<Option<String>> {
+    let db = ctx.db().await;
+    let limit: i64 = module::setting(&db, guild_id, &Moderation, "warn_limit")
+        .await?
+        .and_then(|l| l.parse().ok())
+        .unwrap_or(0);
+    if limit <= 0 || warns % limit != 0 {
+        return Ok(None);
+    }
+    let escalation = module::setting(&db, guild_id, &Moderation, "warn_action").await?;
+    let (action, duration) = match escalation.as_deref() {
+        Some("kick") => (Action::Kick, None),
+        Some("ban") => (Action::Ban, None),
+        _ => {
+            let duration = module::setting(&db, guild_id, &Moderation, "warn_timeout")
+                .await?
+                .and_then(|d| parse_duration(&d))
+                .unwrap_or(Duration::from_secs(60 * 60))
+                .min(MAX_TIMEOUT);
+            (Action::Timeout, Some(duration))
+        }
+    };
+
+    let locale = ctx.locale().await;
+    let reason = Some(t!(locale, "mod-escalation-reason", warns = warns));
+    let (http, cache) = {
+        let oshi = ctx.oshiro.lock().await;
+        (Arc::clone(&oshi.http), Arc::clone(&oshi.cache))
+    };
+    let me = bot_id(&http, &cache).await?;
+    let audit = truncate_reason(reason.clone().unwrap_or_default());
+    let result = carry_out(ctx, guild_id, user, action, duration, &reason, me, &audit).await;
+    Ok(Some(match result {
+        Ok((case, _)) => t!(
+            locale,
+            "mod-escalated",
+            action = action.name(),
+            duration = duration.map(format_duration).unwrap_or_default(),
+            case = case.case_id
+        ),
+        Err(e) => {
+            tracing::warn!("Could not escalate warnings in {}: {}", guild_id, e);
+            t!(locale, "mod-escalation-failed", error = e.to_string())
+        }
+    }))
+}
+
+async fn cases(ctx: CommandContext) -> OshiroResult {
+    let guild_id = match check_permission(&ctx, Permissions::MODERATE_MEMBERS, "cases").await? {
+        Some(g) => g,
+        None => return Ok(()),
+    };
+    let locale = ctx.locale().await;
+    let user = match target(&ctx) {
+        Some(u) => u,
+        None => return ctx.reply_ephemeral(&t!(locale, "mod-which-user")).await,
+    };
+    let cases = db::user_cases(&ctx.db().await, guild_id, user).await?;
+    let mention = format!("<@{}>", user);
+    if cases.is_empty() {
+        return ctx
+            .reply_ephemeral(&t!(locale, "cases-none", user = mention))
+            .await;
+    }
+
+    let mut lines = vec![t!(
+        locale,
+        "cases-heading",
+        user = mention,
+        count = cases.len()
+    )];
+    for case in cases.iter().take(CASES_SHOWN) {
+        lines.push(t!(
+            locale,
+            "cases-line",
+            case = case.case_id,
+            action = case.action.as_str(),
+            when = format!("<t:{}:R>", case.created_at),
+            reason = case
+                .reason
+                .clone()
+                .unwrap_or_else(|| t!(locale, "case-no-reason"))
+        ));
+    }
+    if cases.len() > CASES_SHOWN {
+        lines.push(t!(locale, "cases-more", count = cases.len() - CASES_SHOWN));
+    }
+    ctx.reply_ephemeral(&lines.join("\n")).await
+}
+
+async fn case_show(ctx: CommandContext) -> OshiroResult {
+    let case = match find_case(&ctx).await? {
+        Some((_, c)) => c,
+        None => return Ok(()),
+    };
+    let embed = case_embed(ctx.locale().await, &case)?;
+    ctx.reply_embeds(vec![embed]).await
+}
+
+async fn case_reason(ctx: CommandContext) -> OshiroResult {
+    let (guild_id, case) = match find_case(&ctx).await? {
+        Some(c) => c,
+        None => return Ok(()),
+    };
+    let locale = ctx.locale().await;
+    let reason = match reason(&ctx, 1) {
+        Some(r) => r,
+        None => return ctx.reply_ephemeral(&t!(locale, "case-which-reason")).await,
+    };
+    db::set_case_reason(&ctx.db().await, guild_id, case.case_id, &reason).await?;
+
+    // keep the mod log in step, it's fine if the post is gone
+    if let (Some(channel), Some(message)) = (
+        case.log_channel_id.and_then(|c| Id::new_checked(c as u64)),
+        case.log_message_id.and_then(|m| Id::new_checked(m as u64)),
+    ) {
+        let (http, log_locale) = log_target(&ctx, guild_id).await?;
+        let updated = ModCase {
+            reason: Some(reason.clone()),
+            ..case.clone()
+        };
+        let embed = case_embed(log_locale, &updated)?;
+        if let Err(e) = http
+            .update_message(channel, message)
+            .embeds(Some(&[embed]))?
+            .await
+        {
+            tracing::debug!(
+                "Could not update case {} in {}: {}",
+                case.case_id,
+                guild_id,
+                e
+            );
+        }
+    }
+    ctx.reply_ephemeral(&t!(locale, "case-reason-set", case = case.case_id))
+        .await
+}
+
+async fn case_delete(ctx: CommandContext) -> OshiroResult {
+    let (guild_id, case) = match find_case(&ctx).await? {
+        Some(c) => c,
+        None => return Ok(()),
+    };
+    let locale = ctx.locale().await;
+    db::delete_case(&ctx.db().await, guild_id, case.case_id).await?;
+
+    if let (Some(channel), Some(message)) = (
+        case.log_channel_id.and_then(|c| Id::new_checked(c as u64)),
+        case.log_message_id.and_then(|m| Id::new_checked(m as u64)),
+    ) {
+        let http = Arc::clone(&ctx.oshiro.lock().await.http);
+        if let Err(e) = http.delete_message(channel, message).await {
+            tracing::debug!(
+                "Could not delete case {} in {}: {}",
+                case.case_id,
+                guild_id,
+                e
+            );
+        }
+    }
+    ctx.reply_ephemeral(&t!(locale, "case-deleted", case = case.case_id))
+        .await
+}
+
+/// The case named by the `number` option or the first word, after checking the moderator can
+/// see cases. Replies with the problem if there's no such case.
+async fn find_case(ctx: &CommandContext) -> OshiroResult<Option<(Id<GuildMarker>, ModCase)>> {
+    let guild_id = match check_permission(ctx, Permissions::MODERATE_MEMBERS, "case").await? {
+        Some(g) => g,
+        None => return Ok(None),
+    };
+    let locale = ctx.locale().await;
+    let number = match word(ctx, 0, "number").and_then(|n| n.trim_start_matches('#').parse().ok()) {
+        Some(n) => n,
+        None => {
+            ctx.reply_ephemeral(&t!(locale, "case-which")).await?;
+            return Ok(None);
+        }
+    };
+    match db::mod_case(&ctx.db().await, guild_id, number).await? {
+        Some(case) => Ok(Some((guild_id, case))),
+        None => {
+            ctx.reply_ephemeral(&t!(locale, "case-not-found", case = number))
+                .await?;
+            Ok(None)
+        }
+    }
+}
+
+async fn purge(ctx: CommandContext) -> OshiroResult {
+    let guild_id = match check_permission(&ctx, Permissions::MANAGE_MESSAGES, "purge").await? {
+        Some(g) => g,
+        None => return Ok(()),
+    };
+    let locale = ctx.locale().await;
+    let channel = ctx.channel_id().expect("guild commands have a channel");
+    let (count, filters) = match purge_args(&ctx, locale) {
+        Ok(a) => a,
+        Err(e) => return ctx.reply_ephemeral(&e).await,
+    };
+    let (http, app_id) = {
+        let oshi = ctx.oshiro.lock().await;
+        (Arc::clone(&oshi.http), oshi.app_id)
+    };
+
+    // going through hundreds of messages can outlast the three seconds an interaction gets
+    if let Some(slash) = &ctx.slash {
+        let deferred = InteractionResponse {
+            kind: InteractionResponseType::DeferredChannelMessageWithSource,
+            data: Some(
+                InteractionResponseDataBuilder::new()
+                    .flags(MessageFlags::EPHEMERAL)
+                    .build(),
+            ),
+        };
+        http.interaction(app_id)
+            .create_response(slash.id, &slash.token, &deferred)
+            .await?;
+    }
+
+    // messages too old for a bulk delete go one request at a time, which for hundreds of them
+    // takes minutes of rate limits, so the event loop isn't kept waiting on it
+    tokio::spawn(async move {
+        if let Err(e) = run_purge(ctx, guild_id, channel, count, filters, locale).await {
+            tracing::warn!("Could not finish a purge in {}: {}", channel, e);
+        }
+    });
+    Ok(())
+}
+
+/// Scan, delete and report back, once `purge` has checked everything it could up front
+async fn run_purge(
+    ctx: CommandContext,
+    guild_id: Id<GuildMarker>,
+    channel: Id<ChannelMarker>,
+    count: usize,
+    filters: PurgeFilters,
+    locale: &'static str,
+) -> OshiroResult {
+    let (http, cache, app_id) = {
+        let oshi = ctx.oshiro.lock().await;
+        (Arc::clone(&oshi.http), Arc::clone(&oshi.cache), oshi.app_id)
+    };
+
+    // once deferred the interaction can't get a new response, so errors are reported here
+    // instead of by the slash command handler
+    let result: OshiroResult<String> = async {
+        let skip = ctx.msg.as_ref().map(|m| m.id);
+        let scanned = scan(&http, &cache, channel, count, &filters, skip).await?;
+        let matched: Vec<&Scanned> = scanned.iter().filter(|m| filters.matches(m)).collect();
+        let ids: Vec<Id<MessageMarker>> = matched.iter().map(|m| m.id).collect();
+        let audit = audit_reason(&ctx, &Some("purge".to_string()));
+        let one_by_one = delete_messages(&http, channel, &ids, &audit).await?;
+        tracing::debug!(
+            "Purged {} of {} messages in {} of {}",
+            ids.len(),
+            scanned.len(),
+            channel,
+            guild_id
+        );
+
+        let mut lines = vec![t!(
+            locale,
+            "purge-done",
+            deleted = ids.len(),
+            scanned = scanned.len()
+        )];
+        if one_by_one > 0 {
+            lines.push(t!(locale, "purge-old", count = one_by_one));
+        }
+        let mut authors: Vec<(Id<UserMarker>, usize)> = Vec::new();
+        for m in &matched {
+            match authors.iter_mut().find(|(a, _)| *a == m.author) {
+                Some((_, n)) => *n += 1,
+                None => authors.push((m.author, 1)),
+            }
+        }
+        authors.sort_by(|a, b| b.1.cmp(&a.1));
+        if !authors.is_empty() {
+            let shown: Vec<String> = authors
+                .iter()
+                .take(PURGE_AUTHORS_SHOWN)
+                .map(|(a, n)| format!("<@{}> ({})", a, n))
+                .collect();
+            lines.push(t!(locale, "purge-authors", authors = shown.join(", ")));
+        }
+        Ok(lines.join("\n"))
+    }
+    .await;
+
+    match (&ctx.slash, result) {
+        (Some(slash), result) => {
+            let out = result.unwrap_or_else(|e| {
+                tracing::error!("Error when running a command {:?}", e);
+                t!(locale, "command-failed", error = format!("{:?}", e))
+            });
+            http.interaction(app_id)
+                .update_response(&slash.token)
+                .content(Some(&out))?
+                .await?;
+            Ok(())
+        }
+        (None, result) => ctx.reply(&result?).await,
+    }
+}
+
+/// Which of the messages `purge` looks through get deleted. Pinned messages never are.
+#[derive(Debug, Default)]
+struct PurgeFilters {
+    user: Option<Id<UserMarker>>,
+    bots: bool,
+    contains: Option<String>,
+    attachments: bool,
+    links: bool,
+    before: Option<Id<MessageMarker>>,
+    after: Option<Id<MessageMarker>>,
+}
+
+impl PurgeFilters {
+    fn matches(&self, m: &Scanned) -> bool {
+        !m.pinned
+            && self.user.map_or(true, |u| m.author == u)
+            && (!self.bots || m.bot)
+            && self.contains.as_ref().map_or(true, |c| {
+                m.content.to_lowercase().contains(&c.to_lowercase())
+            })
+            && (!self.attachments || m.attachments)
+            && (!self.links || m.content.contains("http://") || m.content.contains("https://"))
+    }
+}
+
+/// The parts of a message the filters look at, whether it came from the cache or the API
+struct Scanned {
+    id: Id<MessageMarker>,
+    author: Id<UserMarker>,
+    /// Webhooks count as bots
+    bot: bool,
+    content: String,
+    attachments: bool,
+    pinned: bool,
+}
+
+/// How many messages to look through and the filters, from options or from
+/// `purge <count> [user] [bots] [attachments] [links] [before:<id>] [after:<id>] [contains:<text>]`.
+/// Errors are the reply to send.
+fn purge_args(ctx: &CommandContext, locale: &str) -> Result<(usize, PurgeFilters), String> {
+    let mut filters = PurgeFilters::default();
+    let message_id = |s: &str| -> Result<Id<MessageMarker>, String> {
+        // message links work too, the id is the last part
+        s.rsplit('/')
+            .next()
+            .and_then(|id| id.parse().ok())
+            .ok_or_else(|| t!(locale, "purge-bad-message", id = s))
+    };
+
+    let count = match &ctx.slash {
+        Some(slash) => {
+            let flag = |name: &str| {
+                matches!(
+                    slash::option(slash, name),
+                    Some(CommandOptionValue::Boolean(true))
+                )
+            };
+            filters.user = match slash::option(slash, "user") {
+                Some(CommandOptionValue::User(u)) => Some(u),
+                _ => None,
+            };
+            filters.bots = flag("bots");
+            filters.attachments = flag("attachments");
+            filters.links = flag("links");
+            filters.contains = slash::option_string(slash, "contains");
+            if let Some(b) = slash::option_string(slash, "before") {
+                filters.before = Some(message_id(&b)?);
+            }
+            if let Some(a) = slash::option_string(slash, "after") {
+                filters.after = Some(message_id(&a)?);
+            }
+            match slash::option(slash, "count") {
+                Some(CommandOptionValue::Integer(n)) => n,
+                _ => 0,
+            }
+        }
+        None => {
+            let text = ctx.stripped.as_deref().unwrap_or_default();
+            let mut words = text.split_whitespace();
+            let count = words.next().and_then(|n| n.parse().ok()).unwrap_or(0);
+            while let Some(word) = words.next() {
+                if let Some(first) = strip_prefix_ignore_case(word, "contains:") {
+                    // takes the rest of the line, so it has to come last
+                    let text = std::iter::once(first)
+                        .chain(words.by_ref())
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    filters.contains = Some(text).filter(|c| !c.is_empty());
+                } else if let Some(id) = strip_prefix_ignore_case(word, "before:") {
+                    filters.before = Some(message_id(id)?);
+                } else if let Some(id) = strip_prefix_ignore_case(word, "after:") {
+                    filters.after = Some(message_id(id)?);
+                } else if word.eq_ignore_ascii_case("bots") {
+                    filters.bots = true;
+                } else if word.eq_ignore_ascii_case("attachments")
+                    || word.eq_ignore_ascii_case("files")
+                {
+                    filters.attachments = true;
+                } else if word.eq_ignore_ascii_case("links") {
+                    filters.links = true;
+                } else if let Some(user) = parse_user(word) {
+                    filters.user = Some(user);
+                } else {
+                    return Err(t!(locale, "purge-unknown-filter", filter = word));
+                }
+            }
+            count
+        }
+    };
+    if !(1..=PURGE_MAX).contains(&count) {
+        return Err(t!(locale, "purge-which-count", max = PURGE_MAX));
+    }
+    Ok((count as usize, filters))
+}
+
+/// Up to `count` messages from the channel, newest first, between the `before` and `after`
+/// filters. `skip` is left out without counting, for the message that ran the command.
+///
+/// The cache holds a channel's newest messages, so they're taken from there first and only
+/// older ones are fetched. Messages sent while the bot was disconnected are missed by the
+/// cache, and so by purge.
+async fn scan(
+    http: &Client,
+    cache: &InMemoryCache,
+    channel: Id<ChannelMarker>,
+    count: usize,
+    filters: &PurgeFilters,
+    skip: Option<Id<MessageMarker>>,
+) -> OshiroResult<Vec<Scanned>> {
+    let mut scanned = Vec::new();
+    let cached: Vec<Id<MessageMarker>> = cache
+        .channel_messages(channel)
+        .map(|ids| ids.iter().copied().collect())
+        .unwrap_or_default();
+    for id in cached {
+        if scanned.len() == count {
+            return Ok(scanned);
+        }
+        if Some(id) == skip || filters.before.map_or(false, |b| id >= b) {
+            continue;
+        }
+        if filters.after.map_or(false, |a| id <= a) {
+            return Ok(scanned);
+        }
+        let message = match cache.message(id) {
+            Some(m) => m,
+            None => continue,
+        };
+        let bot =
+            message.webhook_id().is_some() || cache.user(message.author()).map_or(false, |u| u.bot);
+        scanned.push(Scanned {
+            id,
+            author: message.author(),
+            bot,
+            content: message.content().to_string(),
+            attachments: !message.attachments().is_empty(),
+            pinned: message.pinned(),
+        });
+    }
+
+    let mut before = scanned.last().map(|m| m.id).or(filters.before);
+    while scanned.len() < count {
+        let limit = (count - scanned.len()).min(100) as u16;
+        let request = http.channel_messages(channel).limit(limit)?;
+        let page = match before {
+            Some(b) => request.before(b).await?,
+            None => request.await?,
+        }
+        .models()
+        .await?;
+        if page.is_empty() {
+            break;
+        }
+        for message in page {
+            before = Some(message.id);
+            if Some(message.id) == skip {
+                continue;
+            }
+            if filters.after.map_or(false, |a| message.id <= a) || scanned.len() == count {
+                return Ok(scanned);
+            }
+            scanned.push(Scanned {
+                id: message.id,
+                author: message.author.id,
+                bot: message.author.bot || message.webhook_id.is_some(),
+                content: message.content,
+                attachments: !message.attachments.is_empty(),
+                pinned: message.pinned,
+            });
+        }
+    }
+    Ok(scanned)
+}
+
+/// Delete messages, in bulk where Discord allows it and one at a time where they're too old.
+/// Returns how many had to go one at a time.
+async fn delete_messages(
+    http: &Client,
+    channel: Id<ChannelMarker>,
+    ids: &[Id<MessageMarker>],
+    audit: &str,
+) -> OshiroResult<usize> {
+    let now = chrono::Utc::now().timestamp_millis();
+    let (recent, old): (Vec<Id<MessageMarker>>, Vec<Id<MessageMarker>>) = ids
+        .iter()
+        .partition(|id| now - id.timestamp() < BULK_DELETE_AGE.as_millis() as i64);
+
+    for chunk in recent.chunks(100) {
+        match chunk {
+            [one] => http.delete_message(channel, *one).reason(audit)?.await?,
+            _ => http.delete_messages(channel, chunk).reason(audit)?.await?,
+        };
+    }
+    for id in &old {
+        match http.delete_message(channel, *id).reason(audit)?.await {
+            Ok(_) => {}
+            // someone else got there first
+            Err(e) if not_found(&e) => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(old.len())
+}
+
+/// Post a new case to the guild's `log_channel`, if it has one. A missing channel or
+/// permission is logged rather than failing the action that's already been taken.
+async fn post_case(
+    ctx: &CommandContext,
+    guild_id: Id<GuildMarker>,
+    case: &ModCase,
+) -> OshiroResult {
+    let db = ctx.db().await;
+    let channel: Id<ChannelMarker> =
+        match module::setting(&db, guild_id, &Moderation, "log_channel")
+            .await?
+            .and_then(|c| c.parse().ok())
+        {
+            Some(c) => c,
+            None => return Ok(()),
+        };
+    let (http, locale) = log_target(ctx, guild_id).await?;
+    let cache = Arc::clone(&ctx.oshiro.lock().await.cache);
+    if !channel_in_guild(&http, &cache, channel, guild_id).await {
+        tracing::warn!("The mod log of {} is set to a channel outside it", guild_id);
+        return Ok(());
+    }
+    let embed = case_embed(locale, case)?;
+    let message = match http.create_message(channel).embeds(&[embed])?.await {
+        Ok(r) => r.model().await?,
+        Err(e) => {
+            tracing::warn!(
+                "Could not post case {} in {}: {}",
+                case.case_id,
+                guild_id,
+                e
+            );
+            return Ok(());
+        }
+    };
+    db::set_case_log_message(&db, guild_id, case.case_id, channel, message.id).await
+}
+
+/// The http client, and the guild's locale for the mod log rather than the moderator's
+async fn log_target(
+    ctx: &CommandContext,
+    guild_id: Id<GuildMarker>,
+) -> OshiroResult<(Arc<Client>, &'static str)> {
+    let (db, http, preferred) = {
+        let oshi = ctx.oshiro.lock().await;
+        let preferred = oshi
+            .cache
+            .guild(guild_id)
+            .map(|g| g.preferred_locale().to_string());
+        (oshi.db.clone(), Arc::clone(&oshi.http), preferred)
+    };
+    let locale = i18n::guild_locale(&db, guild_id, preferred.as_deref()).await;
+    Ok((http, locale))
+}
+
+fn case_embed(locale: &str, case: &ModCase) -> OshiroResult<Embed> {
+    let color = match case.action.as_str() {
+        "warn" => 0xf1c40f,
+        "kick" | "timeout" => 0xe67e22,
+        "ban" => 0xe74c3c,
+        _ => 0x2ecc71,
+    };
+    let mut embed = EmbedBuilder::new()
+        .title(t!(
+            locale,
+            "case-title",
+            case = case.case_id,
+            action = case.action.as_str()
+        ))
+        .color(color)
+        .field(
+            EmbedFieldBuilder::new(
+                t!(locale, "case-user"),
+                format!("<@{}> ({})", case.user_id, case.user_id),
+            )
+            .inline(),
+        )
+        .field(
+            EmbedFieldBuilder::new(
+                t!(locale, "case-moderator"),
+                format!("<@{}>", case.moderator_id),
+            )
+            .inline(),
+        );
+    if let Some(duration) = case.duration {
+        embed = embed.field(
+            EmbedFieldBuilder::new(
+                t!(locale, "case-duration"),
+                format_duration(Duration::from_secs(duration as u64)),
+            )
+            .inline(),
+        );
+    }
+    Ok(embed
+        .field(EmbedFieldBuilder::new(
+            t!(locale, "case-reason"),
+            case.reason
+                .clone()
+                .unwrap_or_else(|| t!(locale, "case-no-reason")),
+        ))
+        .timestamp(Timestamp::from_secs(case.created_at)?)
+        .validate()?
+        .build())
+}
+
+/// Check the command is in a guild and the moderator has the permission it needs, replying
+/// with the problem if not
+async fn check_permission(
+    ctx: &CommandContext,
+    permission: Permissions,
+    command: &str,
+) -> OshiroResult<Option<Id<GuildMarker>>> {
+    let locale = ctx.locale().await;
+    let guild_id = match ctx.guild_id() {
+        Some(g) => g,
+        None => {
+            ctx.reply_ephemeral(&t!(locale, "not-in-guild")).await?;
+            return Ok(None);
+        }
+    };
+    if !ctx.has_permission(permission).await {
+        ctx.reply_ephemeral(&t!(locale, "mod-no-permission", command = command))
+            .await?;
+        return Ok(None);
+    }
+    Ok(Some(guild_id))
+}
+
+/// Check that the command can go ahead: it's in a guild, the moderator has the permission and
+/// the target is within reach of both them and the bot. Replies with the problem if not.
+async fn prepare(
+    ctx: &CommandContext,
+    action: Action,
+) -> OshiroResult<Option<(Id<GuildMarker>, Id<UserMarker>)>> {
+    let guild_id = match check_permission(ctx, action.permission(), action.name()).await? {
+        Some(g) => g,
+        None => return Ok(None),
+    };
+    let locale = ctx.locale().await;
+    let user = match target(ctx) {
+        Some(u) => u,
+        None => {
+            ctx.reply_ephemeral(&t!(locale, "mod-which-user")).await?;
+            return Ok(None);
+        }
+    };
+    if let Some(problem) = check_target(ctx, guild_id, user, action).await? {
+        ctx.reply_ephemeral(&t!(locale, problem, user = format!("<@{}>", user)))
+            .await?;
+        return Ok(None);
+    }
+    Ok(Some((guild_id, user)))
+}
+
+/// Why the target is out of reach, as a message id, or `None` if they aren't.
+///
+/// Like Discord itself, the moderator's and the bot's highest roles both have to be above the
+/// target's. The server owner is above everyone.
+async fn check_target(
+    ctx: &CommandContext,
+    guild_id: Id<GuildMarker>,
+    target: Id<UserMarker>,
+    action: Action,
+) -> OshiroResult<Option<&'static str>> {
+    let moderator = ctx.author_id().expect("commands always have an author");
+    if target == moderator {
+        return Ok(Some("mod-self"));
+    }
+    let (http, cache) = {
+        let oshi = ctx.oshiro.lock().await;
+        (Arc::clone(&oshi.http), Arc::clone(&oshi.cache))
+    };
+    let me = bot_id(&http, &cache).await?;
+    if target == me {
+        return Ok(Some("mod-me"));
+    }
+    if action == Action::Unban {
+        return Ok(None);
+    }
+
+    let owner = match cache.guild(guild_id).map(|g| g.owner_id()) {
+        Some(o) => o,
+        None => http.guild(guild_id).await?.model().await?.owner_id,
+    };
+    if target == owner {
+        return Ok(Some("mod-owner"));
+    }
+    let target_top = match highest_role(&http, &cache, guild_id, target).await? {
+        Some(p) => p,
+        // banning someone who isn't here keeps them from joining
+        None if action == Action::Ban => return Ok(None),
+        None => return Ok(Some("mod-not-member")),
+    };
+    if moderator != owner
+        && highest_role(&http, &cache, guild_id, moderator)
+            .await?
+            .unwrap_or(0)
+            <= target_top
+    {
+        return Ok(Some("mod-above-you"));
+    }
+    if highest_role(&http, &cache, guild_id, me)
+        .await?
+        .unwrap_or(0)
+        <= target_top
+    {
+        return Ok(Some("mod-above-me"));
+    }
+    Ok(None)
+}
+
+pub async fn bot_id(http: &Client, cache: &InMemoryCache) -> OshiroResult<Id<UserMarker>> {
+    Ok(match cache.current_user() {
+        Some(u) => u.id,
+        None => http.current_user().await?.model().await?.id,
+    })
+}
+
+/// The position of a member's highest cached role, 0 if they only have @everyone, or `None`
+/// if they aren't in the guild
+pub async fn highest_role(
+    http: &Client,
+    cache: &InMemoryCache,
+    guild_id: Id<GuildMarker>,
+    user: Id<UserMarker>,
+) -> OshiroResult<Option<i64>> {
+    let cached = cache.member(guild_id, user).map(|m| m.roles().to_vec());
+    let roles = match cached {
+        Some(r) => r,
+        None => match http.guild_member(guild_id, user).await {
+            Ok(r) => r.model().await?.roles,
+            Err(e) if not_found(&e) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        },
+    };
+    Ok(Some(
+        roles
+            .iter()
+            .filter_map(|r| cache.role(*r).map(|r| r.resource().position))
+            .max()
+            .unwrap_or(0),
+    ))
+}
+
+/// DM the target about what happened, if the guild wants that. `message` is the message id,
+/// usually `Action::dm`. Returns whether it got through, or `None` if nothing was sent. Closed DMs are common and not an error.
+async fn notify(
+    ctx: &CommandContext,
+    guild_id: Id<GuildMarker>,
+    user: Id<UserMarker>,
+    message: Option<&'static str>,
+    duration: Option<Duration>,
+    reason: &Option<String>,
+) -> OshiroResult<Option<bool>> {
+    let message = match message {
+        Some(m) => m,
+        None => return Ok(None),
+    };
+    let (db, http, guild) = {
+        let oshi = ctx.oshiro.lock().await;
+        let guild = oshi
+            .cache
+            .guild(guild_id)
+            .map(|g| g.name().to_string())
+            .unwrap_or_else(|| guild_id.to_string());
+        (oshi.db.clone(), Arc::clone(&oshi.http), guild)
+    };
+    let wanted = module::setting(&db, guild_id, &Moderation, "dm_targets").await?;
+    if wanted.as_deref() != Some("true") {
+        return Ok(None);
+    }
+    let locale = ctx.locale().await;
+    let mut content = t!(
+        locale,
+        message,
+        guild = guild,
+        duration = duration.map(format_duration).unwrap_or_default()
+    );
+    if let Some(reason) = reason {
+        content.push('\n');
+        content.push_str(&t!(locale, "mod-reason", reason = reason.as_str()));
+    }
+
+    let channel = match http.create_private_channel(user).await {
+        Ok(r) => r.model().await?,
+        Err(e) => {
+            tracing::debug!("Could not open a DM with {}: {}", user, e);
+            return Ok(Some(false));
+        }
+    };
+    match http.create_message(channel.id).content(&content)?.await {
+        Ok(_) => Ok(Some(true)),
+        Err(e) => {
+            tracing::debug!("Could not DM {}: {}", user, e);
+            Ok(Some(false))
+        }
+    }
+}
+
+/// What happened and its case number, then the reason and whether the DM failed
+fn outcome(
+    locale: &str,
+    action: Action,
+    user: Id<UserMarker>,
+    duration: Option<Duration>,
+    case: &ModCase,
+    reason: &Option<String>,
+    dm: Option<bool>,
+) -> String {
+    let user = format!("<@{}>", user);
+    let mut out = match (action, duration) {
+        (Action::Warn, _) => t!(locale, "mod-warned", user = user),
+        (Action::Kick, _) => t!(locale, "mod-kicked", user = user),
+        (Action::Ban, Some(window)) => t!(
+            locale,
+            "mod-banned-deleted",
+            user = user,
+            window = format_duration(window)
+        ),
+        (Action::Ban, None) => t!(locale, "mod-banned", user = user),
+        (Action::Unban, _) => t!(locale, "mod-unbanned", user = user),
+        (Action::Timeout, _) => t!(
+            locale,
+            "mod-timed-out",
+            user = user,
+            duration = duration.map(format_duration).unwrap_or_default()
+        ),
+        (Action::Untimeout, _) => t!(locale, "mod-untimed-out", user = user),
+    };
+    out.push(' ');
+    out.push_str(&t!(locale, "mod-case", case = case.case_id));
+    if let Some(reason) = reason {
+        out.push('\n');
+        out.push_str(&t!(locale, "mod-reason", reason = reason.as_str()));
+    }
+    if dm == Some(false) {
+        out.push('\n');
+        out.push_str(&t!(locale, "mod-dm-failed"));
+    }
+    out
+}
+
+/// The audit log entry's reason, naming the moderator since the bot is the one acting
+fn audit_reason(ctx: &CommandContext, reason: &Option<String>) -> String {
+    let moderator = match &ctx.slash {
+        Some(slash) => slash
+            .member
+            .as_ref()
+            .and_then(|m| m.user.as_ref())
+            .map(|u| u.name.clone()),
+        None => ctx.msg.as_ref().map(|m| m.author.name.clone()),
+    }
+    .unwrap_or_default();
+    truncate_reason(format!(
+        "{} ({}): {}",
+        moderator,
+        ctx.author_id().expect("commands always have an author"),
+        reason.as_deref().unwrap_or("no reason given")
+    ))
+}
+
+fn truncate_reason(reason: String) -> String {
+    reason.chars().take(AUDIT_REASON_MAX).collect()
+}
+
+/// The user acted on: the `user` option, or a mention or id as the first word
+fn target(ctx: &CommandContext) -> Option<Id<UserMarker>> {
+    match &ctx.slash {
+        Some(slash) => match slash::option(slash, "user") {
+            Some(CommandOptionValue::User(id)) => Some(id),
+            _ => None,
+        },
+        None => word(ctx, 0, "").and_then(|w| parse_user(&w)),
+    }
+}
+
+/// A word by position for text commands, or an option by name for slash commands
+fn word(ctx: &CommandContext, position: usize, name: &str) -> Option<String> {
+    match &ctx.slash {
+        Some(slash) => match slash::option(slash, name) {
+            Some(CommandOptionValue::String(s)) => Some(s),
+            Some(CommandOptionValue::Integer(i)) => Some(i.to_string()),
+            _ => None,
+        },
+        None => ctx
+            .stripped
+            .as_deref()
+            .and_then(|s| s.split_whitespace().nth(position))
+            .map(str::to_string),
+    }
+}
+
+/// The `reason` option, or every word from `skip` on for text commands
+fn reason(ctx: &CommandContext, skip: usize) -> Option<String> {
+    match &ctx.slash {
+        Some(slash) => slash::option_string(slash, "reason"),
+        None => ctx.stripped.as_deref().map(|s| {
+            s.split_whitespace()
+                .skip(skip)
+                .collect::<Vec<_>>()
+                .join(" ")
+        }),
+    }
+    .filter(|r| !r.is_empty())
+}
+
+pub fn not_found(e: &twilight_http::Error) -> bool {
+    matches!(e.kind(), ErrorType::Response { status, .. } if status.get() == 404)
+}
@@ -1,13 +1,81 @@
-use twilight_model::application::interaction::{
-    application_command::CommandOptionValue, InteractionData,
+use std::collections::HashMap;
+
+use twilight_model::{
+    application::{
+        command::{Command, CommandOption, CommandOptionType, CommandType as SlashCommandType},
+        interaction::{application_command::CommandOptionValue, InteractionData},
+    },
+    id::Id,
 };
 
 use crate::{
-    cmd::{CommandContext, CommandType, OshiroResult},
-    slash::message,
+    cmd::{CommandContext, CommandInstance, CommandType, OshiroResult},
+    command,
+    module::Module,
+    slash::{message, CommandWrapper},
+    t,
 };
 
+pub struct Novelty;
+
+impl Module for Novelty {
+    fn name(&self) -> &'static str {
+        "novelty"
+    }
+
+    fn description(&self) -> &'static str {
+        "silly text toys"
+    }
+
+    fn commands(&self) -> Vec<CommandInstance> {
+        vec![command!(uwu, "uwu", "uwuifies strings xD")]
+    }
+
+    fn slash_commands(&self) -> HashMap<String, CommandWrapper> {
+        let mut commands = HashMap::new();
+        commands.insert(
+            "uwu".to_string(),
+            CommandWrapper {
+                command: Command {
+                    application_id: None,
+                    default_member_permissions: None,
+                    dm_permission: Some(true),
+                    description: "Uwuify a piece of text".to_owned(),
+                    description_localizations: None,
+                    guild_id: None,
+                    id: None,
+                    kind: SlashCommandType::ChatInput,
+                    name: "uwu".to_owned(),
+                    name_localizations: None,
+                    options: vec![CommandOption {
+                        autocomplete: None,
+                        channel_types: None,
+                        choices: None,
+                        description: "The text you want to process".to_owned(),
+                        description_localizations: None,
+                        kind: CommandOptionType::String,
+                        max_length: None,
+                        max_value: None,
+                        min_length: None,
+                        min_value: None,
+                        name: "text".to_owned(),
+                        name_localizations: None,
+                        options: None,
+                        required: Some(true),
+                    }],
+                    version: Id::new(1),
+                    nsfw: None,
+                },
+                function: Some(Box::new(move |ctx| Box::pin(uwu(ctx)))),
+                subcommands: None,
+            },
+        );
+        commands
+    }
+}
+
 pub async fn uwu(ctx: CommandContext) -> OshiroResult<()> {
+    let locale = ctx.locale().await;
     let start_string = match ctx.command_type {
         CommandType::SLASH => match ctx
             .slash
@@ -23,7 +91,7 @@ pub async fn uwu(ctx: CommandContext) -> OshiroResult<()> {
                 {
                     out.to_owned()
                 } else {
-                    "Something broke!".to_owned()
+                    t!(locale, "uwu-broken")
                 }
             }
             _ => todo!(),
@@ -0,0 +1,619 @@
+use std::{collections::HashMap, convert::TryFrom, sync::Arc, time::Duration};
+
+use tokio::sync::Mutex;
+
+use twilight_model::{
+    application::{
+        command::CommandType as SlashCommandType,
+        interaction::application_command::CommandOptionValue,
+    },
+    gateway::payload::incoming::GuildCreate,
+    guild::Permissions,
+    id::{marker::GuildMarker, Id},
+};
+use twilight_util::builder::command::{
+    CommandBuilder, IntegerBuilder, StringBuilder, SubCommandBuilder, SubCommandGroupBuilder,
+};
+
+use crate::{
+    cmd::{CommandContext, CommandInstance, OshiroResult},
+    command,
+    ctx::OshiroContext,
+    db::{self, BlacklistKind},
+    helper::{format_duration, parse_duration},
+    listener,
+    listener::Listener,
+    module::Module,
+    reload,
+    shard::shard_for_guild,
+    slash::{self, CommandGroup, CommandWrapper},
+    t,
+};
+
+/// Guilds shown per page of `guilds`
+const GUILDS_PAGE_SIZE: usize = 15;
+/// Entries shown by `blacklist list`, to stay under the message length limit
+const BLACKLIST_SHOWN: usize = 25;
+
+/// Tools for the bot's owners (`bot.owners`). The slash versions live under `/owner` in
+/// `bot.admin_guild`.
+pub struct Owner;
+
+impl Module for Owner {
+    fn name(&self) -> &'static str {
+        "owner"
+    }
+
+    fn description(&self) -> &'static str {
+        "commands for the bot's owners"
+    }
+
+    fn can_disable(&self) -> bool {
+        false
+    }
+
+    fn commands(&self) -> Vec<CommandInstance> {
+        vec![
+            command!(shutdown, "shutdown", "close every shard and exit"),
+            command!(reload_config, "reload-config", "load the config file again"),
+            command!(reload_config, "reload", "load the config file again"),
+            command!(
+                sync_commands,
+                "sync-commands",
+                "register slash commands again"
+            ),
+            command!(guilds, "guilds", "list guilds the bot is in"),
+            command!(leave, "leave", "leave a guild"),
+            command!(
+                blacklist,
+                "blacklist",
+                "list, add or remove blacklisted users and guilds"
+            ),
+            command!(shardinfo, "shardinfo", "show the state of every shard"),
+        ]
+    }
+
+    fn listeners(&self) -> Vec<Listener> {
+        vec![listener!(
+            GuildCreate,
+            "leave_blacklisted",
+            leave_blacklisted
+        )]
+    }
+
+    fn admin_slash_commands(&self) -> HashMap<String, CommandWrapper> {
+        let command = CommandBuilder::new("owner", "Bot owner tools", SlashCommandType::ChatInput)
+            .dm_permission(false)
+            .default_member_permissions(Permissions::ADMINISTRATOR)
+            .option(SubCommandBuilder::new(
+                "shutdown",
+                "Close every shard and exit",
+            ))
+            .option(SubCommandBuilder::new(
+                "reload-config",
+                "Load the config file again",
+            ))
+            .option(SubCommandBuilder::new(
+                "sync-commands",
+                "Register slash commands again",
+            ))
+            .option(
+                SubCommandBuilder::new("guilds", "List guilds the bot is in")
+                    .option(IntegerBuilder::new("page", "Page to show").min_value(1)),
+            )
+            .option(
+                SubCommandBuilder::new("leave", "Leave a guild")
+                    .option(StringBuilder::new("guild", "Guild id").required(true)),
+            )
+            .option(
+                SubCommandGroupBuilder::new("blacklist", "Users and guilds that can't use the bot")
+                    .subcommands([
+                        SubCommandBuilder::new("add", "Stop a user or guild from using the bot")
+                            .option(kind_option())
+                            .option(StringBuilder::new("id", "User or guild id").required(true))
+                            .option(StringBuilder::new(
+                                "duration",
+                                "How long for, like 30m or 7d. Forever if left out",
+                            ))
+                            .option(StringBuilder::new("reason", "Why")),
+                        SubCommandBuilder::new("remove", "Take a user or guild off the blacklist")
+                            .option(kind_option())
+                            .option(StringBuilder::new("id", "User or guild id").required(true)),
+                        SubCommandBuilder::new("list", "Show the blacklist"),
+                    ]),
+            )
+            .option(SubCommandBuilder::new(
+                "shardinfo",
+                "Show the state of every shard",
+            ))
+            .build();
+
+        let mut subcommands: HashMap<String, CommandGroup> = HashMap::new();
+        subcommands.insert(
+            "shutdown".to_string(),
+            CommandGroup::Command(Box::new(move |ctx| Box::pin(shutdown(ctx)))),
+        );
+        subcommands.insert(
+            "reload-config".to_string(),
+            CommandGroup::Command(Box::new(move |ctx| Box::pin(reload_config(ctx)))),
+        );
+        subcommands.insert(
+            "sync-commands".to_string(),
+            CommandGroup::Command(Box::new(move |ctx| Box::pin(sync_commands(ctx)))),
+        );
+        subcommands.insert(
+            "guilds".to_string(),
+            CommandGroup::Command(Box::new(move |ctx| Box::pin(guilds(ctx)))),
+        );
+        subcommands.insert(
+            "leave".to_string(),
+            CommandGroup::Command(Box::new(move |ctx| Box::pin(leave(ctx)))),
+        );
+        let mut blacklist: HashMap<String, CommandGroup> = HashMap::new();
+        blacklist.insert(
+            "add".to_string(),
+            CommandGroup::Command(Box::new(move |ctx| Box::pin(blacklist_add(ctx)))),
+        );
+        blacklist.insert(
+            "remove".to_string(),
+            CommandGroup::Command(Box::new(move |ctx| Box::pin(blacklist_remove(ctx)))),
+        );
+        blacklist.insert(
+            "list".to_string(),
+            CommandGroup::Command(Box::new(move |ctx| Box::pin(blacklist_list(ctx)))),
+        );
+        subcommands.insert(
+            "blacklist".to_string(),
+            CommandGroup::CommandGroup(blacklist),
+        );
+        subcommands.insert(
+            "shardinfo".to_string(),
+            CommandGroup::Command(Box::new(move |ctx| Box::pin(shardinfo(ctx)))),
+        );
+
+        let mut commands = HashMap::new();
+        commands.insert(
+            "owner".to_string(),
+            CommandWrapper {
+                command,
+                function: None,
+                subcommands: Some(subcommands),
+            },
+        );
+        commands
+    }
+}
+
+fn kind_option() -> StringBuilder {
+    StringBuilder::new("kind", "User or guild")
+        .required(true)
+        .choices([("user", "user"), ("guild", "guild")])
+}
+
+/// Owner commands are ignored for everyone else, slash commands get told why
+async fn check_owner(ctx: &CommandContext) -> OshiroResult<bool> {
+    if ctx.is_owner().await {
+        return Ok(true);
+    }
+    if ctx.slash.is_some() {
+        ctx.reply_ephemeral(&t!(ctx.locale().await, "owner-only"))
+            .await?;
+    }
+    Ok(false)
+}
+
+/// An argument by position for text commands, or by name for slash commands
+fn arg(ctx: &CommandContext, position: usize, name: &str) -> Option<String> {
+    match &ctx.slash {
+        Some(slash) => match slash::option(slash, name) {
+            Some(CommandOptionValue::String(s)) => Some(s),
+            Some(CommandOptionValue::Integer(i)) => Some(i.to_string()),
+            _ => None,
+        },
+        None => ctx
+            .stripped
+            .as_deref()
+            .and_then(|s| s.split_whitespace().nth(position))
+            .map(str::to_string),
+    }
+}
+
+async fn shutdown(ctx: CommandContext) -> OshiroResult {
+    if !check_owner(&ctx).await? {
+        return Ok(());
+    }
+    ctx.reply_ephemeral(&t!(ctx.locale().await, "owner-shutting-down"))
+        .await?;
+    ctx.oshiro.lock().await.request_shutdown();
+    Ok(())
+}
+
+async fn reload_config(ctx: CommandContext) -> OshiroResult {
+    if !check_owner(&ctx).await? {
+        return Ok(());
+    }
+    let locale = ctx.locale().await;
+    let out = match reload::apply(&ctx.oshiro).await {
+        Ok(reloaded) => {
+            let mut lines = vec![t!(locale, "owner-reloaded")];
+            if reloaded.synced {
+                lines.push(t!(locale, "owner-reloaded-synced"));
+            }
+            if !reloaded.restart.is_empty() {
+                lines.push(t!(
+                    locale,
+                    "owner-reloaded-restart",
+                    changes = reloaded.restart.join(", ")
+                ));
+            }
+            lines.join(" ")
+        }
+        Err(e) => format!("{}\n```\n{}\n```", t!(locale, "owner-reload-failed"), e),
+    };
+    ctx.reply_ephemeral(&out).await
+}
+
+async fn sync_commands(ctx: CommandContext) -> OshiroResult {
+    if !check_owner(&ctx).await? {
+        return Ok(());
+    }
+    let (http, app_id, config) = {
+        let oshi = ctx.oshiro.lock().await;
+        (
+            Arc::clone(&oshi.http),
+            oshi.app_id,
+            Arc::clone(&oshi.config),
+        )
+    };
+    slash::sync(&http, app_id, &config).await?;
+    ctx.reply_ephemeral(&t!(
+        ctx.locale().await,
+        "owner-synced",
+        commands = slash::definitions().len(),
+        admin = slash::admin_definitions().len()
+    ))
+    .await
+}
+
+async fn guilds(ctx: CommandContext) -> OshiroResult {
+    if !check_owner(&ctx).await? {
+        return Ok(());
+    }
+    let mut guilds: Vec<(String, Id<GuildMarker>, u64)> = ctx
+        .oshiro
+        .lock()
+        .await
+        .cache
+        .iter()
+        .guilds()
+        .map(|g| (g.name().to_string(), g.id(), g.member_count().unwrap_or(0)))
+        .collect();
+    guilds.sort_by(|a, b| b.2.cmp(&a.2));
+
+    let pages = ((guilds.len() + GUILDS_PAGE_SIZE - 1) / GUILDS_PAGE_SIZE).max(1);
+    let page = arg(&ctx, 0, "page")
+        .and_then(|p| p.parse::<usize>().ok())
+        .unwrap_or(1)
+        .clamp(1, pages);
+
+    let locale = ctx.locale().await;
+    let mut lines = vec![t!(
+        locale,
+        "owner-guilds",
+        count = guilds.len(),
+        page = page,
+        pages = pages
+    )];
+    for (name, id, members) in guilds
+        .iter()
+        .skip((page - 1) * GUILDS_PAGE_SIZE)
+        .take(GUILDS_PAGE_SIZE)
+    {
+        lines.push(t!(
+            locale,
+            "owner-guilds-entry",
+            name = name.as_str(),
+            id = id.to_string(),
+            members = *members
+        ));
+    }
+    ctx.reply_ephemeral(&lines.join("\n")).await
+}
+
+async fn leave(ctx: CommandContext) -> OshiroResult {
+    if !check_owner(&ctx).await? {
+        return Ok(());
+    }
+    let locale = ctx.locale().await;
+    let guild_id = match arg(&ctx, 0, "guild").and_then(|g| g.parse::<Id<GuildMarker>>().ok()) {
+        Some(g) => g,
+        None => return ctx.reply_ephemeral(&t!(locale, "owner-which-guild")).await,
+    };
+    let (http, name) = {
+        let oshi = ctx.oshiro.lock().await;
+        let name = oshi.cache.guild(guild_id).map(|g| g.name().to_string());
+        (Arc::clone(&oshi.http), name)
+    };
+    let name = match name {
+        Some(n) => n,
+        None => return ctx.reply_ephemeral(&t!(locale, "owner-not-in-guild")).await,
+    };
+    http.leave_guild(guild_id).await?;
+    ctx.reply_ephemeral(&t!(
+        locale,
+        "owner-left",
+        name = name,
+        id = guild_id.to_string()
+    ))
+    .await
+}
+
+/// Text only, the slash versions are separate subcommands
+async fn blacklist(ctx: CommandContext) -> OshiroResult {
+    match arg(&ctx, 0, "").as_deref() {
+        None | Some("list") => blacklist_list(ctx).await,
+        Some("add") => blacklist_add(ctx).await,
+        Some("remove") => blacklist_remove(ctx).await,
+        Some(other) => {
+            if !check_owner(&ctx).await? {
+                return Ok(());
+            }
+            ctx.reply(&t!(
+                ctx.locale().await,
+                "blacklist-unknown-subcommand",
+                name = other
+            ))
+            .await
+        }
+    }
+}
+
+/// The kind and id of a blacklist entry, after the subcommand for text commands. The error
+/// is a message id.
+fn blacklist_target(ctx: &CommandContext) -> Result<(BlacklistKind, u64), &'static str> {
+    let kind = arg(ctx, 1, "kind")
+        .and_then(|k| BlacklistKind::parse(&k))
+        .ok_or("blacklist-which-kind")?;
+    match arg(ctx, 2, "id").and_then(|i| i.parse::<u64>().ok()) {
+        Some(id) if id != 0 => Ok((kind, id)),
+        _ => Err("blacklist-which-id"),
+    }
+}
+
+async fn blacklist_add(ctx: CommandContext) -> OshiroResult {
+    if !check_owner(&ctx).await? {
+        return Ok(());
+    }
+    let locale = ctx.locale().await;
+    let (kind, id) = match blacklist_target(&ctx) {
+        Ok(t) => t,
+        Err(e) => return ctx.reply_ephemeral(&t!(locale, e)).await,
+    };
+
+    // text commands take an optional duration before the reason
+    let (duration, reason) = match &ctx.slash {
+        Some(slash) => {
+            let duration = match slash::option_string(slash, "duration") {
+                Some(d) => match parse_duration(&d) {
+                    Some(d) => Some(d),
+                    None => {
+                        return ctx
+                            .reply_ephemeral(&t!(locale, "blacklist-bad-duration", duration = d))
+                            .await
+                    }
+                },
+                None => None,
+            };
+            (duration, slash::option_string(slash, "reason"))
+        }
+        None => {
+            let duration = arg(&ctx, 3, "").and_then(|d| parse_duration(&d));
+            let skip = if duration.is_some() { 4 } else { 3 };
+            let reason = ctx
+                .stripped
+                .as_deref()
+                .map(|s| {
+                    s.split_whitespace()
+                        .skip(skip)
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+                .filter(|r| !r.is_empty());
+            (duration, reason)
+        }
+    };
+
+    let (db, http, config, in_guild) = {
+        let oshi = ctx.oshiro.lock().await;
+        let in_guild = kind == BlacklistKind::Guild
+            && Id::<GuildMarker>::new_checked(id).map_or(false, |g| oshi.cache.guild(g).is_some());
+        (
+            oshi.db.clone(),
+            Arc::clone(&oshi.http),
+            Arc::clone(&oshi.config),
+            in_guild,
+        )
+    };
+    if kind == BlacklistKind::User && config.bot.owners.iter().any(|o| o.get() == id) {
+        return ctx.reply_ephemeral(&t!(locale, "blacklist-owner")).await;
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    let expires_at = duration.and_then(|d| {
+        i64::try_from(d.as_secs())
+            .ok()
+            .and_then(|secs| now.checked_add(secs))
+    });
+    // a duration too long to put on the clock is as good as permanent
+    let duration = duration.filter(|_| expires_at.is_some());
+    db::add_to_blacklist(&db, kind, id, reason.as_deref(), expires_at).await?;
+    let mut out = match duration {
+        Some(d) => t!(
+            locale,
+            "blacklist-added-for",
+            kind = kind.as_str(),
+            id = id.to_string(),
+            duration = format_duration(d)
+        ),
+        None => t!(
+            locale,
+            "blacklist-added",
+            kind = kind.as_str(),
+            id = id.to_string()
+        ),
+    };
+    if in_guild && config.bot.leave_blacklisted_guilds {
+        http.leave_guild(Id::new(id)).await?;
+        out.push(' ');
+        out.push_str(&t!(locale, "blacklist-left"));
+    }
+    ctx.reply_ephemeral(&out).await
+}
+
+async fn blacklist_remove(ctx: CommandContext) -> OshiroResult {
+    if !check_owner(&ctx).await? {
+        return Ok(());
+    }
+    let locale = ctx.locale().await;
+    let (kind, id) = match blacklist_target(&ctx) {
+        Ok(t) => t,
+        Err(e) => return ctx.reply_ephemeral(&t!(locale, e)).await,
+    };
+    let db = ctx.db().await;
+    let out = if db::remove_from_blacklist(&db, kind, id).await? {
+        t!(
+            locale,
+            "blacklist-removed",
+            kind = kind.as_str(),
+            id = id.to_string()
+        )
+    } else {
+        t!(
+            locale,
+            "blacklist-not-listed",
+            kind = kind.as_str(),
+            id = id.to_string()
+        )
+    };
+    ctx.reply_ephemeral(&out).await
+}
+
+async fn blacklist_list(ctx: CommandContext) -> OshiroResult {
+    if !check_owner(&ctx).await? {
+        return Ok(());
+    }
+    let locale = ctx.locale().await;
+    let entries = db::blacklist(&ctx.db().await).await?;
+    if entries.is_empty() {
+        return ctx.reply_ephemeral(&t!(locale, "blacklist-empty")).await;
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    let mut lines = vec![t!(locale, "blacklist-count", count = entries.len())];
+    for e in entries.iter().take(BLACKLIST_SHOWN) {
+        let expires = match e.expires_at {
+            Some(at) => t!(
+                locale,
+                "blacklist-time-left",
+                duration = format_duration(Duration::from_secs((at - now).max(0) as u64))
+            ),
+            None => t!(locale, "blacklist-permanent"),
+        };
+        lines.push(t!(
+            locale,
+            "blacklist-entry",
+            kind = e.kind.as_str(),
+            id = e.id.to_string(),
+            reason = e
+                .reason
+                .clone()
+                .unwrap_or_else(|| t!(locale, "blacklist-no-reason")),
+            expires = expires
+        ));
+    }
+    if entries.len() > BLACKLIST_SHOWN {
+        lines.push(t!(
+            locale,
+            "blacklist-more",
+            count = entries.len() - BLACKLIST_SHOWN
+        ));
+    }
+    ctx.reply_ephemeral(&lines.join("\n")).await
+}
+
+/// Leave a guild that adds the bot back while it's blacklisted
+async fn leave_blacklisted(
+    guild: Box<GuildCreate>,
+    ctx: Arc<Mutex<OshiroContext>>,
+) -> OshiroResult {
+    let (db, http, leave) = {
+        let oshi = ctx.lock().await;
+        (
+            oshi.db.clone(),
+            Arc::clone(&oshi.http),
+            oshi.config.bot.leave_blacklisted_guilds,
+        )
+    };
+    if !leave {
+        return Ok(());
+    }
+    if let Some(entry) = db::blacklist_entry(&db, BlacklistKind::Guild, guild.id.get()).await? {
+        tracing::info!(
+            "Leaving blacklisted guild {} ({}): {}",
+            guild.name,
+            guild.id,
+            entry.reason.as_deref().unwrap_or("no reason")
+        );
+        http.leave_guild(guild.id).await?;
+    }
+    Ok(())
+}
+
+async fn shardinfo(ctx: CommandContext) -> OshiroResult {
+    if !check_owner(&ctx).await? {
+        return Ok(());
+    }
+    let (shards, guilds, timeout) = {
+        let oshi = ctx.oshiro.lock().await;
+        let guilds: Vec<u64> = oshi.cache.iter().guilds().map(|g| g.id().get()).collect();
+        (
+            oshi.shards.snapshot(),
+            guilds,
+            Duration::from_secs(oshi.config.server.heartbeat_timeout),
+        )
+    };
+
+    let locale = ctx.locale().await;
+    let mut lines = vec![t!(
+        locale,
+        "shardinfo-count",
+        running = shards.len(),
+        total = shards.first().map_or(0, |s| s.id.total())
+    )];
+    for s in &shards {
+        let mut state = format!("{:?}", s.state);
+        if !s.healthy(timeout) {
+            state.push(' ');
+            state.push_str(&t!(locale, "shardinfo-unhealthy"));
+        }
+        let latency = match s.average_latency() {
+            Some(l) => t!(locale, "shardinfo-latency", ms = l.as_millis() as u64),
+            None => t!(locale, "shardinfo-no-latency"),
+        };
+        lines.push(t!(
+            locale,
+            "shardinfo-entry",
+            id = s.id.number(),
+            state = state,
+            latency = latency,
+            guilds = guilds
+                .iter()
+                .filter(|g| shard_for_guild(**g, s.id.total()) == s.id.number())
+                .count(),
+            reconnects = s.reconnects,
+            resumes = s.resumes,
+            invalid = s.invalid_sessions
+        ));
+    }
+    ctx.reply_ephemeral(&lines.join("\n")).await
+}
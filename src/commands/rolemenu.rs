@@ -0,0 +1,713 @@
+use std::{collections::HashMap, sync::Arc};
+
+use twilight_cache_inmemory::InMemoryCache;
+use twilight_http::{request::AuditLogReason, Client};
+use twilight_model::{
+    application::{
+        command::CommandType as SlashCommandType,
+        interaction::{application_command::CommandOptionValue, InteractionData},
+    },
+    channel::message::{
+        component::{ActionRow, Button, ButtonStyle, SelectMenu, SelectMenuOption},
+        Component, Embed,
+    },
+    guild::Permissions,
+    id::{
+        marker::{ChannelMarker, MessageMarker, RoleMarker},
+        Id,
+    },
+};
+use twilight_util::builder::{
+    command::{
+        ChannelBuilder, CommandBuilder, IntegerBuilder, RoleBuilder, StringBuilder,
+        SubCommandBuilder,
+    },
+    embed::{EmbedBuilder, EmbedFooterBuilder},
+};
+
+use crate::{
+    cmd::{CommandContext, CommandInstance, OshiroResult},
+    command,
+    commands::{
+        admin::check_admin,
+        autorole::{cant_grant, cant_hand_out},
+    },
+    db::{self, RoleMenu, RoleMenuRole},
+    helper::channel_in_guild,
+    module::{ConfigKind, Module},
+    slash::{self, CommandGroup, CommandWrapper, SlashCommandFn},
+    t,
+};
+
+/// Custom id prefix for every menu's components. Posted menus keep theirs, so it can't change.
+const PREFIX: &str = "rolemenu";
+/// Menu styles
+const KINDS: &[&str] = &["buttons", "select"];
+/// Discord allows 25 buttons (5 rows of 5) or 25 select options
+const MAX_ROLES: usize = 25;
+const BUTTONS_PER_ROW: usize = 5;
+
+/// Self-assignable roles: admins post menus of buttons or a select, and members click to
+/// toggle the roles on themselves. Menus are stored, and clicks are routed by custom id, so
+/// they keep working across restarts.
+pub struct RoleMenus;
+
+impl Module for RoleMenus {
+    fn name(&self) -> &'static str {
+        "rolemenu"
+    }
+
+    fn description(&self) -> &'static str {
+        "menus of buttons or selects that members use to pick their own roles"
+    }
+
+    fn commands(&self) -> Vec<CommandInstance> {
+        vec![command!(
+            rolemenu_text,
+            "rolemenu",
+            "create, edit, post and delete role menus"
+        )]
+    }
+
+    fn slash_commands(&self) -> HashMap<String, CommandWrapper> {
+        let menu = || IntegerBuilder::new("menu", "The menu's number").required(true);
+        let role = || RoleBuilder::new("role", "The role");
+        let command = CommandBuilder::new(
+            "rolemenu",
+            "Menus members use to pick their own roles",
+            SlashCommandType::ChatInput,
+        )
+        .dm_permission(false)
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .option(SubCommandBuilder::new("list", "Show this server's menus"))
+        .option(
+            SubCommandBuilder::new("create", "Make a new menu")
+                .option(
+                    StringBuilder::new("kind", "Buttons, or a select menu")
+                        .required(true)
+                        .choices(KINDS.iter().map(|k| (*k, *k))),
+                )
+                .option(StringBuilder::new("title", "Shown above the menu").required(true)),
+        )
+        .option(
+            SubCommandBuilder::new("add", "Add a role, or change its label")
+                .option(menu())
+                .option(role().required(true))
+                .option(StringBuilder::new(
+                    "label",
+                    "Shown instead of the role's name",
+                )),
+        )
+        .option(
+            SubCommandBuilder::new("remove", "Take a role off a menu")
+                .option(menu())
+                .option(role().required(true)),
+        )
+        .option(
+            SubCommandBuilder::new("max", "Limit how many of the roles a member can have")
+                .option(menu())
+                .option(
+                    IntegerBuilder::new("count", "0 for no limit")
+                        .required(true)
+                        .min_value(0)
+                        .max_value(MAX_ROLES as i64),
+                ),
+        )
+        .option(
+            SubCommandBuilder::new("require", "Only let members with a role use the menu")
+                .option(menu())
+                .option(role()),
+        )
+        .option(
+            SubCommandBuilder::new("post", "Post the menu, or move it")
+                .option(menu())
+                .option(ChannelBuilder::new("channel", "Where, here by default")),
+        )
+        .option(SubCommandBuilder::new("delete", "Delete a menu").option(menu()))
+        .build();
+
+        let mut subcommands: HashMap<String, CommandGroup> = HashMap::new();
+        for sub in [
+            "list", "create", "add", "remove", "max", "require", "post", "delete",
+        ] {
+            subcommands.insert(
+                sub.to_string(),
+                CommandGroup::Command(Box::new(move |ctx| Box::pin(rolemenu_slash(ctx)))),
+            );
+        }
+
+        let mut commands = HashMap::new();
+        commands.insert(
+            "rolemenu".to_string(),
+            CommandWrapper {
+                command,
+                function: None,
+                subcommands: Some(subcommands),
+            },
+        );
+        commands
+    }
+
+    fn components(&self) -> HashMap<String, SlashCommandFn> {
+        let mut components: HashMap<String, SlashCommandFn> = HashMap::new();
+        components.insert(
+            PREFIX.to_string(),
+            Box::new(move |ctx| Box::pin(menu_used(ctx))),
+        );
+        components
+    }
+}
+
+/// Everything a subcommand might take, from either the text or slash form
+#[derive(Default)]
+struct Args {
+    menu: Option<i64>,
+    role: Option<Id<RoleMarker>>,
+    text: Option<String>,
+    kind: Option<String>,
+    count: Option<i64>,
+    channel: Option<Id<ChannelMarker>>,
+}
+
+async fn rolemenu_text(ctx: CommandContext) -> OshiroResult {
+    if !check_admin(&ctx).await? {
+        return Ok(());
+    }
+    let stripped = ctx.stripped.clone().unwrap_or_default();
+    let words: Vec<&str> = stripped.split_whitespace().collect();
+    let sub = words.first().copied().unwrap_or("list").to_lowercase();
+    let menu = words
+        .get(1)
+        .and_then(|m| m.trim_start_matches('#').parse().ok());
+    let role = |i: usize| {
+        words
+            .get(i)
+            .and_then(|r| ConfigKind::Role.parse(r).ok())
+            .and_then(|r| r.parse().ok())
+    };
+    let rest = |i: usize| Some(words.get(i..)?.join(" ")).filter(|r| !r.is_empty());
+    let args = match sub.as_str() {
+        "create" => Args {
+            kind: words.get(1).map(|k| k.to_lowercase()),
+            text: rest(2),
+            ..Default::default()
+        },
+        "add" => Args {
+            menu,
+            role: role(2),
+            text: rest(3),
+            ..Default::default()
+        },
+        "remove" | "require" => Args {
+            menu,
+            role: role(2),
+            ..Default::default()
+        },
+        "max" => Args {
+            menu,
+            count: words.get(2).and_then(|c| c.parse().ok()),
+            ..Default::default()
+        },
+        "post" => Args {
+            menu,
+            channel: words
+                .get(2)
+                .and_then(|c| ConfigKind::Channel.parse(c).ok())
+                .and_then(|c| c.parse().ok()),
+            ..Default::default()
+        },
+        _ => Args {
+            menu,
+            ..Default::default()
+        },
+    };
+    let out = rolemenu(&ctx, &sub, args).await?;
+    ctx.reply(&out).await
+}
+
+async fn rolemenu_slash(ctx: CommandContext) -> OshiroResult {
+    if !check_admin(&ctx).await? {
+        return Ok(());
+    }
+    let slash = ctx.slash.as_ref().expect("slash command");
+    let sub = match &slash.data {
+        Some(InteractionData::ApplicationCommand(data)) => data
+            .options
+            .first()
+            .map(|o| o.name.clone())
+            .unwrap_or_default(),
+        _ => String::new(),
+    };
+    let args = Args {
+        menu: match slash::option(slash, "menu") {
+            Some(CommandOptionValue::Integer(m)) => Some(m),
+            _ => None,
+        },
+        role: match slash::option(slash, "role") {
+            Some(CommandOptionValue::Role(r)) => Some(r),
+            _ => None,
+        },
+        text: slash::option_string(slash, "label").or_else(|| slash::option_string(slash, "title")),
+        kind: slash::option_string(slash, "kind"),
+        count: match slash::option(slash, "count") {
+            Some(CommandOptionValue::Integer(c)) => Some(c),
+            _ => None,
+        },
+        channel: match slash::option(slash, "channel") {
+            Some(CommandOptionValue::Channel(c)) => Some(c),
+            _ => None,
+        },
+    };
+    let out = rolemenu(&ctx, &sub, args).await?;
+    ctx.reply_ephemeral(&out).await
+}
+
+async fn rolemenu(ctx: &CommandContext, sub: &str, args: Args) -> OshiroResult<String> {
+    let guild_id = ctx.guild_id().expect("checked by check_admin");
+    let locale = ctx.locale().await;
+    let db = ctx.db().await;
+
+    if sub == "list" {
+        let menus = db::role_menus(&db, guild_id).await?;
+        if menus.is_empty() {
+            return Ok(t!(locale, "rolemenu-none"));
+        }
+        let mut lines = Vec::with_capacity(menus.len());
+        for menu in menus {
+            let roles = db::role_menu_roles(&db, menu.menu_id).await?;
+            let posted = match (menu.channel_id, menu.message_id) {
+                (Some(c), Some(m)) => {
+                    format!("https://discord.com/channels/{}/{}/{}", guild_id, c, m)
+                }
+                _ => t!(locale, "rolemenu-not-posted"),
+            };
+            lines.push(t!(
+                locale,
+                "rolemenu-list-entry",
+                menu = menu.menu_id,
+                title = menu.title,
+                kind = menu.kind,
+                roles = roles.len(),
+                posted = posted
+            ));
+        }
+        return Ok(lines.join("\n"));
+    }
+
+    if sub == "create" {
+        let kind = match args.kind.filter(|k| KINDS.contains(&k.as_str())) {
+            Some(k) => k,
+            None => return Ok(t!(locale, "rolemenu-which-kind")),
+        };
+        let title = match args.text {
+            Some(t) => t,
+            None => return Ok(t!(locale, "rolemenu-which-title")),
+        };
+        let menu = db::create_role_menu(&db, guild_id, &kind, &title).await?;
+        return Ok(t!(locale, "rolemenu-created", menu = menu.menu_id));
+    }
+
+    let menu_id = match args.menu {
+        Some(m) => m,
+        None => return Ok(t!(locale, "rolemenu-which-menu")),
+    };
+    let menu = match db::role_menu(&db, guild_id, menu_id).await? {
+        Some(m) => m,
+        None => return Ok(t!(locale, "rolemenu-not-found", menu = menu_id)),
+    };
+    let (http, cache) = {
+        let oshi = ctx.oshiro.lock().await;
+        (Arc::clone(&oshi.http), Arc::clone(&oshi.cache))
+    };
+    let mention = args.role.map(|r| format!("<@&{}>", r)).unwrap_or_default();
+
+    let done = match sub {
+        "add" => {
+            let role = match args.role {
+                Some(r) => r,
+                None => return Ok(t!(locale, "rolemenu-which-role")),
+            };
+            if let Some(problem) = cant_hand_out(ctx, guild_id, role).await? {
+                return Ok(t!(locale, problem, role = mention));
+            }
+            if let Some(problem) = cant_grant(&http, &cache, guild_id, role).await? {
+                return Ok(t!(locale, problem, role = mention));
+            }
+            let roles = db::role_menu_roles(&db, menu_id).await?;
+            let listed = roles.iter().any(|r| r.role_id == db::id(role));
+            if !listed && roles.len() >= MAX_ROLES {
+                return Ok(t!(locale, "rolemenu-full", max = MAX_ROLES));
+            }
+            db::add_role_menu_role(&db, menu_id, role, args.text.as_deref()).await?;
+            t!(
+                locale,
+                "rolemenu-role-added",
+                role = mention,
+                menu = menu_id
+            )
+        }
+        "remove" => {
+            let role = match args.role {
+                Some(r) => r,
+                None => return Ok(t!(locale, "rolemenu-which-role")),
+            };
+            if !db::remove_role_menu_role(&db, menu_id, role).await? {
+                return Ok(t!(locale, "rolemenu-role-not-on", role = mention));
+            }
+            t!(
+                locale,
+                "rolemenu-role-removed",
+                role = mention,
+                menu = menu_id
+            )
+        }
+        "max" => {
+            let count = match args.count.filter(|c| (0..=MAX_ROLES as i64).contains(c)) {
+                Some(c) => c,
+                None => return Ok(t!(locale, "rolemenu-which-count", max = MAX_ROLES)),
+            };
+            db::set_role_menu_max(&db, menu_id, Some(count).filter(|c| *c > 0)).await?;
+            t!(locale, "rolemenu-max-set", count = count, menu = menu_id)
+        }
+        "require" => {
+            if let Some(role) = args.role {
+                if let Some(problem) = cant_hand_out(ctx, guild_id, role).await? {
+                    return Ok(t!(locale, problem, role = mention));
+                }
+            }
+            db::set_role_menu_required(&db, menu_id, args.role).await?;
+            match args.role {
+                Some(_) => t!(
+                    locale,
+                    "rolemenu-required-set",
+                    role = mention,
+                    menu = menu_id
+                ),
+                None => t!(locale, "rolemenu-required-cleared", menu = menu_id),
+            }
+        }
+        "post" => {
+            let channel = match args.channel.or_else(|| ctx.channel_id()) {
+                Some(c) => c,
+                None => return Ok(t!(locale, "rolemenu-which-channel")),
+            };
+            if !channel_in_guild(&http, &cache, channel, guild_id).await {
+                return Ok(t!(
+                    locale,
+                    "rolemenu-foreign-channel",
+                    channel = format!("<#{}>", channel)
+                ));
+            }
+            let roles = db::role_menu_roles(&db, menu_id).await?;
+            if roles.is_empty() {
+                return Ok(t!(locale, "rolemenu-empty", menu = menu_id));
+            }
+            let (embed, components) = render(locale, &cache, &menu, &roles)?;
+            let message = http
+                .create_message(channel)
+                .embeds(&[embed])?
+                .components(&components)?
+                .await?
+                .model()
+                .await?;
+            // the old post would still work but go stale, so it's taken down
+            if let Some((c, m)) = posted(&menu) {
+                if let Err(e) = http.delete_message(c, m).await {
+                    tracing::debug!("Could not delete the old post of menu {}: {}", menu_id, e);
+                }
+            }
+            db::set_role_menu_message(&db, menu_id, channel, message.id).await?;
+            return Ok(t!(
+                locale,
+                "rolemenu-posted",
+                menu = menu_id,
+                channel = format!("<#{}>", channel)
+            ));
+        }
+        "delete" => {
+            db::delete_role_menu(&db, guild_id, menu_id).await?;
+            if let Some((c, m)) = posted(&menu) {
+                if let Err(e) = http.delete_message(c, m).await {
+                    tracing::debug!("Could not delete the post of menu {}: {}", menu_id, e);
+                }
+            }
+            return Ok(t!(locale, "rolemenu-deleted", menu = menu_id));
+        }
+        other => return Ok(t!(locale, "rolemenu-unknown-subcommand", name = other)),
+    };
+
+    // changes show up on the posted menu straight away
+    let menu = db::role_menu(&db, guild_id, menu_id)
+        .await?
+        .expect("menu exists");
+    if let Err(e) = refresh(&http, &cache, &db, locale, &menu).await {
+        tracing::warn!("Could not update the post of menu {}: {}", menu_id, e);
+        return Ok(format!(
+            "{}\n{}",
+            done,
+            t!(locale, "rolemenu-refresh-failed")
+        ));
+    }
+    Ok(done)
+}
+
+/// Update the posted menu, if there is one, to match what's stored
+async fn refresh(
+    http: &Client,
+    cache: &InMemoryCache,
+    db: &sqlx::SqlitePool,
+    locale: &str,
+    menu: &RoleMenu,
+) -> OshiroResult {
+    let (channel, message) = match posted(menu) {
+        Some(p) => p,
+        None => return Ok(()),
+    };
+    let roles = db::role_menu_roles(db, menu.menu_id).await?;
+    if roles.is_empty() {
+        // a select can't have no options, and a menu with nothing on it is no use anyway
+        let none: &[Component] = &[];
+        http.update_message(channel, message)
+            .components(Some(none))?
+            .await?;
+        return Ok(());
+    }
+    let (embed, components) = render(locale, cache, menu, &roles)?;
+    let embeds = [embed];
+    http.update_message(channel, message)
+        .embeds(Some(&embeds))?
+        .components(Some(&components))?
+        .await?;
+    Ok(())
+}
+
+fn posted(menu: &RoleMenu) -> Option<(Id<ChannelMarker>, Id<MessageMarker>)> {
+    let channel = Id::new_checked(menu.channel_id? as u64)?;
+    let message = Id::new_checked(menu.message_id? as u64)?;
+    Some((channel, message))
+}
+
+/// The menu's embed and its buttons or select. Labels fall back to the role's name.
+fn render(
+    locale: &str,
+    cache: &InMemoryCache,
+    menu: &RoleMenu,
+    roles: &[RoleMenuRole],
+) -> OshiroResult<(Embed, Vec<Component>)> {
+    let label = |r: &RoleMenuRole| {
+        r.label.clone().unwrap_or_else(|| {
+            Id::new_checked(r.role_id as u64)
+                .and_then(|id| cache.role(id).map(|role| role.resource().name.clone()))
+                .unwrap_or_else(|| r.role_id.to_string())
+        })
+    };
+    let mentions: Vec<String> = roles.iter().map(|r| format!("<@&{}>", r.role_id)).collect();
+    let mut notes = Vec::new();
+    if let Some(max) = menu.max_selections {
+        notes.push(t!(locale, "rolemenu-footer-max", max = max));
+    }
+    if menu.required_role_id.is_some() {
+        notes.push(t!(locale, "rolemenu-footer-required"));
+    }
+    let mut embed = EmbedBuilder::new()
+        .title(menu.title.clone())
+        .color(0x3498db)
+        .description(mentions.join("\n"));
+    if !notes.is_empty() {
+        embed = embed.footer(EmbedFooterBuilder::new(notes.join(" · ")));
+    }
+    let embed = embed.validate()?.build();
+
+    let components = if menu.kind == "select" {
+        let max = menu
+            .max_selections
+            .map_or(roles.len(), |m| (m as usize).min(roles.len()));
+        vec![Component::ActionRow(ActionRow {
+            components: vec![Component::SelectMenu(SelectMenu {
+                custom_id: format!("{}:{}", PREFIX, menu.menu_id),
+                disabled: false,
+                max_values: Some(max as u8),
+                min_values: Some(0),
+                options: roles
+                    .iter()
+                    .map(|r| SelectMenuOption {
+                        default: false,
+                        description: None,
+                        emoji: None,
+                        label: label(r),
+                        value: r.role_id.to_string(),
+                    })
+                    .collect(),
+                placeholder: Some(t!(locale, "rolemenu-placeholder")),
+            })],
+        })]
+    } else {
+        roles
+            .chunks(BUTTONS_PER_ROW)
+            .map(|row| {
+                Component::ActionRow(ActionRow {
+                    components: row
+                        .iter()
+                        .map(|r| {
+                            Component::Button(Button {
+                                custom_id: Some(format!(
+                                    "{}:{}:{}",
+                                    PREFIX, menu.menu_id, r.role_id
+                                )),
+                                disabled: false,
+                                emoji: None,
+                                label: Some(label(r)),
+                                style: ButtonStyle::Secondary,
+                                url: None,
+                            })
+                        })
+                        .collect(),
+                })
+            })
+            .collect()
+    };
+    Ok((embed, components))
+}
+
+/// A button toggles its role. A select sets the member's roles from the menu to exactly what
+/// was picked. Either way the member is told what changed, only they see it.
+async fn menu_used(ctx: CommandContext) -> OshiroResult {
+    let locale = ctx.locale().await;
+    let interaction = ctx.slash.as_ref().expect("component interaction");
+    let (custom_id, values) = match &interaction.data {
+        Some(InteractionData::MessageComponent(data)) => {
+            (data.custom_id.clone(), data.values.clone())
+        }
+        _ => return Ok(()),
+    };
+    let (guild_id, member) = match (interaction.guild_id, &interaction.member) {
+        (Some(g), Some(m)) => (g, m),
+        _ => return Ok(()),
+    };
+    let user = match ctx.author_id() {
+        Some(u) => u,
+        None => return Ok(()),
+    };
+    let mut parts = custom_id.split(':').skip(1);
+    let menu_id: Option<i64> = parts.next().and_then(|m| m.parse().ok());
+    let button: Option<i64> = parts.next().and_then(|r| r.parse().ok());
+
+    let db = ctx.db().await;
+    let menu = match menu_id {
+        Some(m) => db::role_menu(&db, guild_id, m).await?,
+        None => None,
+    };
+    let menu = match menu {
+        Some(m) => m,
+        None => return ctx.reply_ephemeral(&t!(locale, "rolemenu-gone")).await,
+    };
+    let roles: Vec<i64> = db::role_menu_roles(&db, menu.menu_id)
+        .await?
+        .into_iter()
+        .map(|r| r.role_id)
+        .collect();
+
+    if let Some(required) = menu.required_role_id {
+        if !member.roles.iter().any(|r| db::id(*r) == required) {
+            let role = format!("<@&{}>", required);
+            return ctx
+                .reply_ephemeral(&t!(locale, "rolemenu-needs-role", role = role))
+                .await;
+        }
+    }
+
+    let has: Vec<i64> = member
+        .roles
+        .iter()
+        .map(|r| db::id(*r))
+        .filter(|r| roles.contains(r))
+        .collect();
+    let max = menu.max_selections.map(|m| m as usize);
+    let (add, remove): (Vec<i64>, Vec<i64>) = match button {
+        Some(role) => {
+            if !roles.contains(&role) {
+                return ctx.reply_ephemeral(&t!(locale, "rolemenu-gone")).await;
+            }
+            if has.contains(&role) {
+                (Vec::new(), vec![role])
+            } else if max.map_or(false, |m| has.len() >= m) {
+                return ctx
+                    .reply_ephemeral(&t!(locale, "rolemenu-max", max = max.unwrap_or_default()))
+                    .await;
+            } else {
+                (vec![role], Vec::new())
+            }
+        }
+        None => {
+            let picked: Vec<i64> = values
+                .iter()
+                .filter_map(|v| v.parse().ok())
+                .filter(|r| roles.contains(r))
+                .collect();
+            if max.map_or(false, |m| picked.len() > m) {
+                return ctx
+                    .reply_ephemeral(&t!(locale, "rolemenu-max", max = max.unwrap_or_default()))
+                    .await;
+            }
+            (
+                picked
+                    .iter()
+                    .filter(|r| !has.contains(r))
+                    .copied()
+                    .collect(),
+                has.iter()
+                    .filter(|r| !picked.contains(r))
+                    .copied()
+                    .collect(),
+            )
+        }
+    };
+
+    if add.is_empty() && remove.is_empty() {
+        return ctx.reply_ephemeral(&t!(locale, "rolemenu-unchanged")).await;
+    }
+    // one request for the whole change, a role at a time could outlast the interaction
+    let roles: Vec<Id<RoleMarker>> = member
+        .roles
+        .iter()
+        .copied()
+        .filter(|r| !remove.contains(&db::id(*r)))
+        .chain(add.iter().filter_map(|r| Id::new_checked(*r as u64)))
+        .collect();
+    let http = Arc::clone(&ctx.oshiro.lock().await.http);
+    let result = http
+        .update_guild_member(guild_id, user)
+        .roles(&roles)
+        .reason("role menu")?
+        .await;
+    let mentions =
+        |roles: &[i64]| -> Vec<String> { roles.iter().map(|r| format!("<@&{}>", r)).collect() };
+    let (added, removed, failed) = match result {
+        Ok(_) => (mentions(&add), mentions(&remove), Vec::new()),
+        Err(e) => {
+            tracing::warn!(
+                "Role menu {} could not change the roles of {}: {}",
+                menu.menu_id,
+                user,
+                e
+            );
+            let failed = add.iter().chain(&remove).copied().collect::<Vec<i64>>();
+            (Vec::new(), Vec::new(), mentions(&failed))
+        }
+    };
+
+    let mut lines = Vec::new();
+    if !added.is_empty() {
+        lines.push(t!(locale, "rolemenu-added", roles = added.join(", ")));
+    }
+    if !removed.is_empty() {
+        lines.push(t!(locale, "rolemenu-removed", roles = removed.join(", ")));
+    }
+    if !failed.is_empty() {
+        lines.push(t!(locale, "rolemenu-failed", roles = failed.join(", ")));
+    }
+    if lines.is_empty() {
+        lines.push(t!(locale, "rolemenu-unchanged"));
+    }
+    ctx.reply_ephemeral(&lines.join("\n")).await
+}
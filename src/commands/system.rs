@@ -1,22 +1,184 @@
+use std::collections::HashMap;
+
 use tokio::time;
+use twilight_model::{
+    application::command::{Command, CommandOption, CommandOptionType, CommandType},
+    id::Id,
+};
 
 use crate::{
-    cmd::{CommandContext, OshiroResult},
+    cmd::{CommandContext, CommandInstance, OshiroResult},
+    command,
     helper::{get_cdn_guild_asset, Timer},
-    slash::{self, message},
+    module::Module,
+    slash::{self, message, CommandGroup, CommandWrapper},
+    t,
 };
 use twilight_util::{builder::embed::*, snowflake::Snowflake};
 
 use heim::{process, units, memory::memory};
 
+pub struct System;
+
+impl Module for System {
+    fn name(&self) -> &'static str {
+        "system"
+    }
+
+    fn description(&self) -> &'static str {
+        "ping, stats and server info"
+    }
+
+    fn commands(&self) -> Vec<CommandInstance> {
+        vec![
+            command!(hi_echo, "hi", "will respond with hi"),
+            command!(ping, "ping", "just 4 fun"),
+            command!(guild_info, "guild_info", "get info about the guild"),
+        ]
+    }
+
+    fn slash_commands(&self) -> HashMap<String, CommandWrapper> {
+        let mut commands = HashMap::new();
+        commands.insert(
+            "ping".to_string(),
+            CommandWrapper {
+                command: Command {
+                    application_id: None,
+                    default_member_permissions: None,
+                    dm_permission: Some(true),
+                    description: "Get the current ping to Discord".to_owned(),
+                    description_localizations: None,
+                    guild_id: None,
+                    id: None,
+                    kind: CommandType::ChatInput,
+                    name: "ping".to_owned(),
+                    name_localizations: None,
+                    options: vec![],
+                    version: Id::new(1),
+                    nsfw: None,
+                },
+                function: Some(Box::new(move |ctx| Box::pin(ping(ctx)))),
+                subcommands: None,
+            },
+        );
+        commands.insert(
+            "stats".to_string(),
+            CommandWrapper {
+                command: Command {
+                    application_id: None,
+                    default_member_permissions: None,
+                    dm_permission: Some(true),
+                    description: "Server statistics".to_owned(),
+                    description_localizations: None,
+                    guild_id: None,
+                    id: None,
+                    kind: CommandType::ChatInput,
+                    name: "stats".to_owned(),
+                    name_localizations: None,
+                    options: vec![],
+                    version: Id::new(1),
+                    nsfw: None,
+                },
+                function: Some(Box::new(move |ctx| Box::pin(stats(ctx)))),
+                subcommands: None,
+            },
+        );
+        commands.insert(
+            "server".to_string(),
+            CommandWrapper {
+                command: Command {
+                    application_id: None,
+                    default_member_permissions: None,
+                    dm_permission: Some(true),
+                    description: "Server statistics".to_owned(),
+                    description_localizations: None,
+                    guild_id: None,
+                    id: None,
+                    kind: CommandType::ChatInput,
+                    name: "server".to_owned(),
+                    name_localizations: None,
+                    options: vec![CommandOption {
+                        name: "info".to_string(),
+                        options: None,
+                        autocomplete: None,
+                        channel_types: None,
+                        choices: None,
+                        description: "Get info about the server".to_string(),
+                        description_localizations: None,
+                        kind: CommandOptionType::SubCommand,
+                        max_length: None,
+                        max_value: None,
+                        min_length: None,
+                        min_value: None,
+                        name_localizations: None,
+                        required: None,
+                    }],
+                    version: Id::new(1),
+                    nsfw: None,
+                },
+                function: None,
+                subcommands: Some({
+                    let mut subcommands: HashMap<String, CommandGroup> = HashMap::new();
+                    subcommands.insert(
+                        "info".to_string(),
+                        CommandGroup::Command(Box::new(move |ctx| Box::pin(guild_info(ctx)))),
+                    );
+                    subcommands
+                }),
+            },
+        );
+        commands
+    }
+}
+
+async fn hi_echo(ctx: CommandContext) -> OshiroResult<()> {
+    let locale = ctx.locale().await;
+    if ctx.command_type == crate::cmd::CommandType::TEXT {
+        let msg = ctx.msg.expect("is text");
+
+        let a = msg.author;
+        ctx.oshiro
+            .lock()
+            .await
+            .http
+            .create_message(msg.channel_id)
+            .content(&format!(
+                "{}\n```{}```",
+                t!(locale, "hi", name = a.name.as_str()),
+                simd_json::to_string_pretty(&a)?
+            ))?
+            .await?;
+    } else {
+        let slash = ctx.slash.expect("is slash command");
+        let user = slash.user.expect("slash command has user");
+        ctx.oshiro
+            .lock()
+            .await
+            .interaction()
+            .create_response(
+                slash.id,
+                &slash.token,
+                &message(&format!(
+                    "{}\n```{}```",
+                    t!(locale, "hi", name = user.name.as_str()),
+                    simd_json::to_string_pretty(&user)?
+                )),
+            )
+            .await?;
+    }
+    Ok(())
+}
+
 pub async fn ping(ctx: CommandContext) -> OshiroResult<()> {
+    // looked up first, it needs the lock
+    let locale = ctx.locale().await;
     let oshi = ctx.oshiro.lock().await;
     let timer = Timer::new();
 
     // send first message
     if let Some(slash) = &ctx.slash {
         oshi.interaction()
-            .create_response(slash.id, &slash.token, &message("ayup"))
+            .create_response(slash.id, &slash.token, &message(&t!(locale, "ping-ack")))
             .await?;
     }
     // text command - we don't need a response with slash commands as
@@ -25,7 +187,7 @@ pub async fn ping(ctx: CommandContext) -> OshiroResult<()> {
         Some(
             oshi.http
                 .create_message(msg.channel_id)
-                .content("ping!!!")?
+                .content(&t!(locale, "ping-sending"))?
                 .await?,
         )
     } else {
@@ -35,25 +197,33 @@ pub async fn ping(ctx: CommandContext) -> OshiroResult<()> {
     let msg_ms = timer.elapsed_ms();
 
     let mut shard_latencies = vec![];
-    for i in 0..oshi.shard_latency.len() {
-        let avg = match oshi.shard_latency[i].average() {
-            Some(x) => format!(
-                "{:.3}",
-                x.as_secs() as f64 / 1000.0 + f64::from(x.subsec_nanos()) * 1e-6
-            ),
-            None => "N/A".to_string(),
+    for shard in oshi.shards.snapshot() {
+        let avg = match shard.average_latency() {
+            Some(x) => format!("{:.3}", x.as_secs_f64() * 1000.0),
+            None => continue,
         };
-        if avg != "N/A" {
-            shard_latencies.push(format!(
-                "shard id {:} - ws avg: {}ms - beats: {}",
-                i,
-                avg,
-                oshi.shard_latency[i].periods()
-            ))
-        }
+        // how far back the averaged samples go
+        let window = shard
+            .history
+            .front()
+            .map_or(0, |s| s.at.elapsed().as_secs());
+        shard_latencies.push(t!(
+            locale,
+            "ping-shard",
+            id = shard.id.number(),
+            avg = avg,
+            window = window,
+            beats = shard.heartbeats,
+            state = format!("{:?}", shard.state),
+        ))
     }
 
-    let update = format!("pong!\nhttp: {}ms\n{}", msg_ms, shard_latencies.join("\n"));
+    let update = format!(
+        "{}\n{}\n{}",
+        t!(locale, "ping-pong"),
+        t!(locale, "ping-http", ms = msg_ms),
+        shard_latencies.join("\n")
+    );
 
     if let Some(sent_msg) = sent_msg {
         // if sent via text command
@@ -76,6 +246,7 @@ pub async fn ping(ctx: CommandContext) -> OshiroResult<()> {
 }
 
 pub async fn stats(ctx: CommandContext) -> OshiroResult {
+    let locale = ctx.locale().await;
     let oshi = ctx.oshiro.lock().await;
     let platform = heim::host::platform().await?;
     let process = process::current().await.unwrap();
@@ -87,53 +258,74 @@ pub async fn stats(ctx: CommandContext) -> OshiroResult {
     time::sleep(time::Duration::from_millis(100)).await;
 
     let cpu_2 = process.cpu_usage().await.unwrap();
+    let shards = oshi.shards.snapshot();
 
     let embed = EmbedBuilder::new()
         .title("oshiro")
-        .field(
-            EmbedFieldBuilder::new(
-                "system",
-                format!(
-                    "running on {} {} ({})",
-                    platform.system(),
-                    platform.release(),
-                    platform.hostname(),
-                ),
-            )
-        )
-        .field(
-            EmbedFieldBuilder::new(
-                "cpu",
-                format!(
-                    "{} cores, {}% usage",
-                    num_cpus::get(),
-                    (cpu_2 - cpu_1).get::<units::ratio::percent>().round(),
-                ),
-            )
-        )
-        .field(
-            EmbedFieldBuilder::new(
-                "memory",
-                format!(
-                    "{:.2} MB used, {:.2} GB total",
-                    process.memory().await.unwrap().rss().get::<units::information::megabyte>(),
-                    memory.total().get::<units::information::gigabyte>(),
+        .field(EmbedFieldBuilder::new(
+            t!(locale, "stats-system"),
+            t!(
+                locale,
+                "stats-system-value",
+                os = platform.system(),
+                release = platform.release(),
+                host = platform.hostname(),
+            ),
+        ))
+        .field(EmbedFieldBuilder::new(
+            t!(locale, "stats-cpu"),
+            t!(
+                locale,
+                "stats-cpu-value",
+                cores = num_cpus::get(),
+                usage = (cpu_2 - cpu_1)
+                    .get::<units::ratio::percent>()
+                    .round()
+                    .to_string(),
+            ),
+        ))
+        .field(EmbedFieldBuilder::new(
+            t!(locale, "stats-memory"),
+            t!(
+                locale,
+                "stats-memory-value",
+                used = format!(
+                    "{:.2}",
+                    process
+                        .memory()
+                        .await
+                        .unwrap()
+                        .rss()
+                        .get::<units::information::megabyte>()
                 ),
-            )
-        )
-        .field(
-            EmbedFieldBuilder::new(
-                "cache",
-                format!(
-                    "{} guilds, {} users, {} channels",
-                    oshi.cache.stats().guilds(),
-                    oshi.cache.stats().users(),
-                    oshi.cache.stats().channels(),
+                total = format!(
+                    "{:.2}",
+                    memory.total().get::<units::information::gigabyte>()
                 ),
-            )
-        )
+            ),
+        ))
+        .field(EmbedFieldBuilder::new(
+            t!(locale, "stats-cache"),
+            t!(
+                locale,
+                "stats-cache-value",
+                guilds = oshi.cache.stats().guilds(),
+                users = oshi.cache.stats().users(),
+                channels = oshi.cache.stats().channels(),
+            ),
+        ))
+        .field(EmbedFieldBuilder::new(
+            t!(locale, "stats-shards"),
+            t!(
+                locale,
+                "stats-shards-value",
+                running = shards.len(),
+                total = shards.first().map_or(0, |s| s.id.total()),
+                ready = shards.iter().filter(|s| s.ready).count(),
+            ),
+        ))
         .image(ImageSource::url("https://i.imgur.com/V6whkQN.png")?)
-        .footer(EmbedFooterBuilder::new("running on tiny horse"))
+        .footer(EmbedFooterBuilder::new(t!(locale, "stats-footer")))
         .validate()?
         .build();
     let embeds = vec![embed];
@@ -156,6 +348,7 @@ pub async fn stats(ctx: CommandContext) -> OshiroResult {
 }
 
 pub async fn guild_info(ctx: CommandContext) -> OshiroResult {
+    let locale = ctx.locale().await;
     let oshi = ctx.oshiro.lock().await;
 
     // get the guild id
@@ -219,7 +412,7 @@ pub async fn guild_info(ctx: CommandContext) -> OshiroResult {
     };
 
     let embed = embed.field(EmbedFieldBuilder::new(
-        "owner",
+        t!(locale, "guild-owner"),
         format!("{}{} (<@{}>)", owner.name, owner_discrim, owner.id),
     ));
 
@@ -233,33 +426,34 @@ pub async fn guild_info(ctx: CommandContext) -> OshiroResult {
         })
     });
     let embed = embed.field(EmbedFieldBuilder::new(
-        "channels",
-        format!("{} (incl categories)", ccount),
+        t!(locale, "guild-channels"),
+        t!(locale, "guild-channels-value", count = ccount),
     ));
 
     // member count
     let embed = embed.field(EmbedFieldBuilder::new(
-        "members",
+        t!(locale, "guild-members"),
         match guild.approximate_member_count {
             Some(count) => format!("{}", count),
-            None => "could not fetch".to_string(),
+            None => t!(locale, "guild-members-unknown"),
         },
     ));
 
     // emoji and sticker count
     let embed = embed.field(EmbedFieldBuilder::new(
-        "emojis and stickers",
-        format!(
-            "{} emojis\n{} stickers",
-            guild.emojis.len(),
-            guild.stickers.len()
+        t!(locale, "guild-emojis"),
+        t!(
+            locale,
+            "guild-emojis-value",
+            emojis = guild.emojis.len(),
+            stickers = guild.stickers.len()
         ),
     ));
 
     // features
     let disp: Vec<String> = guild.features.iter().map(|f| format!("{:?}", f)).collect();
     let embed = embed.field(EmbedFieldBuilder::new(
-        "features",
+        t!(locale, "guild-features"),
         format!("```{}```",disp.join("\n")),
     ));
 
@@ -0,0 +1,512 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use sqlx::SqlitePool;
+use tokio::sync::Mutex;
+use twilight_cache_inmemory::InMemoryCache;
+use twilight_http::Client;
+use twilight_model::{
+    application::command::CommandType as SlashCommandType,
+    channel::message::Embed,
+    gateway::payload::incoming::{MemberAdd, MemberRemove},
+    guild::Permissions,
+    id::{
+        marker::{ChannelMarker, GuildMarker},
+        Id,
+    },
+    user::User,
+};
+use twilight_util::{
+    builder::{
+        command::{CommandBuilder, StringBuilder, SubCommandBuilder},
+        embed::{EmbedBuilder, ImageSource},
+    },
+    snowflake::Snowflake,
+};
+
+use crate::{
+    cmd::{CommandContext, CommandInstance, OshiroResult},
+    command,
+    commands::admin::check_admin,
+    ctx::OshiroContext,
+    helper::{channel_in_guild, format_duration, hash_to_cdn_user_asset, UserAssetType},
+    listener,
+    listener::Listener,
+    module::{self, ConfigField, ConfigKind, Module},
+    slash::{self, CommandGroup, CommandWrapper},
+    t,
+};
+
+/// Which message `welcome test` renders
+const PREVIEWS: &[&str] = &["welcome", "goodbye", "dm"];
+
+/// Greets members that join and sees off the ones that leave, with templates set per guild
+pub struct Welcome;
+
+impl Module for Welcome {
+    fn name(&self) -> &'static str {
+        "welcome"
+    }
+
+    fn description(&self) -> &'static str {
+        "welcome and goodbye messages"
+    }
+
+    fn commands(&self) -> Vec<CommandInstance> {
+        vec![command!(
+            welcome_text,
+            "welcome",
+            "preview the welcome, goodbye or DM message on yourself"
+        )]
+    }
+
+    fn slash_commands(&self) -> HashMap<String, CommandWrapper> {
+        let command = CommandBuilder::new(
+            "welcome",
+            "Welcome and goodbye messages",
+            SlashCommandType::ChatInput,
+        )
+        .dm_permission(false)
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .option(
+            SubCommandBuilder::new("test", "Preview a message with yourself as the member").option(
+                StringBuilder::new("message", "Which message, welcome by default")
+                    .choices(PREVIEWS.iter().map(|p| (*p, *p))),
+            ),
+        )
+        .build();
+
+        let mut subcommands: HashMap<String, CommandGroup> = HashMap::new();
+        subcommands.insert(
+            "test".to_string(),
+            CommandGroup::Command(Box::new(move |ctx| Box::pin(welcome_test_slash(ctx)))),
+        );
+
+        let mut commands = HashMap::new();
+        commands.insert(
+            "welcome".to_string(),
+            CommandWrapper {
+                command,
+                function: None,
+                subcommands: Some(subcommands),
+            },
+        );
+        commands
+    }
+
+    fn listeners(&self) -> Vec<Listener> {
+        vec![
+            listener!(MemberAdd, "welcome", member_joined),
+            listener!(MemberRemove, "goodbye", member_left),
+        ]
+    }
+
+    fn config_schema(&self) -> Vec<ConfigField> {
+        vec![
+            ConfigField {
+                key: "welcome_channel",
+                description: "channel new members are welcomed in, off if not set",
+                kind: ConfigKind::Channel,
+                default: None,
+            },
+            ConfigField {
+                key: "welcome_message",
+                description: "the welcome, with {user}, {user.mention}, {guild}, {member_count} and {account_age}",
+                kind: ConfigKind::Text,
+                default: Some("Welcome to {guild}, {user.mention}!"),
+            },
+            ConfigField {
+                key: "goodbye_channel",
+                description: "channel leaving members are seen off in, off if not set",
+                kind: ConfigKind::Channel,
+                default: None,
+            },
+            ConfigField {
+                key: "goodbye_message",
+                description: "the goodbye, with the same placeholders as welcome_message",
+                kind: ConfigKind::Text,
+                default: Some("{user} left {guild}."),
+            },
+            ConfigField {
+                key: "embed",
+                description: "send the messages as embeds with the member's avatar",
+                kind: ConfigKind::Bool,
+                default: Some("false"),
+            },
+            ConfigField {
+                key: "dm_message",
+                description: "also DM new members this, with the same placeholders. off if not set",
+                kind: ConfigKind::Text,
+                default: None,
+            },
+        ]
+    }
+}
+
+/// What the placeholders are filled in with
+struct Placeholders<'a> {
+    user: &'a User,
+    guild: String,
+    member_count: Option<u64>,
+}
+
+impl Placeholders<'_> {
+    fn value(&self, key: &str) -> Option<String> {
+        Some(match key {
+            "user" => self.user.name.clone(),
+            "user.mention" => format!("<@{}>", self.user.id),
+            "guild" => self.guild.clone(),
+            "member_count" => self
+                .member_count
+                .map_or_else(|| "?".to_string(), |c| c.to_string()),
+            "account_age" => {
+                let now = chrono::Utc::now().timestamp_millis();
+                let age = (now - self.user.id.timestamp()).max(0) as u64;
+                format_duration(Duration::from_millis(age))
+            }
+            _ => return None,
+        })
+    }
+
+    /// Fill in `{placeholder}`s. Anything in braces that isn't one is left as it is.
+    fn render(&self, template: &str) -> String {
+        let mut out = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            out.push_str(&rest[..start]);
+            let after = &rest[start..];
+            match after
+                .find('}')
+                .and_then(|end| self.value(&after[1..end]).map(|v| (end, v)))
+            {
+                Some((end, value)) => {
+                    out.push_str(&value);
+                    rest = &after[end + 1..];
+                }
+                None => {
+                    out.push('{');
+                    rest = &after[1..];
+                }
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+/// A rendered message, as plain text or as an embed
+enum Rendered {
+    Text(String),
+    Embed(Embed),
+}
+
+impl Rendered {
+    fn new(text: String, user: &User, embed: bool) -> OshiroResult<Self> {
+        if !embed {
+            return Ok(Rendered::Text(text));
+        }
+        let mut builder = EmbedBuilder::new().color(0x2ecc71).description(text);
+        if let Some(avatar) = user.avatar {
+            builder = builder.thumbnail(ImageSource::url(hash_to_cdn_user_asset(
+                UserAssetType::Avatar,
+                &user.id.to_string(),
+                &avatar.to_string(),
+            ))?);
+        }
+        Ok(Rendered::Embed(builder.validate()?.build()))
+    }
+
+    async fn send(&self, http: &Client, channel: Id<ChannelMarker>) -> OshiroResult {
+        match self {
+            Rendered::Text(text) => http.create_message(channel).content(text)?.await?,
+            Rendered::Embed(embed) => {
+                http.create_message(channel)
+                    .embeds(&[embed.clone()])?
+                    .await?
+            }
+        };
+        Ok(())
+    }
+}
+
+async fn member_joined(member: Box<MemberAdd>, ctx: Arc<Mutex<OshiroContext>>) -> OshiroResult {
+    let guild_id = member.guild_id;
+    let (http, cache, db, placeholders) = {
+        let oshi = ctx.lock().await;
+        let placeholders = placeholders(&oshi, guild_id, &member.user);
+        (
+            Arc::clone(&oshi.http),
+            Arc::clone(&oshi.cache),
+            oshi.db.clone(),
+            placeholders,
+        )
+    };
+    let embed = setting(&db, guild_id, "embed").await?.as_deref() == Some("true");
+
+    if let Some(channel) = channel(&http, &cache, &db, guild_id, "welcome_channel").await? {
+        let template = setting(&db, guild_id, "welcome_message")
+            .await?
+            .unwrap_or_default();
+        let message = Rendered::new(placeholders.render(&template), &member.user, embed)?;
+        if let Err(e) = message.send(&http, channel).await {
+            tracing::warn!(
+                "Could not welcome {} in {}: {}",
+                member.user.id,
+                guild_id,
+                e
+            );
+        }
+    }
+
+    // no DMs for bots, they can't be opened
+    if member.user.bot {
+        return Ok(());
+    }
+    if let Some(template) = setting(&db, guild_id, "dm_message").await? {
+        let message = Rendered::new(placeholders.render(&template), &member.user, embed)?;
+        let sent = match http.create_private_channel(member.user.id).await {
+            Ok(r) => message.send(&http, r.model().await?.id).await,
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e) = sent {
+            tracing::debug!("Could not DM the welcome to {}: {}", member.user.id, e);
+        }
+    }
+    Ok(())
+}
+
+async fn member_left(member: MemberRemove, ctx: Arc<Mutex<OshiroContext>>) -> OshiroResult {
+    let guild_id = member.guild_id;
+    let (http, cache, db, placeholders) = {
+        let oshi = ctx.lock().await;
+        let placeholders = placeholders(&oshi, guild_id, &member.user);
+        (
+            Arc::clone(&oshi.http),
+            Arc::clone(&oshi.cache),
+            oshi.db.clone(),
+            placeholders,
+        )
+    };
+    let channel = match channel(&http, &cache, &db, guild_id, "goodbye_channel").await? {
+        Some(c) => c,
+        None => return Ok(()),
+    };
+    let embed = setting(&db, guild_id, "embed").await?.as_deref() == Some("true");
+    let template = setting(&db, guild_id, "goodbye_message")
+        .await?
+        .unwrap_or_default();
+    let message = Rendered::new(placeholders.render(&template), &member.user, embed)?;
+    if let Err(e) = message.send(&http, channel).await {
+        tracing::warn!(
+            "Could not say goodbye to {} in {}: {}",
+            member.user.id,
+            guild_id,
+            e
+        );
+    }
+    Ok(())
+}
+
+async fn setting(
+    db: &SqlitePool,
+    guild_id: Id<GuildMarker>,
+    key: &str,
+) -> OshiroResult<Option<String>> {
+    module::setting(db, guild_id, &Welcome, key).await
+}
+
+/// A channel setting, as long as it's still a channel of the guild
+async fn channel(
+    http: &Client,
+    cache: &InMemoryCache,
+    db: &SqlitePool,
+    guild_id: Id<GuildMarker>,
+    key: &str,
+) -> OshiroResult<Option<Id<ChannelMarker>>> {
+    let channel = match setting(db, guild_id, key)
+        .await?
+        .and_then(|c| c.parse().ok())
+    {
+        Some(c) => c,
+        None => return Ok(None),
+    };
+    if !channel_in_guild(http, cache, channel, guild_id).await {
+        tracing::warn!("The {} of {} is set to a channel outside it", key, guild_id);
+        return Ok(None);
+    }
+    Ok(Some(channel))
+}
+
+fn placeholders<'a>(
+    oshi: &OshiroContext,
+    guild_id: Id<GuildMarker>,
+    user: &'a User,
+) -> Placeholders<'a> {
+    let guild = oshi.cache.guild(guild_id);
+    Placeholders {
+        user,
+        guild: guild
+            .as_ref()
+            .map_or_else(|| guild_id.to_string(), |g| g.name().to_string()),
+        member_count: guild.and_then(|g| g.member_count()),
+    }
+}
+
+async fn welcome_text(ctx: CommandContext) -> OshiroResult {
+    if !check_admin(&ctx).await? {
+        return Ok(());
+    }
+    let stripped = ctx.stripped.clone().unwrap_or_default();
+    let args: Vec<&str> = stripped.split_whitespace().collect();
+    match args.first().copied() {
+        None | Some("test") => welcome_test(&ctx, args.get(1).copied()).await,
+        Some(other) => {
+            ctx.reply(&t!(
+                ctx.locale().await,
+                "welcome-unknown-subcommand",
+                name = other
+            ))
+            .await
+        }
+    }
+}
+
+async fn welcome_test_slash(ctx: CommandContext) -> OshiroResult {
+    if !check_admin(&ctx).await? {
+        return Ok(());
+    }
+    let which = ctx
+        .slash
+        .as_ref()
+        .and_then(|s| slash::option_string(s, "message"));
+    welcome_test(&ctx, which.as_deref()).await
+}
+
+/// Render a message against whoever ran the command and reply with it, in the form it'd be
+/// sent in
+async fn welcome_test(ctx: &CommandContext, which: Option<&str>) -> OshiroResult {
+    let guild_id = ctx.guild_id().expect("checked by check_admin");
+    let locale = ctx.locale().await;
+    let which = which.unwrap_or("welcome").to_lowercase();
+    let key = match which.as_str() {
+        "welcome" => "welcome_message",
+        "goodbye" => "goodbye_message",
+        "dm" => "dm_message",
+        _ => {
+            return ctx
+                .reply(&t!(
+                    locale,
+                    "welcome-unknown-message",
+                    name = which.as_str()
+                ))
+                .await
+        }
+    };
+    let user = match author(ctx) {
+        Some(u) => u.clone(),
+        None => return Ok(()),
+    };
+    let (db, placeholders) = {
+        let oshi = ctx.oshiro.lock().await;
+        (oshi.db.clone(), placeholders(&oshi, guild_id, &user))
+    };
+    let template = match setting(&db, guild_id, key).await? {
+        Some(t) => t,
+        None => return ctx.reply(&t!(locale, "welcome-dm-off")).await,
+    };
+    let embed = setting(&db, guild_id, "embed").await?.as_deref() == Some("true");
+    match Rendered::new(placeholders.render(&template), &user, embed)? {
+        Rendered::Text(text) => ctx.reply(&text).await,
+        Rendered::Embed(embed) => ctx.reply_embeds(vec![embed]).await,
+    }
+}
+
+/// The user that ran the command
+fn author(ctx: &CommandContext) -> Option<&User> {
+    match &ctx.slash {
+        Some(slash) => slash
+            .member
+            .as_ref()
+            .and_then(|m| m.user.as_ref())
+            .or(slash.user.as_ref()),
+        None => ctx.msg.as_ref().map(|m| &m.author),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user() -> User {
+        serde_json::from_value(serde_json::json!({
+            "id": "80351110224678912",
+            "username": "nelly",
+            "discriminator": "0",
+            "avatar": null,
+        }))
+        .unwrap()
+    }
+
+    fn placeholders(user: &User) -> Placeholders<'_> {
+        Placeholders {
+            user,
+            guild: "Oshiro".to_string(),
+            member_count: Some(42),
+        }
+    }
+
+    #[test]
+    fn fills_in_placeholders() {
+        let user = user();
+        assert_eq!(
+            placeholders(&user)
+                .render("Hi {user.mention}, welcome to {guild}! You're #{member_count}."),
+            "Hi <@80351110224678912>, welcome to Oshiro! You're #42."
+        );
+        assert_eq!(placeholders(&user).render("{user}{user}"), "nellynelly");
+    }
+
+    #[test]
+    fn unknown_member_count() {
+        let user = user();
+        let placeholders = Placeholders {
+            member_count: None,
+            ..placeholders(&user)
+        };
+        assert_eq!(placeholders.render("#{member_count}"), "#?");
+    }
+
+    #[test]
+    fn leaves_unknown_keys() {
+        let user = user();
+        assert_eq!(
+            placeholders(&user).render("{nope} {User} {user}"),
+            "{nope} {User} nelly"
+        );
+    }
+
+    #[test]
+    fn leaves_stray_braces() {
+        let user = user();
+        let placeholders = placeholders(&user);
+        assert_eq!(placeholders.render("{"), "{");
+        assert_eq!(placeholders.render("}"), "}");
+        assert_eq!(placeholders.render("a { b"), "a { b");
+        assert_eq!(placeholders.render("{user"), "{user");
+        assert_eq!(placeholders.render("} {user} {"), "} nelly {");
+    }
+
+    #[test]
+    fn nested_braces() {
+        let user = user();
+        let placeholders = placeholders(&user);
+        assert_eq!(placeholders.render("{{user}}"), "{nelly}");
+        assert_eq!(placeholders.render("{user {guild}}"), "{user Oshiro}");
+    }
+
+    #[test]
+    fn no_placeholders() {
+        let user = user();
+        assert_eq!(placeholders(&user).render(""), "");
+        assert_eq!(placeholders(&user).render("Welcome!"), "Welcome!");
+    }
+}
@@ -0,0 +1,979 @@
+use std::{
+    collections::HashMap,
+    env, fmt, fs, io,
+    net::SocketAddr,
+    ops::Range,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+use serde::Deserialize;
+use twilight_cache_inmemory::ResourceType;
+use twilight_gateway::{EventTypeFlags, Intents};
+use twilight_model::id::{
+    marker::{GuildMarker, UserMarker},
+    Id,
+};
+
+/// Path used when `OSHIRO_CONFIG` isn't set
+pub const DEFAULT_CONFIG_PATH: &str = "oshiro.toml";
+
+/// The bot's configuration.
+///
+/// Values are layered: built-in defaults, then the TOML file, then environment variables.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub bot: BotConfig,
+    pub commands: CommandsConfig,
+    pub reload: ReloadConfig,
+    pub gateway: GatewayConfig,
+    pub sharding: ShardingConfig,
+    pub cache: CacheConfig,
+    pub logging: LoggingConfig,
+    pub storage: StorageConfig,
+    pub server: ServerConfig,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BotConfig {
+    pub token: String,
+    pub prefix: String,
+    pub owners: Vec<Id<UserMarker>>,
+    /// Bots whose messages are still handled as commands
+    pub good_bots: Vec<Id<UserMarker>>,
+    /// If set, slash commands are registered to this guild only instead of globally
+    pub dev_guild: Option<Id<GuildMarker>>,
+    /// Where the owner-only slash commands are registered. Without it they're text only.
+    pub admin_guild: Option<Id<GuildMarker>>,
+    /// Leave blacklisted guilds when they're blacklisted, and whenever they add the bot back
+    pub leave_blacklisted_guilds: bool,
+}
+
+impl Default for BotConfig {
+    fn default() -> Self {
+        BotConfig {
+            token: String::new(),
+            prefix: "!".to_string(),
+            owners: Vec::new(),
+            good_bots: Vec::new(),
+            dev_guild: None,
+            admin_guild: None,
+            leave_blacklisted_guilds: false,
+        }
+    }
+}
+
+/// Commands and modules switched off everywhere, on top of what each guild turns off
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CommandsConfig {
+    pub disabled_modules: Vec<String>,
+    pub disabled_commands: Vec<String>,
+    /// Seconds a user has to wait between uses of any one command, 0 for no wait
+    pub cooldown: u64,
+    /// Per-command overrides of `cooldown`, keyed by (top level) command name
+    pub cooldowns: HashMap<String, u64>,
+}
+
+impl CommandsConfig {
+    pub fn cooldown(&self, command: &str) -> Duration {
+        Duration::from_secs(*self.cooldowns.get(command).unwrap_or(&self.cooldown))
+    }
+}
+
+/// Watching the config file and applying changes without a restart
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReloadConfig {
+    pub watch: bool,
+    /// Seconds between checks of the file's modification time
+    pub interval: u64,
+}
+
+impl Default for ReloadConfig {
+    fn default() -> Self {
+        ReloadConfig {
+            watch: true,
+            interval: 5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GatewayConfig {
+    pub intents: Vec<String>,
+    pub events: Vec<String>,
+    /// Seconds between shard monitor samples
+    pub sample_interval: u64,
+    /// How many samples of each shard's latency to keep
+    pub latency_history: usize,
+    /// Append every gateway dispatch to this file, for `oshiro replay`
+    pub record: Option<PathBuf>,
+}
+
+impl Default for GatewayConfig {
+    fn default() -> Self {
+        GatewayConfig {
+            intents: names(&[
+                "GUILDS",
+                "GUILD_MEMBERS",
+                "GUILD_MESSAGES",
+                "DIRECT_MESSAGES",
+                "MESSAGE_CONTENT",
+            ]),
+            events: names(&[
+                "CHANNEL_CREATE",
+                "CHANNEL_DELETE",
+                "CHANNEL_UPDATE",
+                "GUILD_CREATE",
+                "GUILD_DELETE",
+                "GUILD_UPDATE",
+                "INTERACTION_CREATE",
+                "MEMBER_ADD",
+                "MEMBER_REMOVE",
+                "MEMBER_UPDATE",
+                "MEMBER_CHUNK",
+                "MESSAGE_CREATE",
+                "MESSAGE_DELETE",
+                "MESSAGE_DELETE_BULK",
+                "MESSAGE_UPDATE",
+                "READY",
+                "ROLE_CREATE",
+                "ROLE_DELETE",
+                "ROLE_UPDATE",
+                "THREAD_CREATE",
+                "THREAD_DELETE",
+                "THREAD_UPDATE",
+                "UNAVAILABLE_GUILD",
+                "USER_UPDATE",
+            ]),
+            sample_interval: 30,
+            latency_history: 20,
+            record: None,
+        }
+    }
+}
+
+impl GatewayConfig {
+    pub fn intents(&self) -> Intents {
+        fold_flags(&self.intents, INTENTS, Intents::empty())
+    }
+
+    pub fn event_types(&self) -> EventTypeFlags {
+        fold_flags(&self.events, EVENT_TYPES, EventTypeFlags::empty())
+    }
+}
+
+/// Which shards this process runs, so the gateway can be split over several processes.
+/// Every process must be given the same `total`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ShardingConfig {
+    /// Total shards across every process, or the gateway's recommendation if unset
+    pub total: Option<u64>,
+    /// First shard run by this process
+    pub start: u64,
+    /// One past the last shard run by this process, or `total` if unset
+    pub end: Option<u64>,
+    /// Seconds between checks of the recommended shard count, 0 to never reshard.
+    /// Only used when `total` is unset.
+    pub reshard_interval: u64,
+    /// How many shards the recommendation has to grow by before resharding
+    pub reshard_threshold: u64,
+}
+
+impl Default for ShardingConfig {
+    fn default() -> Self {
+        ShardingConfig {
+            total: None,
+            start: 0,
+            end: None,
+            reshard_interval: 3600,
+            reshard_threshold: 1,
+        }
+    }
+}
+
+impl ShardingConfig {
+    pub fn range(&self, total: u64) -> Range<u64> {
+        self.start..self.end.unwrap_or(total)
+    }
+
+    /// Resharding needs every shard in this process, with the count left to the gateway
+    pub fn reshard(&self) -> bool {
+        self.total.is_none() && self.reshard_interval > 0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheConfig {
+    pub resource_types: Vec<String>,
+    pub message_cache_size: usize,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        CacheConfig {
+            resource_types: names(&[
+                "MESSAGE",
+                "CHANNEL",
+                "MEMBER",
+                "GUILD",
+                "ROLE",
+                "USER",
+                "USER_CURRENT",
+            ]),
+            message_cache_size: 512,
+        }
+    }
+}
+
+impl CacheConfig {
+    pub fn resource_types(&self) -> ResourceType {
+        fold_flags(&self.resource_types, RESOURCE_TYPES, ResourceType::empty())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoggingConfig {
+    /// A `tracing_subscriber::EnvFilter` directive, e.g. `info,oshiro=debug`
+    pub level: String,
+    pub format: LogFormat,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        LoggingConfig {
+            level: "info".to_string(),
+            format: LogFormat::Full,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    Full,
+    Compact,
+    Pretty,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "full" => Ok(LogFormat::Full),
+            "compact" => Ok(LogFormat::Compact),
+            "pretty" => Ok(LogFormat::Pretty),
+            other => Err(format!("unknown log format `{}`", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageConfig {
+    pub path: PathBuf,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        StorageConfig {
+            path: PathBuf::from("oshiro.db"),
+        }
+    }
+}
+
+/// The embedded HTTP server for metrics and health checks
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub enabled: bool,
+    pub bind: SocketAddr,
+    /// Seconds without a heartbeat acknowledgement before a shard stops counting as ready
+    pub heartbeat_timeout: u64,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            enabled: true,
+            bind: SocketAddr::from(([0, 0, 0, 0], 9000)),
+            heartbeat_timeout: 120,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Read {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    Invalid(Vec<String>),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Read { path, source } => {
+                write!(
+                    f,
+                    "could not read config file {}: {}",
+                    path.display(),
+                    source
+                )
+            }
+            ConfigError::Parse { path, source } => {
+                write!(
+                    f,
+                    "could not parse config file {}:\n{}",
+                    path.display(),
+                    source
+                )
+            }
+            ConfigError::Invalid(problems) => {
+                writeln!(f, "invalid configuration ({} problem(s)):", problems.len())?;
+                for p in problems {
+                    writeln!(f, "  - {}", p)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// A module as far as the config is concerned, for checking the names in `commands`
+pub struct KnownModule {
+    pub name: &'static str,
+    pub can_disable: bool,
+    /// Text, slash and admin slash command names
+    pub commands: Vec<String>,
+}
+
+impl Config {
+    /// Load the config from `OSHIRO_CONFIG` (or `oshiro.toml`), apply env overrides and validate it
+    /// against the modules there are.
+    ///
+    /// A missing file is only an error if its path was given explicitly.
+    pub fn load(modules: &[KnownModule]) -> Result<Self, ConfigError> {
+        let (path, explicit) = Self::path();
+        Self::load_from(&path, explicit, modules)
+    }
+
+    /// Where the config file is, and whether that was given explicitly
+    pub fn path() -> (PathBuf, bool) {
+        match env::var("OSHIRO_CONFIG") {
+            Ok(p) => (PathBuf::from(p), true),
+            Err(_) => (PathBuf::from(DEFAULT_CONFIG_PATH), false),
+        }
+    }
+
+    pub fn load_from(
+        path: &Path,
+        required: bool,
+        modules: &[KnownModule],
+    ) -> Result<Self, ConfigError> {
+        let mut config = match fs::read_to_string(path) {
+            Ok(s) => toml::from_str(&s).map_err(|source| ConfigError::Parse {
+                path: path.to_owned(),
+                source,
+            })?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound && !required => Config::default(),
+            Err(source) => {
+                return Err(ConfigError::Read {
+                    path: path.to_owned(),
+                    source,
+                })
+            }
+        };
+
+        let mut problems = config.apply_env();
+        problems.extend(config.validate(modules));
+        if problems.is_empty() {
+            Ok(config)
+        } else {
+            Err(ConfigError::Invalid(problems))
+        }
+    }
+
+    /// Apply environment variable overrides, returning any values that failed to parse.
+    fn apply_env(&mut self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        // the old variable names still work
+        if let Some(v) = var("DISCORD_TOKEN").or_else(|| var("OSHIRO_TOKEN")) {
+            self.bot.token = v;
+        }
+        if let Some(v) = var("PREFIX").or_else(|| var("OSHIRO_PREFIX")) {
+            self.bot.prefix = v;
+        }
+        if let Some(v) = var("OSHIRO_OWNERS") {
+            self.bot.owners = parse_list("OSHIRO_OWNERS", &v, &mut problems);
+        }
+        if let Some(v) = var("OSHIRO_GOOD_BOTS") {
+            self.bot.good_bots = parse_list("OSHIRO_GOOD_BOTS", &v, &mut problems);
+        }
+        if let Some(v) = var("OSHIRO_DEV_GUILD") {
+            self.bot.dev_guild = parse_env("OSHIRO_DEV_GUILD", &v, &mut problems);
+        }
+        if let Some(v) = var("OSHIRO_ADMIN_GUILD") {
+            self.bot.admin_guild = parse_env("OSHIRO_ADMIN_GUILD", &v, &mut problems);
+        }
+        if let Some(v) = var("OSHIRO_LEAVE_BLACKLISTED") {
+            if let Some(leave) = parse_env("OSHIRO_LEAVE_BLACKLISTED", &v, &mut problems) {
+                self.bot.leave_blacklisted_guilds = leave;
+            }
+        }
+        if let Some(v) = var("OSHIRO_DISABLED_MODULES") {
+            self.commands.disabled_modules = split(&v);
+        }
+        if let Some(v) = var("OSHIRO_DISABLED_COMMANDS") {
+            self.commands.disabled_commands = split(&v);
+        }
+        if let Some(v) = var("OSHIRO_COOLDOWN") {
+            if let Some(cooldown) = parse_env("OSHIRO_COOLDOWN", &v, &mut problems) {
+                self.commands.cooldown = cooldown;
+            }
+        }
+        if let Some(v) = var("OSHIRO_WATCH_CONFIG") {
+            if let Some(watch) = parse_env("OSHIRO_WATCH_CONFIG", &v, &mut problems) {
+                self.reload.watch = watch;
+            }
+        }
+        if let Some(v) = var("OSHIRO_INTENTS") {
+            self.gateway.intents = split(&v);
+        }
+        if let Some(v) = var("OSHIRO_EVENTS") {
+            self.gateway.events = split(&v);
+        }
+        if let Some(v) = var("OSHIRO_RECORD") {
+            self.gateway.record = Some(PathBuf::from(v));
+        }
+        if let Some(v) = var("OSHIRO_SHARD_TOTAL") {
+            self.sharding.total = parse_env("OSHIRO_SHARD_TOTAL", &v, &mut problems);
+        }
+        if let Some(v) = var("OSHIRO_SHARD_START") {
+            if let Some(start) = parse_env("OSHIRO_SHARD_START", &v, &mut problems) {
+                self.sharding.start = start;
+            }
+        }
+        if let Some(v) = var("OSHIRO_SHARD_END") {
+            self.sharding.end = parse_env("OSHIRO_SHARD_END", &v, &mut problems);
+        }
+        if let Some(v) = var("OSHIRO_RESHARD_INTERVAL") {
+            if let Some(interval) = parse_env("OSHIRO_RESHARD_INTERVAL", &v, &mut problems) {
+                self.sharding.reshard_interval = interval;
+            }
+        }
+        if let Some(v) = var("OSHIRO_MESSAGE_CACHE_SIZE") {
+            if let Some(size) = parse_env("OSHIRO_MESSAGE_CACHE_SIZE", &v, &mut problems) {
+                self.cache.message_cache_size = size;
+            }
+        }
+        if let Some(v) = var("OSHIRO_LOG").or_else(|| var("RUST_LOG")) {
+            self.logging.level = v;
+        }
+        if let Some(v) = var("OSHIRO_LOG_FORMAT") {
+            if let Some(format) = parse_env("OSHIRO_LOG_FORMAT", &v, &mut problems) {
+                self.logging.format = format;
+            }
+        }
+        if let Some(v) = var("OSHIRO_STORAGE_PATH") {
+            self.storage.path = PathBuf::from(v);
+        }
+        if let Some(v) = var("OSHIRO_SERVER_ENABLED") {
+            if let Some(enabled) = parse_env("OSHIRO_SERVER_ENABLED", &v, &mut problems) {
+                self.server.enabled = enabled;
+            }
+        }
+        if let Some(v) = var("OSHIRO_SERVER_BIND") {
+            if let Some(bind) = parse_env("OSHIRO_SERVER_BIND", &v, &mut problems) {
+                self.server.bind = bind;
+            }
+        }
+
+        problems
+    }
+
+    /// Sections that differ from `new` and are only read at start-up. Everything else is read
+    /// as it's used, or applied by `reload::apply`.
+    pub fn restart_needed(&self, new: &Config) -> Vec<&'static str> {
+        let mut restart = Vec::new();
+        if self.bot.token != new.bot.token {
+            restart.push("bot.token");
+        }
+        if self.gateway != new.gateway {
+            restart.push("gateway");
+        }
+        if self.sharding != new.sharding {
+            restart.push("sharding");
+        }
+        if self.cache != new.cache {
+            restart.push("cache");
+        }
+        if self.logging.format != new.logging.format {
+            restart.push("logging.format");
+        }
+        if self.storage != new.storage {
+            restart.push("storage");
+        }
+        if self.server != new.server {
+            restart.push("server");
+        }
+        restart
+    }
+
+    /// Check the config for problems, returning every one found rather than stopping at the first.
+    /// Module and command names are checked against `modules`.
+    pub fn validate(&self, modules: &[KnownModule]) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        if self.bot.token.trim().is_empty() {
+            problems.push(
+                "bot.token is empty: set it in the config file or with DISCORD_TOKEN".to_string(),
+            );
+        }
+        if self.bot.prefix.is_empty() {
+            problems.push("bot.prefix must not be empty".to_string());
+        } else if self.bot.prefix.contains(char::is_whitespace) {
+            problems.push(format!(
+                "bot.prefix `{}` must not contain whitespace",
+                self.bot.prefix
+            ));
+        }
+
+        check_names(
+            "gateway.intents",
+            &self.gateway.intents,
+            INTENTS,
+            &mut problems,
+        );
+        check_names(
+            "gateway.events",
+            &self.gateway.events,
+            EVENT_TYPES,
+            &mut problems,
+        );
+        check_names(
+            "cache.resource_types",
+            &self.cache.resource_types,
+            RESOURCE_TYPES,
+            &mut problems,
+        );
+
+        if self.gateway.sample_interval == 0 {
+            problems.push("gateway.sample_interval must be more than 0".to_string());
+        }
+        if self.gateway.latency_history == 0 {
+            problems.push("gateway.latency_history must be more than 0".to_string());
+        }
+        match self.sharding.total {
+            Some(0) => problems.push("sharding.total must be more than 0".to_string()),
+            Some(total) => {
+                let range = self.sharding.range(total);
+                if range.start >= range.end || range.end > total {
+                    problems.push(format!(
+                        "sharding range {}..{} must be a non-empty part of 0..{}",
+                        range.start, range.end, total
+                    ));
+                }
+            }
+            // the recommended count can change between processes, so a split needs a fixed total
+            None if self.sharding.start != 0 || self.sharding.end.is_some() => problems
+                .push("sharding.start and sharding.end need sharding.total to be set".to_string()),
+            None => {}
+        }
+        check_module_names(&self.commands, modules, &mut problems);
+        if self.reload.interval == 0 {
+            problems.push("reload.interval must be more than 0".to_string());
+        }
+        if self.sharding.reshard_threshold == 0 {
+            problems.push("sharding.reshard_threshold must be more than 0".to_string());
+        }
+        if self.gateway.intents.is_empty() {
+            problems.push("gateway.intents must enable at least one intent".to_string());
+        }
+        if self.cache.resource_types().contains(ResourceType::MESSAGE)
+            && self.cache.message_cache_size == 0
+        {
+            problems.push(
+                "cache.message_cache_size is 0 but MESSAGE is in cache.resource_types".to_string(),
+            );
+        }
+
+        if let Err(e) = tracing_subscriber::EnvFilter::try_new(&self.logging.level) {
+            problems.push(format!(
+                "logging.level `{}` is invalid: {}",
+                self.logging.level, e
+            ));
+        }
+
+        if self.server.heartbeat_timeout == 0 {
+            problems.push("server.heartbeat_timeout must be more than 0".to_string());
+        }
+
+        if self.storage.path.as_os_str().is_empty() {
+            problems.push("storage.path must not be empty".to_string());
+        }
+
+        problems
+    }
+}
+
+fn names(n: &[&str]) -> Vec<String> {
+    n.iter().map(|s| s.to_string()).collect()
+}
+
+fn var(key: &str) -> Option<String> {
+    env::var(key).ok().filter(|v| !v.is_empty())
+}
+
+fn split(v: &str) -> Vec<String> {
+    v.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn parse_env<T: FromStr>(key: &str, v: &str, problems: &mut Vec<String>) -> Option<T>
+where
+    T::Err: fmt::Display,
+{
+    match v.trim().parse() {
+        Ok(x) => Some(x),
+        Err(e) => {
+            problems.push(format!("{} `{}` is invalid: {}", key, v, e));
+            None
+        }
+    }
+}
+
+fn parse_list<T: FromStr>(key: &str, v: &str, problems: &mut Vec<String>) -> Vec<T>
+where
+    T::Err: fmt::Display,
+{
+    split(v)
+        .iter()
+        .filter_map(|s| parse_env(key, s, problems))
+        .collect()
+}
+
+fn check_names<T>(field: &str, names: &[String], table: &[(&str, T)], problems: &mut Vec<String>) {
+    for name in names {
+        if !table.iter().any(|(n, _)| n.eq_ignore_ascii_case(name)) {
+            problems.push(format!("{}: unknown name `{}`", field, name));
+        }
+    }
+}
+
+/// Global switches have to name modules and commands that exist and can be turned off
+fn check_module_names(
+    commands: &CommandsConfig,
+    modules: &[KnownModule],
+    problems: &mut Vec<String>,
+) {
+    for name in &commands.disabled_modules {
+        match modules.iter().find(|m| m.name == name) {
+            Some(m) if !m.can_disable => problems.push(format!(
+                "commands.disabled_modules: `{}` can't be turned off",
+                name
+            )),
+            Some(_) => {}
+            None => problems.push(format!(
+                "commands.disabled_modules: unknown module `{}`",
+                name
+            )),
+        }
+    }
+
+    let module_of = |name: &str| {
+        modules
+            .iter()
+            .find(|m| m.commands.iter().any(|c| c == name))
+    };
+    for name in &commands.disabled_commands {
+        match module_of(name) {
+            Some(m) if !m.can_disable => problems.push(format!(
+                "commands.disabled_commands: `{}` is part of `{}`, which can't be turned off",
+                name, m.name
+            )),
+            Some(_) => {}
+            None => problems.push(format!(
+                "commands.disabled_commands: unknown command `{}`",
+                name
+            )),
+        }
+    }
+    for name in commands.cooldowns.keys() {
+        if module_of(name).is_none() {
+            problems.push(format!("commands.cooldowns: unknown command `{}`", name));
+        }
+    }
+}
+
+/// Combine named flags, skipping unknown names (those are reported by `validate`)
+fn fold_flags<T>(names: &[String], table: &[(&str, T)], empty: T) -> T
+where
+    T: Copy + std::ops::BitOr<Output = T>,
+{
+    names
+        .iter()
+        .filter_map(|name| {
+            table
+                .iter()
+                .find(|(n, _)| n.eq_ignore_ascii_case(name))
+                .map(|(_, f)| *f)
+        })
+        .fold(empty, |acc, f| acc | f)
+}
+
+const INTENTS: &[(&str, Intents)] = &[
+    ("GUILDS", Intents::GUILDS),
+    ("GUILD_MEMBERS", Intents::GUILD_MEMBERS),
+    ("GUILD_MODERATION", Intents::GUILD_MODERATION),
+    (
+        "GUILD_EMOJIS_AND_STICKERS",
+        Intents::GUILD_EMOJIS_AND_STICKERS,
+    ),
+    ("GUILD_INTEGRATIONS", Intents::GUILD_INTEGRATIONS),
+    ("GUILD_WEBHOOKS", Intents::GUILD_WEBHOOKS),
+    ("GUILD_INVITES", Intents::GUILD_INVITES),
+    ("GUILD_VOICE_STATES", Intents::GUILD_VOICE_STATES),
+    ("GUILD_PRESENCES", Intents::GUILD_PRESENCES),
+    ("GUILD_MESSAGES", Intents::GUILD_MESSAGES),
+    ("GUILD_MESSAGE_REACTIONS", Intents::GUILD_MESSAGE_REACTIONS),
+    ("GUILD_MESSAGE_TYPING", Intents::GUILD_MESSAGE_TYPING),
+    ("DIRECT_MESSAGES", Intents::DIRECT_MESSAGES),
+    (
+        "DIRECT_MESSAGE_REACTIONS",
+        Intents::DIRECT_MESSAGE_REACTIONS,
+    ),
+    ("DIRECT_MESSAGE_TYPING", Intents::DIRECT_MESSAGE_TYPING),
+    ("MESSAGE_CONTENT", Intents::MESSAGE_CONTENT),
+    ("GUILD_SCHEDULED_EVENTS", Intents::GUILD_SCHEDULED_EVENTS),
+    (
+        "AUTO_MODERATION_CONFIGURATION",
+        Intents::AUTO_MODERATION_CONFIGURATION,
+    ),
+    (
+        "AUTO_MODERATION_EXECUTION",
+        Intents::AUTO_MODERATION_EXECUTION,
+    ),
+];
+
+const EVENT_TYPES: &[(&str, EventTypeFlags)] = &[
+    ("BAN_ADD", EventTypeFlags::BAN_ADD),
+    ("BAN_REMOVE", EventTypeFlags::BAN_REMOVE),
+    ("CHANNEL_CREATE", EventTypeFlags::CHANNEL_CREATE),
+    ("CHANNEL_DELETE", EventTypeFlags::CHANNEL_DELETE),
+    ("CHANNEL_PINS_UPDATE", EventTypeFlags::CHANNEL_PINS_UPDATE),
+    ("CHANNEL_UPDATE", EventTypeFlags::CHANNEL_UPDATE),
+    ("GUILD_CREATE", EventTypeFlags::GUILD_CREATE),
+    ("GUILD_DELETE", EventTypeFlags::GUILD_DELETE),
+    ("GUILD_EMOJIS_UPDATE", EventTypeFlags::GUILD_EMOJIS_UPDATE),
+    ("GUILD_UPDATE", EventTypeFlags::GUILD_UPDATE),
+    ("INTERACTION_CREATE", EventTypeFlags::INTERACTION_CREATE),
+    ("INVITE_CREATE", EventTypeFlags::INVITE_CREATE),
+    ("INVITE_DELETE", EventTypeFlags::INVITE_DELETE),
+    ("MEMBER_ADD", EventTypeFlags::MEMBER_ADD),
+    ("MEMBER_REMOVE", EventTypeFlags::MEMBER_REMOVE),
+    ("MEMBER_UPDATE", EventTypeFlags::MEMBER_UPDATE),
+    ("MEMBER_CHUNK", EventTypeFlags::MEMBER_CHUNK),
+    ("MESSAGE_CREATE", EventTypeFlags::MESSAGE_CREATE),
+    ("MESSAGE_DELETE", EventTypeFlags::MESSAGE_DELETE),
+    ("MESSAGE_DELETE_BULK", EventTypeFlags::MESSAGE_DELETE_BULK),
+    ("MESSAGE_UPDATE", EventTypeFlags::MESSAGE_UPDATE),
+    ("PRESENCE_UPDATE", EventTypeFlags::PRESENCE_UPDATE),
+    ("REACTION_ADD", EventTypeFlags::REACTION_ADD),
+    ("REACTION_REMOVE", EventTypeFlags::REACTION_REMOVE),
+    ("READY", EventTypeFlags::READY),
+    ("RESUMED", EventTypeFlags::RESUMED),
+    ("ROLE_CREATE", EventTypeFlags::ROLE_CREATE),
+    ("ROLE_DELETE", EventTypeFlags::ROLE_DELETE),
+    ("ROLE_UPDATE", EventTypeFlags::ROLE_UPDATE),
+    ("THREAD_CREATE", EventTypeFlags::THREAD_CREATE),
+    ("THREAD_DELETE", EventTypeFlags::THREAD_DELETE),
+    ("THREAD_LIST_SYNC", EventTypeFlags::THREAD_LIST_SYNC),
+    ("THREAD_MEMBER_UPDATE", EventTypeFlags::THREAD_MEMBER_UPDATE),
+    (
+        "THREAD_MEMBERS_UPDATE",
+        EventTypeFlags::THREAD_MEMBERS_UPDATE,
+    ),
+    ("THREAD_UPDATE", EventTypeFlags::THREAD_UPDATE),
+    ("TYPING_START", EventTypeFlags::TYPING_START),
+    ("UNAVAILABLE_GUILD", EventTypeFlags::UNAVAILABLE_GUILD),
+    ("USER_UPDATE", EventTypeFlags::USER_UPDATE),
+    ("VOICE_STATE_UPDATE", EventTypeFlags::VOICE_STATE_UPDATE),
+];
+
+const RESOURCE_TYPES: &[(&str, ResourceType)] = &[
+    ("CHANNEL", ResourceType::CHANNEL),
+    ("EMOJI", ResourceType::EMOJI),
+    ("GUILD", ResourceType::GUILD),
+    ("INTEGRATION", ResourceType::INTEGRATION),
+    ("MEMBER", ResourceType::MEMBER),
+    ("MESSAGE", ResourceType::MESSAGE),
+    ("PRESENCE", ResourceType::PRESENCE),
+    ("REACTION", ResourceType::REACTION),
+    ("ROLE", ResourceType::ROLE),
+    ("STAGE_INSTANCE", ResourceType::STAGE_INSTANCE),
+    ("STICKER", ResourceType::STICKER),
+    ("USER", ResourceType::USER),
+    ("USER_CURRENT", ResourceType::USER_CURRENT),
+    ("VOICE_STATE", ResourceType::VOICE_STATE),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn modules() -> Vec<KnownModule> {
+        vec![
+            KnownModule {
+                name: "admin",
+                can_disable: false,
+                commands: names(&["modules"]),
+            },
+            KnownModule {
+                name: "info",
+                can_disable: true,
+                commands: names(&["userinfo", "avatar"]),
+            },
+        ]
+    }
+
+    fn with_token() -> Config {
+        let mut config = Config::default();
+        config.bot.token = "token".to_string();
+        config
+    }
+
+    #[test]
+    fn the_file_goes_over_the_defaults() {
+        let config: Config = toml::from_str(
+            r#"
+            [bot]
+            prefix = "?"
+
+            [commands]
+            cooldown = 3
+            cooldowns = { userinfo = 10 }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.bot.prefix, "?");
+        assert_eq!(config.commands.cooldown("avatar"), Duration::from_secs(3));
+        assert_eq!(
+            config.commands.cooldown("userinfo"),
+            Duration::from_secs(10)
+        );
+        // sections and fields that aren't in the file keep their defaults
+        assert_eq!(config.reload, ReloadConfig::default());
+        assert!(config.bot.owners.is_empty());
+    }
+
+    #[test]
+    fn unknown_fields_are_rejected() {
+        assert!(toml::from_str::<Config>("[bot]\ntokne = \"x\"").is_err());
+        assert!(toml::from_str::<Config>("[bots]\ntoken = \"x\"").is_err());
+    }
+
+    // the only test that touches the environment, since tests share it
+    #[test]
+    fn the_environment_goes_over_the_file() {
+        let path = env::temp_dir().join(format!("oshiro-config-test-{}.toml", std::process::id()));
+        fs::write(&path, "[bot]\ntoken = \"file\"\nprefix = \"?\"\n").unwrap();
+        env::remove_var("PREFIX");
+        env::set_var("DISCORD_TOKEN", "env");
+        env::set_var("OSHIRO_PREFIX", "$");
+        env::set_var("OSHIRO_DISABLED_COMMANDS", "userinfo, avatar");
+
+        let config = Config::load_from(&path, true, &modules()).unwrap();
+        assert_eq!(config.bot.token, "env");
+        assert_eq!(config.bot.prefix, "$");
+        assert_eq!(
+            config.commands.disabled_commands,
+            names(&["userinfo", "avatar"])
+        );
+
+        env::set_var("OSHIRO_SHARD_TOTAL", "many");
+        let loaded = Config::load_from(&path, true, &modules());
+        env::remove_var("OSHIRO_SHARD_TOTAL");
+        match loaded {
+            Err(ConfigError::Invalid(problems)) => {
+                assert!(problems.iter().any(|p| p.starts_with("OSHIRO_SHARD_TOTAL")))
+            }
+            other => panic!("expected an invalid config, got {:?}", other.map(|_| ())),
+        }
+
+        fs::remove_file(&path).unwrap();
+        let config = Config::load_from(&path, false, &modules()).unwrap();
+        assert_eq!(config.bot.token, "env");
+        assert!(matches!(
+            Config::load_from(&path, true, &modules()),
+            Err(ConfigError::Read { .. })
+        ));
+
+        env::remove_var("DISCORD_TOKEN");
+        env::remove_var("OSHIRO_PREFIX");
+        env::remove_var("OSHIRO_DISABLED_COMMANDS");
+    }
+
+    #[test]
+    fn the_defaults_only_need_a_token() {
+        assert!(with_token().validate(&modules()).is_empty());
+        let problems = Config::default().validate(&modules());
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].starts_with("bot.token"));
+    }
+
+    #[test]
+    fn every_problem_is_reported() {
+        let mut config = with_token();
+        config.bot.prefix = "o !".to_string();
+        config.gateway.intents.push("GUILD_SPAM".to_string());
+        config.sharding.total = Some(4);
+        config.sharding.start = 4;
+        config.reload.interval = 0;
+        let problems = config.validate(&modules());
+        assert_eq!(problems.len(), 4, "{:?}", problems);
+        assert!(problems[0].starts_with("bot.prefix"));
+        assert!(problems[1].contains("GUILD_SPAM"));
+        assert!(problems[2].starts_with("sharding range 4..4"));
+        assert!(problems[3].starts_with("reload.interval"));
+    }
+
+    #[test]
+    fn disabled_modules_and_commands_have_to_exist_and_be_optional() {
+        let mut config = with_token();
+        config.commands.disabled_modules = names(&["info", "admin", "music"]);
+        config.commands.disabled_commands = names(&["avatar", "modules", "play"]);
+        config.commands.cooldowns.insert("skip".to_string(), 5);
+        config.commands.cooldowns.insert("userinfo".to_string(), 5);
+        assert_eq!(
+            config.validate(&modules()),
+            vec![
+                "commands.disabled_modules: `admin` can't be turned off",
+                "commands.disabled_modules: unknown module `music`",
+                "commands.disabled_commands: `modules` is part of `admin`, which can't be turned off",
+                "commands.disabled_commands: unknown command `play`",
+                "commands.cooldowns: unknown command `skip`",
+            ]
+        );
+    }
+}
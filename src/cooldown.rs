@@ -0,0 +1,37 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use twilight_model::id::{marker::UserMarker, Id};
+
+/// When each user can next use each command, for `commands.cooldown`
+#[derive(Debug, Default)]
+pub struct Cooldowns {
+    until: Mutex<HashMap<(String, Id<UserMarker>), Instant>>,
+}
+
+impl Cooldowns {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a cooldown on a user's use of a command, or return how much is left of the one
+    /// already running
+    pub fn hit(&self, command: &str, user: Id<UserMarker>, cooldown: Duration) -> Option<Duration> {
+        if cooldown.is_zero() {
+            return None;
+        }
+        let now = Instant::now();
+        let mut until = self.until.lock().unwrap();
+        until.retain(|_, at| *at > now);
+
+        let key = (command.to_string(), user);
+        if let Some(at) = until.get(&key) {
+            return Some(*at - now);
+        }
+        until.insert(key, now + cooldown);
+        None
+    }
+}
@@ -1,7 +1,8 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Instant};
 
+use sqlx::SqlitePool;
+use tokio::sync::watch;
 use twilight_cache_inmemory::InMemoryCache;
-use twilight_gateway::Latency;
 use twilight_http::{Client, Response};
 use twilight_model::{
     application::interaction::Interaction,
@@ -9,18 +10,37 @@ use twilight_model::{
     id::{marker::ApplicationMarker, Id}, http::interaction::InteractionResponse,
 };
 
-use crate::{cmd::{CommandFramework, OshiroResult}, slash::message};
+use crate::{
+    cmd::{CommandFramework, OshiroResult},
+    config::Config,
+    cooldown::Cooldowns,
+    shard::ShardMonitor,
+    slash::message,
+};
 
 #[derive(Clone)]
 pub struct OshiroContext {
     pub framework: Arc<CommandFramework>,
     pub http: Arc<Client>,
     pub cache: Arc<InMemoryCache>,
-    pub shard_latency: Vec<Latency>,
     pub app_id: Id<ApplicationMarker>,
+    pub config: Arc<Config>,
+    pub db: SqlitePool,
+    pub shards: Arc<ShardMonitor>,
+    pub cooldowns: Arc<Cooldowns>,
+    /// Whether this process registers slash commands, i.e. runs shard 0
+    pub registers_commands: bool,
+    pub started: Instant,
+    /// Set to true to close every shard and let the event loop finish
+    pub shutdown: Arc<watch::Sender<bool>>,
 }
 
 impl OshiroContext {
+    pub fn request_shutdown(&self) {
+        tracing::info!("Shutting down");
+        self.shutdown.send_replace(true);
+    }
+
     /// Shortcut function for accessing interactions
     pub fn interaction(&self) -> twilight_http::client::InteractionClient<'_> {
         self.http.interaction(self.app_id)
@@ -0,0 +1,731 @@
+use std::{path::Path, str::FromStr};
+
+use sqlx::{
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+    SqlitePool,
+};
+use twilight_model::id::{
+    marker::{ChannelMarker, GuildMarker, MessageMarker, RoleMarker, UserMarker},
+    Id,
+};
+
+use crate::cmd::OshiroResult;
+
+/// Open (creating if needed) the sqlite database and bring its schema up to date
+pub async fn connect(path: &Path) -> OshiroResult<SqlitePool> {
+    let options = SqliteConnectOptions::new()
+        .filename(path)
+        .create_if_missing(true);
+    let pool = SqlitePoolOptions::new()
+        .max_connections(4)
+        .connect_with(options)
+        .await?;
+    sqlx::migrate!("./migrations").run(&pool).await?;
+    Ok(pool)
+}
+
+/// A fresh, empty database that only lives as long as the pool
+pub async fn connect_in_memory() -> OshiroResult<SqlitePool> {
+    // every connection to :memory: is its own database, so there can only be one
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(SqliteConnectOptions::from_str("sqlite::memory:")?)
+        .await?;
+    sqlx::migrate!("./migrations").run(&pool).await?;
+    Ok(pool)
+}
+
+/// sqlite has no unsigned integers, snowflakes fit in an i64 for the foreseeable future
+pub fn id<T>(id: Id<T>) -> i64 {
+    id.get() as i64
+}
+
+pub async fn disabled_modules(
+    db: &SqlitePool,
+    guild_id: Id<GuildMarker>,
+) -> OshiroResult<Vec<String>> {
+    Ok(
+        sqlx::query_scalar("SELECT module FROM disabled_modules WHERE guild_id = ?")
+            .bind(id(guild_id))
+            .fetch_all(db)
+            .await?,
+    )
+}
+
+pub async fn disabled_commands(
+    db: &SqlitePool,
+    guild_id: Id<GuildMarker>,
+) -> OshiroResult<Vec<String>> {
+    Ok(
+        sqlx::query_scalar("SELECT command FROM disabled_commands WHERE guild_id = ?")
+            .bind(id(guild_id))
+            .fetch_all(db)
+            .await?,
+    )
+}
+
+pub async fn set_module_enabled(
+    db: &SqlitePool,
+    guild_id: Id<GuildMarker>,
+    module: &str,
+    enabled: bool,
+) -> OshiroResult<()> {
+    let query = if enabled {
+        "DELETE FROM disabled_modules WHERE guild_id = ? AND module = ?"
+    } else {
+        "INSERT OR IGNORE INTO disabled_modules (guild_id, module) VALUES (?, ?)"
+    };
+    sqlx::query(query)
+        .bind(id(guild_id))
+        .bind(module)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+pub async fn set_command_enabled(
+    db: &SqlitePool,
+    guild_id: Id<GuildMarker>,
+    command: &str,
+    enabled: bool,
+) -> OshiroResult<()> {
+    let query = if enabled {
+        "DELETE FROM disabled_commands WHERE guild_id = ? AND command = ?"
+    } else {
+        "INSERT OR IGNORE INTO disabled_commands (guild_id, command) VALUES (?, ?)"
+    };
+    sqlx::query(query)
+        .bind(id(guild_id))
+        .bind(command)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+pub async fn module_setting(
+    db: &SqlitePool,
+    guild_id: Id<GuildMarker>,
+    module: &str,
+    key: &str,
+) -> OshiroResult<Option<String>> {
+    Ok(sqlx::query_scalar(
+        "SELECT value FROM module_settings WHERE guild_id = ? AND module = ? AND key = ?",
+    )
+    .bind(id(guild_id))
+    .bind(module)
+    .bind(key)
+    .fetch_optional(db)
+    .await?)
+}
+
+pub async fn module_settings(
+    db: &SqlitePool,
+    guild_id: Id<GuildMarker>,
+    module: &str,
+) -> OshiroResult<Vec<(String, String)>> {
+    Ok(
+        sqlx::query_as("SELECT key, value FROM module_settings WHERE guild_id = ? AND module = ?")
+            .bind(id(guild_id))
+            .bind(module)
+            .fetch_all(db)
+            .await?,
+    )
+}
+
+/// Set a module setting, or reset it to its default with `None`
+pub async fn set_module_setting(
+    db: &SqlitePool,
+    guild_id: Id<GuildMarker>,
+    module: &str,
+    key: &str,
+    value: Option<&str>,
+) -> OshiroResult<()> {
+    match value {
+        Some(value) => {
+            sqlx::query(
+                "INSERT INTO module_settings (guild_id, module, key, value) VALUES (?, ?, ?, ?)
+                 ON CONFLICT (guild_id, module, key) DO UPDATE SET value = excluded.value",
+            )
+            .bind(id(guild_id))
+            .bind(module)
+            .bind(key)
+            .bind(value)
+            .execute(db)
+            .await?;
+        }
+        None => {
+            sqlx::query(
+                "DELETE FROM module_settings WHERE guild_id = ? AND module = ? AND key = ?",
+            )
+            .bind(id(guild_id))
+            .bind(module)
+            .bind(key)
+            .execute(db)
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+pub async fn allowed_bots(
+    db: &SqlitePool,
+    guild_id: Id<GuildMarker>,
+) -> OshiroResult<Vec<Id<UserMarker>>> {
+    let ids: Vec<i64> = sqlx::query_scalar("SELECT bot_id FROM allowed_bots WHERE guild_id = ?")
+        .bind(id(guild_id))
+        .fetch_all(db)
+        .await?;
+    Ok(ids
+        .into_iter()
+        .filter_map(|i| Id::new_checked(i as u64))
+        .collect())
+}
+
+pub async fn set_bot_allowed(
+    db: &SqlitePool,
+    guild_id: Id<GuildMarker>,
+    bot_id: Id<UserMarker>,
+    allowed: bool,
+) -> OshiroResult<()> {
+    let query = if allowed {
+        "INSERT OR IGNORE INTO allowed_bots (guild_id, bot_id) VALUES (?, ?)"
+    } else {
+        "DELETE FROM allowed_bots WHERE guild_id = ? AND bot_id = ?"
+    };
+    sqlx::query(query)
+        .bind(id(guild_id))
+        .bind(id(bot_id))
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+/// Channels left out of the message logs. Threads follow their parent.
+pub async fn log_ignored_channels(
+    db: &SqlitePool,
+    guild_id: Id<GuildMarker>,
+) -> OshiroResult<Vec<Id<ChannelMarker>>> {
+    let ids: Vec<i64> =
+        sqlx::query_scalar("SELECT channel_id FROM log_ignored_channels WHERE guild_id = ?")
+            .bind(id(guild_id))
+            .fetch_all(db)
+            .await?;
+    Ok(ids
+        .into_iter()
+        .filter_map(|i| Id::new_checked(i as u64))
+        .collect())
+}
+
+pub async fn set_log_ignored(
+    db: &SqlitePool,
+    guild_id: Id<GuildMarker>,
+    channel_id: Id<ChannelMarker>,
+    ignored: bool,
+) -> OshiroResult<()> {
+    let query = if ignored {
+        "INSERT OR IGNORE INTO log_ignored_channels (guild_id, channel_id) VALUES (?, ?)"
+    } else {
+        "DELETE FROM log_ignored_channels WHERE guild_id = ? AND channel_id = ?"
+    };
+    sqlx::query(query)
+        .bind(id(guild_id))
+        .bind(id(channel_id))
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+/// Roles given to members as they join, to bots with `bots` and to everyone else without
+pub async fn autoroles(
+    db: &SqlitePool,
+    guild_id: Id<GuildMarker>,
+    bots: bool,
+) -> OshiroResult<Vec<Id<RoleMarker>>> {
+    let ids: Vec<i64> =
+        sqlx::query_scalar("SELECT role_id FROM autoroles WHERE guild_id = ? AND bots = ?")
+            .bind(id(guild_id))
+            .bind(bots)
+            .fetch_all(db)
+            .await?;
+    Ok(ids
+        .into_iter()
+        .filter_map(|i| Id::new_checked(i as u64))
+        .collect())
+}
+
+pub async fn set_autorole(
+    db: &SqlitePool,
+    guild_id: Id<GuildMarker>,
+    role_id: Id<RoleMarker>,
+    bots: bool,
+    enabled: bool,
+) -> OshiroResult<()> {
+    let query = if enabled {
+        "INSERT OR IGNORE INTO autoroles (guild_id, role_id, bots) VALUES (?, ?, ?)"
+    } else {
+        "DELETE FROM autoroles WHERE guild_id = ? AND role_id = ? AND bots = ?"
+    };
+    sqlx::query(query)
+        .bind(id(guild_id))
+        .bind(id(role_id))
+        .bind(bots)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+/// What a blacklist entry refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlacklistKind {
+    User,
+    Guild,
+}
+
+impl BlacklistKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BlacklistKind::User => "user",
+            BlacklistKind::Guild => "guild",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "user" => Some(BlacklistKind::User),
+            "guild" | "server" => Some(BlacklistKind::Guild),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct BlacklistEntry {
+    pub kind: String,
+    pub id: i64,
+    pub reason: Option<String>,
+    /// Unix timestamps
+    pub added_at: i64,
+    pub expires_at: Option<i64>,
+}
+
+/// Blacklist a user or guild, replacing the reason and expiry if it already was
+pub async fn add_to_blacklist(
+    db: &SqlitePool,
+    kind: BlacklistKind,
+    id: u64,
+    reason: Option<&str>,
+    expires_at: Option<i64>,
+) -> OshiroResult<()> {
+    sqlx::query(
+        "INSERT INTO blacklist (kind, id, added_at, reason, expires_at) VALUES (?, ?, ?, ?, ?)
+         ON CONFLICT (kind, id) DO UPDATE SET
+            added_at = excluded.added_at, reason = excluded.reason, expires_at = excluded.expires_at",
+    )
+    .bind(kind.as_str())
+    .bind(id as i64)
+    .bind(chrono::Utc::now().timestamp())
+    .bind(reason)
+    .bind(expires_at)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// Returns whether there was anything to remove
+pub async fn remove_from_blacklist(
+    db: &SqlitePool,
+    kind: BlacklistKind,
+    id: u64,
+) -> OshiroResult<bool> {
+    let result = sqlx::query("DELETE FROM blacklist WHERE kind = ? AND id = ?")
+        .bind(kind.as_str())
+        .bind(id as i64)
+        .execute(db)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Every entry that hasn't expired, newest first. Expired entries are cleared out.
+pub async fn blacklist(db: &SqlitePool) -> OshiroResult<Vec<BlacklistEntry>> {
+    let now = chrono::Utc::now().timestamp();
+    sqlx::query("DELETE FROM blacklist WHERE expires_at <= ?")
+        .bind(now)
+        .execute(db)
+        .await?;
+    Ok(sqlx::query_as(
+        "SELECT kind, id, reason, added_at, expires_at FROM blacklist ORDER BY added_at DESC",
+    )
+    .fetch_all(db)
+    .await?)
+}
+
+pub async fn blacklist_entry(
+    db: &SqlitePool,
+    kind: BlacklistKind,
+    id: u64,
+) -> OshiroResult<Option<BlacklistEntry>> {
+    Ok(sqlx::query_as(
+        "SELECT kind, id, reason, added_at, expires_at FROM blacklist
+         WHERE kind = ? AND id = ? AND (expires_at IS NULL OR expires_at > ?)",
+    )
+    .bind(kind.as_str())
+    .bind(id as i64)
+    .bind(chrono::Utc::now().timestamp())
+    .fetch_optional(db)
+    .await?)
+}
+
+/// Whether a user, or the guild they're in, is blacklisted
+pub async fn is_blacklisted(
+    db: &SqlitePool,
+    user_id: Id<UserMarker>,
+    guild_id: Option<Id<GuildMarker>>,
+) -> OshiroResult<bool> {
+    let found: Option<i64> = sqlx::query_scalar(
+        "SELECT 1 FROM blacklist
+         WHERE ((kind = 'user' AND id = ?) OR (kind = 'guild' AND id = ?))
+           AND (expires_at IS NULL OR expires_at > ?)",
+    )
+    .bind(id(user_id))
+    .bind(guild_id.map(id))
+    .bind(chrono::Utc::now().timestamp())
+    .fetch_optional(db)
+    .await?;
+    Ok(found.is_some())
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ModCase {
+    pub case_id: i64,
+    pub action: String,
+    pub user_id: i64,
+    pub moderator_id: i64,
+    pub reason: Option<String>,
+    /// Seconds: how long a timeout lasts, or how far back a ban deleted messages
+    pub duration: Option<i64>,
+    /// Unix timestamp
+    pub created_at: i64,
+    /// Where the case was posted in the mod log, if it was
+    pub log_channel_id: Option<i64>,
+    pub log_message_id: Option<i64>,
+}
+
+const MOD_CASE_COLUMNS: &str = "case_id, action, user_id, moderator_id, reason, duration, \
+    created_at, log_channel_id, log_message_id";
+
+/// Save a case under the guild's next case number. Numbers are never reused, even after
+/// the case with the highest one is deleted.
+pub async fn create_case(
+    db: &SqlitePool,
+    guild_id: Id<GuildMarker>,
+    action: &str,
+    user_id: Id<UserMarker>,
+    moderator_id: Id<UserMarker>,
+    reason: Option<&str>,
+    duration: Option<i64>,
+) -> OshiroResult<ModCase> {
+    let mut tx = db.begin().await?;
+    let case_id: i64 = sqlx::query_scalar(
+        "INSERT INTO mod_case_numbers (guild_id, last_case) VALUES (?, 1)
+         ON CONFLICT (guild_id) DO UPDATE SET last_case = last_case + 1
+         RETURNING last_case",
+    )
+    .bind(id(guild_id))
+    .fetch_one(&mut tx)
+    .await?;
+    let case = sqlx::query_as(&format!(
+        "INSERT INTO mod_cases
+            (guild_id, case_id, action, user_id, moderator_id, reason, duration, created_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+         RETURNING {}",
+        MOD_CASE_COLUMNS
+    ))
+    .bind(id(guild_id))
+    .bind(case_id)
+    .bind(action)
+    .bind(id(user_id))
+    .bind(id(moderator_id))
+    .bind(reason)
+    .bind(duration)
+    .bind(chrono::Utc::now().timestamp())
+    .fetch_one(&mut tx)
+    .await?;
+    tx.commit().await?;
+    Ok(case)
+}
+
+pub async fn mod_case(
+    db: &SqlitePool,
+    guild_id: Id<GuildMarker>,
+    case_id: i64,
+) -> OshiroResult<Option<ModCase>> {
+    Ok(sqlx::query_as(&format!(
+        "SELECT {} FROM mod_cases WHERE guild_id = ? AND case_id = ?",
+        MOD_CASE_COLUMNS
+    ))
+    .bind(id(guild_id))
+    .bind(case_id)
+    .fetch_optional(db)
+    .await?)
+}
+
+/// Every case against a user, newest first
+pub async fn user_cases(
+    db: &SqlitePool,
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+) -> OshiroResult<Vec<ModCase>> {
+    Ok(sqlx::query_as(&format!(
+        "SELECT {} FROM mod_cases WHERE guild_id = ? AND user_id = ? ORDER BY case_id DESC",
+        MOD_CASE_COLUMNS
+    ))
+    .bind(id(guild_id))
+    .bind(id(user_id))
+    .fetch_all(db)
+    .await?)
+}
+
+/// How many cases of one kind a user has
+pub async fn count_cases(
+    db: &SqlitePool,
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+    action: &str,
+) -> OshiroResult<i64> {
+    Ok(sqlx::query_scalar(
+        "SELECT COUNT(*) FROM mod_cases WHERE guild_id = ? AND user_id = ? AND action = ?",
+    )
+    .bind(id(guild_id))
+    .bind(id(user_id))
+    .bind(action)
+    .fetch_one(db)
+    .await?)
+}
+
+/// Returns whether the case exists
+pub async fn set_case_reason(
+    db: &SqlitePool,
+    guild_id: Id<GuildMarker>,
+    case_id: i64,
+    reason: &str,
+) -> OshiroResult<bool> {
+    let result = sqlx::query("UPDATE mod_cases SET reason = ? WHERE guild_id = ? AND case_id = ?")
+        .bind(reason)
+        .bind(id(guild_id))
+        .bind(case_id)
+        .execute(db)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn set_case_log_message(
+    db: &SqlitePool,
+    guild_id: Id<GuildMarker>,
+    case_id: i64,
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+) -> OshiroResult<()> {
+    sqlx::query(
+        "UPDATE mod_cases SET log_channel_id = ?, log_message_id = ?
+         WHERE guild_id = ? AND case_id = ?",
+    )
+    .bind(id(channel_id))
+    .bind(id(message_id))
+    .bind(id(guild_id))
+    .bind(case_id)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// Returns the case that was deleted, if there was one
+pub async fn delete_case(
+    db: &SqlitePool,
+    guild_id: Id<GuildMarker>,
+    case_id: i64,
+) -> OshiroResult<Option<ModCase>> {
+    Ok(sqlx::query_as(&format!(
+        "DELETE FROM mod_cases WHERE guild_id = ? AND case_id = ? RETURNING {}",
+        MOD_CASE_COLUMNS
+    ))
+    .bind(id(guild_id))
+    .bind(case_id)
+    .fetch_optional(db)
+    .await?)
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct RoleMenu {
+    pub menu_id: i64,
+    /// `buttons` or `select`
+    pub kind: String,
+    pub title: String,
+    /// How many of the menu's roles a member can have at once
+    pub max_selections: Option<i64>,
+    /// Only members with this role can use the menu
+    pub required_role_id: Option<i64>,
+    /// Where the menu was posted, if it was
+    pub channel_id: Option<i64>,
+    pub message_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct RoleMenuRole {
+    pub role_id: i64,
+    /// Shown on the button or option instead of the role's name
+    pub label: Option<String>,
+}
+
+const ROLE_MENU_COLUMNS: &str =
+    "menu_id, kind, title, max_selections, required_role_id, channel_id, message_id";
+
+pub async fn create_role_menu(
+    db: &SqlitePool,
+    guild_id: Id<GuildMarker>,
+    kind: &str,
+    title: &str,
+) -> OshiroResult<RoleMenu> {
+    Ok(sqlx::query_as(&format!(
+        "INSERT INTO role_menus (guild_id, kind, title) VALUES (?, ?, ?) RETURNING {}",
+        ROLE_MENU_COLUMNS
+    ))
+    .bind(id(guild_id))
+    .bind(kind)
+    .bind(title)
+    .fetch_one(db)
+    .await?)
+}
+
+pub async fn role_menu(
+    db: &SqlitePool,
+    guild_id: Id<GuildMarker>,
+    menu_id: i64,
+) -> OshiroResult<Option<RoleMenu>> {
+    Ok(sqlx::query_as(&format!(
+        "SELECT {} FROM role_menus WHERE guild_id = ? AND menu_id = ?",
+        ROLE_MENU_COLUMNS
+    ))
+    .bind(id(guild_id))
+    .bind(menu_id)
+    .fetch_optional(db)
+    .await?)
+}
+
+pub async fn role_menus(db: &SqlitePool, guild_id: Id<GuildMarker>) -> OshiroResult<Vec<RoleMenu>> {
+    Ok(sqlx::query_as(&format!(
+        "SELECT {} FROM role_menus WHERE guild_id = ? ORDER BY menu_id",
+        ROLE_MENU_COLUMNS
+    ))
+    .bind(id(guild_id))
+    .fetch_all(db)
+    .await?)
+}
+
+/// A menu's roles, in the order they were added
+pub async fn role_menu_roles(db: &SqlitePool, menu_id: i64) -> OshiroResult<Vec<RoleMenuRole>> {
+    Ok(sqlx::query_as(
+        "SELECT role_id, label FROM role_menu_roles WHERE menu_id = ? ORDER BY rowid",
+    )
+    .bind(menu_id)
+    .fetch_all(db)
+    .await?)
+}
+
+/// Add a role to a menu, or change its label if it's already there
+pub async fn add_role_menu_role(
+    db: &SqlitePool,
+    menu_id: i64,
+    role_id: Id<RoleMarker>,
+    label: Option<&str>,
+) -> OshiroResult<()> {
+    sqlx::query(
+        "INSERT INTO role_menu_roles (menu_id, role_id, label) VALUES (?, ?, ?)
+         ON CONFLICT (menu_id, role_id) DO UPDATE SET label = excluded.label",
+    )
+    .bind(menu_id)
+    .bind(id(role_id))
+    .bind(label)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// Returns whether the role was on the menu
+pub async fn remove_role_menu_role(
+    db: &SqlitePool,
+    menu_id: i64,
+    role_id: Id<RoleMarker>,
+) -> OshiroResult<bool> {
+    let result = sqlx::query("DELETE FROM role_menu_roles WHERE menu_id = ? AND role_id = ?")
+        .bind(menu_id)
+        .bind(id(role_id))
+        .execute(db)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn set_role_menu_max(
+    db: &SqlitePool,
+    menu_id: i64,
+    max_selections: Option<i64>,
+) -> OshiroResult<()> {
+    sqlx::query("UPDATE role_menus SET max_selections = ? WHERE menu_id = ?")
+        .bind(max_selections)
+        .bind(menu_id)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+pub async fn set_role_menu_required(
+    db: &SqlitePool,
+    menu_id: i64,
+    role_id: Option<Id<RoleMarker>>,
+) -> OshiroResult<()> {
+    sqlx::query("UPDATE role_menus SET required_role_id = ? WHERE menu_id = ?")
+        .bind(role_id.map(id))
+        .bind(menu_id)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+pub async fn set_role_menu_message(
+    db: &SqlitePool,
+    menu_id: i64,
+    channel_id: Id<ChannelMarker>,
+    message_id: Id<MessageMarker>,
+) -> OshiroResult<()> {
+    sqlx::query("UPDATE role_menus SET channel_id = ?, message_id = ? WHERE menu_id = ?")
+        .bind(id(channel_id))
+        .bind(id(message_id))
+        .bind(menu_id)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+/// Delete a menu and its roles, returning it if it existed
+pub async fn delete_role_menu(
+    db: &SqlitePool,
+    guild_id: Id<GuildMarker>,
+    menu_id: i64,
+) -> OshiroResult<Option<RoleMenu>> {
+    let mut tx = db.begin().await?;
+    let menu = sqlx::query_as(&format!(
+        "DELETE FROM role_menus WHERE guild_id = ? AND menu_id = ? RETURNING {}",
+        ROLE_MENU_COLUMNS
+    ))
+    .bind(id(guild_id))
+    .bind(menu_id)
+    .fetch_optional(&mut tx)
+    .await?;
+    if menu.is_some() {
+        sqlx::query("DELETE FROM role_menu_roles WHERE menu_id = ?")
+            .bind(menu_id)
+            .execute(&mut tx)
+            .await?;
+    }
+    tx.commit().await?;
+    Ok(menu)
+}
@@ -1,5 +1,11 @@
 use chrono::DateTime;
 use chrono::Utc;
+use twilight_cache_inmemory::InMemoryCache;
+use twilight_http::Client;
+use twilight_model::id::{
+    marker::{ChannelMarker, GuildMarker, RoleMarker, UserMarker},
+    Id,
+};
 
 pub struct Timer {
     start: DateTime<Utc>,
@@ -31,13 +37,62 @@ pub enum UserAssetType {
 
 // hash to discord cdn endpoint for user avatar
 // https://cdn.discordapp.com/avatars/(userid)/(useravatar).png
+// animated hashes start with `a_` and get a .gif instead
 pub fn hash_to_cdn_user_asset(asset_type: UserAssetType, userid: &str, useravatar: &str) -> String {
     let asset_type = match asset_type {
         UserAssetType::Avatar => "avatars",
         UserAssetType::DefaultAvatar => "embed/avatars",
         UserAssetType::Banner => "banners",
     };
-    format!("https://cdn.discordapp.com/{}/{}/{}.png", asset_type, userid, useravatar)
+    format!(
+        "https://cdn.discordapp.com/{}/{}/{}.{}",
+        asset_type,
+        userid,
+        useravatar,
+        extension(useravatar)
+    )
+}
+
+// a member's server avatar
+// https://cdn.discordapp.com/guilds/(guildid)/users/(userid)/avatars/(hash).png
+pub fn hash_to_cdn_member_avatar(guildid: &str, userid: &str, hash: &str) -> String {
+    format!(
+        "https://cdn.discordapp.com/guilds/{}/users/{}/avatars/{}.{}",
+        guildid,
+        userid,
+        hash,
+        extension(hash)
+    )
+}
+
+// the avatar users without one get: by id since usernames lost their discriminators,
+// by discriminator for those that still have one
+// https://cdn.discordapp.com/embed/avatars/(index).png
+pub fn cdn_default_avatar(userid: u64, discriminator: u16) -> String {
+    let index = if discriminator == 0 {
+        (userid >> 22) % 6
+    } else {
+        u64::from(discriminator) % 5
+    };
+    format!("https://cdn.discordapp.com/embed/avatars/{}.png", index)
+}
+
+fn extension(hash: &str) -> &'static str {
+    if hash.starts_with("a_") {
+        "gif"
+    } else {
+        "png"
+    }
+}
+
+/// Ask the cdn for an image `size` pixels wide, which has to be a power of two from 16 to 4096
+pub fn cdn_size(url: &str, size: u16) -> String {
+    format!("{}?size={}", url, size)
+}
+
+/// Whether the cdn takes `size` as an image size
+pub fn valid_cdn_size(size: u64) -> bool {
+    (16..=4096).contains(&size) && size.is_power_of_two()
 }
 
 pub enum GuildAssetType {
@@ -57,4 +112,213 @@ pub fn get_cdn_guild_asset(asset_type: GuildAssetType, guildid: &u64, guildicon:
         GuildAssetType::Banner => "banners",
     };
     format!("https://cdn.discordapp.com/{}/{}/{}.png", asset_type, guildid, guildicon)
-}
\ No newline at end of file
+}
+/// Parses durations like `30m`, `2h`, `1d12h` or `1w`. A bare number is taken as minutes.
+pub fn parse_duration(input: &str) -> Option<std::time::Duration> {
+    let input = input.trim().to_lowercase();
+    if let Ok(minutes) = input.parse::<u64>() {
+        if minutes == 0 {
+            return None;
+        }
+        return Some(std::time::Duration::from_secs(minutes.checked_mul(60)?));
+    }
+
+    let mut total = 0u64;
+    let mut number = String::new();
+    for c in input.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit = match c {
+            's' => 1,
+            'm' => 60,
+            'h' => 60 * 60,
+            'd' => 60 * 60 * 24,
+            'w' => 60 * 60 * 24 * 7,
+            _ => return None,
+        };
+        let n: u64 = number.parse().ok()?;
+        total = total.checked_add(n.checked_mul(unit)?)?;
+        number.clear();
+    }
+    if !number.is_empty() || total == 0 {
+        return None;
+    }
+    Some(std::time::Duration::from_secs(total))
+}
+
+/// The largest two units of a duration, like `1d 12h` or `5m 30s`
+pub fn format_duration(duration: std::time::Duration) -> String {
+    let units = [
+        ("w", 60 * 60 * 24 * 7),
+        ("d", 60 * 60 * 24),
+        ("h", 60 * 60),
+        ("m", 60),
+        ("s", 1),
+    ];
+    let mut left = duration.as_secs();
+    let mut parts = Vec::new();
+    for (name, size) in units {
+        if left >= size {
+            parts.push(format!("{}{}", left / size, name));
+            left %= size;
+        }
+        if parts.len() == 2 {
+            break;
+        }
+    }
+    if parts.is_empty() {
+        "0s".to_string()
+    } else {
+        parts.join(" ")
+    }
+}
+
+/// Accepts a user mention or a raw id
+pub fn parse_user(input: &str) -> Option<Id<UserMarker>> {
+    input
+        .trim_start_matches("<@")
+        .trim_start_matches('!')
+        .trim_end_matches('>')
+        .parse()
+        .ok()
+}
+
+/// Like `str::strip_prefix`, ignoring ASCII case
+pub fn strip_prefix_ignore_case<'a>(input: &'a str, prefix: &str) -> Option<&'a str> {
+    match input.get(..prefix.len()) {
+        Some(start) if start.eq_ignore_ascii_case(prefix) => Some(&input[prefix.len()..]),
+        _ => None,
+    }
+}
+
+/// Cut `input` down to at most `max` characters, ending in an ellipsis if anything was cut
+pub fn truncate(input: &str, max: usize) -> String {
+    if input.chars().count() <= max {
+        return input.to_string();
+    }
+    let mut out: String = input.chars().take(max.saturating_sub(1)).collect();
+    out.push('…');
+    out
+}
+
+/// Whether a channel belongs to the guild, asking Discord when it isn't cached. Settings and
+/// commands only take ids, which could be any channel the bot can see.
+pub async fn channel_in_guild(
+    http: &Client,
+    cache: &InMemoryCache,
+    channel: Id<ChannelMarker>,
+    guild: Id<GuildMarker>,
+) -> bool {
+    if let Some(c) = cache.channel(channel) {
+        return c.guild_id == Some(guild);
+    }
+    match http.channel(channel).await {
+        Ok(response) => response
+            .model()
+            .await
+            .map_or(false, |c| c.guild_id == Some(guild)),
+        Err(_) => false,
+    }
+}
+
+/// Whether a role belongs to the guild, going by the cache, which has every role of the guilds
+/// the bot is in
+pub fn role_in_guild(cache: &InMemoryCache, role: Id<RoleMarker>, guild: Id<GuildMarker>) -> bool {
+    cache.role(role).map_or(false, |r| r.guild_id() == guild)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const MINUTE: u64 = 60;
+    const HOUR: u64 = 60 * MINUTE;
+    const DAY: u64 = 24 * HOUR;
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("30s"), Some(Duration::from_secs(30)));
+        assert_eq!(
+            parse_duration("30m"),
+            Some(Duration::from_secs(30 * MINUTE))
+        );
+        assert_eq!(parse_duration("2h"), Some(Duration::from_secs(2 * HOUR)));
+        assert_eq!(
+            parse_duration("1d12h"),
+            Some(Duration::from_secs(36 * HOUR))
+        );
+        assert_eq!(parse_duration("1w"), Some(Duration::from_secs(7 * DAY)));
+        assert_eq!(
+            parse_duration(" 1H30M "),
+            Some(Duration::from_secs(90 * MINUTE))
+        );
+    }
+
+    #[test]
+    fn bare_numbers_are_minutes() {
+        assert_eq!(parse_duration("90"), Some(Duration::from_secs(90 * MINUTE)));
+    }
+
+    #[test]
+    fn rejects_what_isnt_a_duration() {
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("m"), None);
+        assert_eq!(parse_duration("5x"), None);
+        assert_eq!(parse_duration("spam"), None);
+        assert_eq!(parse_duration("10m5"), None);
+        assert_eq!(parse_duration("0m"), None);
+        assert_eq!(parse_duration("0"), None);
+        assert_eq!(parse_duration("307445734561825861"), None);
+        assert_eq!(parse_duration("99999999999999999999w"), None);
+        assert_eq!(parse_duration("9999999999999999w"), None);
+    }
+
+    #[test]
+    fn formats_the_two_largest_units() {
+        assert_eq!(format_duration(Duration::from_secs(0)), "0s");
+        assert_eq!(format_duration(Duration::from_secs(45)), "45s");
+        assert_eq!(format_duration(Duration::from_secs(90)), "1m 30s");
+        assert_eq!(format_duration(Duration::from_secs(7 * DAY)), "1w");
+        assert_eq!(
+            format_duration(Duration::from_secs(36 * HOUR + 5 * MINUTE)),
+            "1d 12h"
+        );
+    }
+
+    #[test]
+    fn formatted_durations_parse_back() {
+        for text in ["1w", "1d 12h", "2h", "30m", "45s"] {
+            let duration = parse_duration(&text.replace(' ', "")).unwrap();
+            assert_eq!(format_duration(duration), text);
+        }
+    }
+
+    #[test]
+    fn animated_assets_are_gifs() {
+        assert_eq!(
+            hash_to_cdn_user_asset(UserAssetType::Avatar, "1", "a_abc"),
+            "https://cdn.discordapp.com/avatars/1/a_abc.gif"
+        );
+        assert_eq!(
+            hash_to_cdn_user_asset(UserAssetType::Banner, "1", "abc"),
+            "https://cdn.discordapp.com/banners/1/abc.png"
+        );
+        assert_eq!(
+            hash_to_cdn_member_avatar("2", "1", "a_abc"),
+            "https://cdn.discordapp.com/guilds/2/users/1/avatars/a_abc.gif"
+        );
+    }
+
+    #[test]
+    fn cdn_sizes_are_powers_of_two() {
+        assert!(valid_cdn_size(16));
+        assert!(valid_cdn_size(1024));
+        assert!(valid_cdn_size(4096));
+        assert!(!valid_cdn_size(8));
+        assert!(!valid_cdn_size(1000));
+        assert!(!valid_cdn_size(8192));
+    }
+}
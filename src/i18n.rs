@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+
+use fluent_bundle::{concurrent::FluentBundle, FluentArgs, FluentResource};
+use once_cell::sync::Lazy;
+use sqlx::SqlitePool;
+use twilight_model::{
+    application::command::{Command, CommandOption},
+    id::{marker::GuildMarker, Id},
+};
+use unic_langid::LanguageIdentifier;
+
+use crate::{cmd::OshiroResult, db};
+
+/// What we answer in when nothing better is known, and what command definitions are written in
+pub const FALLBACK: &str = "en-US";
+
+/// Every locale with a translation file, named the way Discord names them
+pub const LOCALES: &[&str] = &["en-US", "es-ES"];
+
+const SOURCES: &[(&str, &str)] = &[
+    ("en-US", include_str!("../locales/en-US/oshiro.ftl")),
+    ("es-ES", include_str!("../locales/es-ES/oshiro.ftl")),
+];
+
+static BUNDLES: Lazy<HashMap<&'static str, FluentBundle<FluentResource>>> = Lazy::new(|| {
+    SOURCES
+        .iter()
+        .map(|(locale, source)| (*locale, bundle(locale, source)))
+        .collect()
+});
+
+fn bundle(locale: &str, source: &str) -> FluentBundle<FluentResource> {
+    let lang: LanguageIdentifier = locale.parse().expect("locale names are valid");
+    let resource = FluentResource::try_new(source.to_string())
+        .unwrap_or_else(|(_, e)| panic!("{} translations don't parse: {:?}", locale, e));
+    let mut bundle = FluentBundle::new_concurrent(vec![lang]);
+    // the unicode isolation marks around arguments show up as boxes on some clients
+    bundle.set_use_isolating(false);
+    bundle
+        .add_resource(resource)
+        .unwrap_or_else(|e| panic!("{} translations have duplicates: {:?}", locale, e));
+    bundle
+}
+
+/// Parse every translation file now rather than on the first response
+pub fn load() {
+    tracing::info!("Loaded translations for {}", BUNDLES.len());
+}
+
+/// Translate a message, falling back to English and then to the message id itself.
+/// Use the `t!` macro rather than calling this directly.
+pub fn tr(locale: &str, id: &str, args: Option<&FluentArgs>) -> String {
+    format(locale, id, None, args)
+        .or_else(|| format(FALLBACK, id, None, args))
+        .unwrap_or_else(|| {
+            tracing::warn!("No translation for {}", id);
+            id.to_string()
+        })
+}
+
+/// Translate a message only if the locale has it, for text that has an English version in code
+pub fn lookup(locale: &str, id: &str) -> Option<String> {
+    format(locale, id, None, None)
+}
+
+fn format(
+    locale: &str,
+    id: &str,
+    attribute: Option<&str>,
+    args: Option<&FluentArgs>,
+) -> Option<String> {
+    let bundle = BUNDLES.get(locale)?;
+    let message = bundle.get_message(id)?;
+    let pattern = match attribute {
+        Some(a) => message.get_attribute(a)?.value(),
+        None => message.value()?,
+    };
+    let mut errors = Vec::new();
+    let text = bundle.format_pattern(pattern, args, &mut errors);
+    if !errors.is_empty() {
+        tracing::warn!("Problems formatting {} in {}: {:?}", id, locale, errors);
+    }
+    Some(text.into_owned())
+}
+
+/// The locale we have that's closest to a Discord one, matching `es-419` to `es-ES` and so on
+pub fn supported(locale: &str) -> Option<&'static str> {
+    let language = |l: &str| l.split('-').next().unwrap_or(l).to_lowercase();
+    LOCALES
+        .iter()
+        .find(|l| l.eq_ignore_ascii_case(locale))
+        .or_else(|| LOCALES.iter().find(|l| language(l) == language(locale)))
+        .copied()
+}
+
+/// A guild's `language` setting from the admin module, if it has one
+pub async fn guild_language(
+    db: &SqlitePool,
+    guild_id: Id<GuildMarker>,
+) -> OshiroResult<Option<&'static str>> {
+    Ok(db::module_setting(db, guild_id, "admin", "language")
+        .await?
+        .as_deref()
+        .and_then(supported))
+}
+
+/// The locale for messages that don't answer anyone, like log posts: the guild's `language`
+/// setting, then the guild's own locale, then English
+pub async fn guild_locale(
+    db: &SqlitePool,
+    guild_id: Id<GuildMarker>,
+    preferred: Option<&str>,
+) -> &'static str {
+    match guild_language(db, guild_id).await {
+        Ok(Some(locale)) => return locale,
+        Ok(None) => {}
+        Err(e) => tracing::warn!("Could not load the language of {}: {}", guild_id, e),
+    }
+    preferred.and_then(supported).unwrap_or(FALLBACK)
+}
+
+/// Fill in a command's name and description localizations, and those of its options.
+///
+/// They come from the `cmd-<command>` message, or `cmd-<command>-<option>` (and so on down
+/// through subcommands): the value is the localized name, the `.description` attribute the
+/// localized description. Either can be left out.
+pub fn localize_command(command: &mut Command) {
+    let id = format!("cmd-{}", command.name);
+    command.name_localizations = localizations(&id, None);
+    command.description_localizations = localizations(&id, Some("description"));
+    for option in &mut command.options {
+        localize_option(&id, option);
+    }
+}
+
+fn localize_option(parent: &str, option: &mut CommandOption) {
+    let id = format!("{}-{}", parent, option.name);
+    option.name_localizations = localizations(&id, None);
+    option.description_localizations = localizations(&id, Some("description"));
+    for option in option.options.iter_mut().flatten() {
+        localize_option(&id, option);
+    }
+}
+
+/// A message in every locale but English, or `None` if nobody has translated it
+fn localizations(id: &str, attribute: Option<&str>) -> Option<HashMap<String, String>> {
+    let translated: HashMap<String, String> = LOCALES
+        .iter()
+        .filter(|l| **l != FALLBACK)
+        .filter_map(|l| Some((l.to_string(), format(l, id, attribute, None)?)))
+        .collect();
+    if translated.is_empty() {
+        None
+    } else {
+        Some(translated)
+    }
+}
+
+#[macro_use]
+pub mod macros {
+    #[macro_export]
+    /// Translate a message for a locale, with optional arguments
+    /// ```
+    /// t!(locale, "command-disabled");
+    /// t!(locale, "modules-not-found", name = name.as_str());
+    /// ```
+    macro_rules! t {
+        ($locale: expr, $id: expr) => {
+            $crate::i18n::tr($locale, $id, None)
+        };
+        ($locale: expr, $id: expr, $($arg: ident = $value: expr),+ $(,)?) => {{
+            let mut args = fluent_bundle::FluentArgs::new();
+            $(args.set(stringify!($arg), $value);)+
+            $crate::i18n::tr($locale, $id, Some(&args))
+        }};
+    }
+}
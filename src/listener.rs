@@ -0,0 +1,149 @@
+use std::{collections::HashMap, panic::AssertUnwindSafe, sync::Arc};
+
+use futures::FutureExt;
+use tokio::sync::Mutex;
+use twilight_gateway::{Event, EventType, EventTypeFlags};
+
+use crate::{cmd::CommandResultOuter, ctx::OshiroContext, db, snapshot::Snapshot};
+
+pub type ListenerFn = Box<
+    dyn Fn(Event, Arc<Snapshot>, Arc<Mutex<OshiroContext>>) -> CommandResultOuter + Send + Sync,
+>;
+
+/// A handler for one kind of gateway event
+pub struct Listener {
+    pub name: String,
+    pub kind: EventType,
+    pub exec: ListenerFn,
+}
+
+#[macro_use]
+pub mod macros {
+    #[macro_export]
+    /// Builds a Listener for one `Event` variant. The handler gets the variant's payload, and
+    /// with `snapshot` also what the cache had before the event.
+    /// ```
+    /// // async fn greet(member: Box<MemberAdd>, ctx: Arc<Mutex<OshiroContext>>) -> OshiroResult
+    /// listener!(MemberAdd, "greet", greet);
+    /// // async fn deleted(d: MessageDelete, before: Arc<Snapshot>, ctx: ...) -> OshiroResult
+    /// listener!(MessageDelete, "deleted", deleted, snapshot);
+    /// ```
+    macro_rules! listener {
+        ($variant: ident, $name: expr, $func: path) => {{
+            $crate::listener::Listener {
+                name: format!("{}", $name),
+                kind: twilight_gateway::EventType::$variant,
+                exec: Box::new(move |event, _, ctx| {
+                    Box::pin(async move {
+                        match event {
+                            twilight_gateway::Event::$variant(e) => $func(e, ctx).await,
+                            _ => Ok(()),
+                        }
+                    })
+                }),
+            }
+        }};
+        ($variant: ident, $name: expr, $func: path, snapshot) => {{
+            $crate::listener::Listener {
+                name: format!("{}", $name),
+                kind: twilight_gateway::EventType::$variant,
+                exec: Box::new(move |event, snapshot, ctx| {
+                    Box::pin(async move {
+                        match event {
+                            twilight_gateway::Event::$variant(e) => $func(e, snapshot, ctx).await,
+                            _ => Ok(()),
+                        }
+                    })
+                }),
+            }
+        }};
+    }
+}
+
+/// Listeners from every module, grouped by the event they want
+#[derive(Default, Clone)]
+pub struct ListenerRegistry {
+    listeners: HashMap<EventType, Vec<(&'static str, Arc<Listener>)>>,
+}
+
+impl ListenerRegistry {
+    pub fn add(&mut self, module: &'static str, listener: Listener) {
+        self.listeners
+            .entry(listener.kind)
+            .or_default()
+            .push((module, Arc::new(listener)));
+    }
+
+    /// Warn about listeners that will never run because their event isn't subscribed to
+    pub fn check_subscribed(&self, flags: EventTypeFlags) {
+        for (kind, listeners) in &self.listeners {
+            if !flags.contains(EventTypeFlags::from(*kind)) {
+                for (module, l) in listeners {
+                    tracing::warn!(
+                        "Listener {}/{} wants {:?}, which isn't in gateway.events",
+                        module,
+                        l.name,
+                        kind
+                    );
+                }
+            }
+        }
+    }
+
+    /// Run every listener for an event in its own task, skipping modules that are off in the
+    /// event's guild or everywhere. Listeners can't see each other's errors or panics, and don't hold up the
+    /// event loop.
+    pub async fn dispatch(
+        &self,
+        event: &Event,
+        snapshot: Arc<Snapshot>,
+        ctx: Arc<Mutex<OshiroContext>>,
+    ) {
+        let listeners = match self.listeners.get(&event.kind()) {
+            Some(l) if !l.is_empty() => l,
+            _ => return,
+        };
+
+        let (framework, pool, config) = {
+            let oshi = ctx.lock().await;
+            (
+                Arc::clone(&oshi.framework),
+                oshi.db.clone(),
+                Arc::clone(&oshi.config),
+            )
+        };
+        let disabled = match event.guild_id() {
+            Some(guild_id) => match db::disabled_modules(&pool, guild_id).await {
+                Ok(d) => d,
+                Err(e) => {
+                    tracing::error!("Could not load disabled modules, skipping listeners: {}", e);
+                    return;
+                }
+            },
+            None => Vec::new(),
+        };
+
+        for (module, listener) in listeners {
+            let can_disable = framework.module(module).map_or(true, |m| m.can_disable());
+            let off = |d: &String| d == module;
+            if can_disable
+                && (disabled.iter().any(off) || config.commands.disabled_modules.iter().any(off))
+            {
+                continue;
+            }
+
+            let module = *module;
+            let listener = Arc::clone(listener);
+            let fut = (listener.exec)(event.clone(), Arc::clone(&snapshot), Arc::clone(&ctx));
+            tokio::spawn(async move {
+                match AssertUnwindSafe(fut).catch_unwind().await {
+                    Ok(Ok(())) => {}
+                    Ok(Err(e)) => {
+                        tracing::error!("Listener {}/{} failed: {}", module, listener.name, e)
+                    }
+                    Err(_) => tracing::error!("Listener {}/{} panicked", module, listener.name),
+                }
+            });
+        }
+    }
+}
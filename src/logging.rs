@@ -0,0 +1,32 @@
+use once_cell::sync::OnceCell;
+use tracing_subscriber::{fmt, prelude::*, registry::Registry, reload, EnvFilter};
+
+use crate::config::{LogFormat, LoggingConfig};
+
+/// Lets `logging.level` change without a restart
+static FILTER: OnceCell<reload::Handle<EnvFilter, Registry>> = OnceCell::new();
+
+/// Install the global subscriber. The format is fixed from here on, the level isn't.
+pub fn init(logging: &LoggingConfig) {
+    // the level was checked by Config::validate
+    let (filter, handle) = reload::Layer::new(EnvFilter::new(&logging.level));
+    let format = match logging.format {
+        LogFormat::Full => fmt::layer().boxed(),
+        LogFormat::Compact => fmt::layer().compact().boxed(),
+        LogFormat::Pretty => fmt::layer().pretty().boxed(),
+    };
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(format)
+        .init();
+    FILTER.set(handle).ok();
+}
+
+/// Swap in a new `EnvFilter` directive
+pub fn set_level(level: &str) -> Result<(), String> {
+    let filter = EnvFilter::try_new(level).map_err(|e| e.to_string())?;
+    match FILTER.get() {
+        Some(handle) => handle.reload(filter).map_err(|e| e.to_string()),
+        None => Err("logging isn't set up".to_string()),
+    }
+}
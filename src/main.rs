@@ -1,97 +1,111 @@
+use clap::Parser;
 use cmd::OshiroResult;
-use futures::StreamExt;
-use std::{
-    env,
-    error::Error,
-    sync::Arc,
-    time::{Duration, SystemTime},
-};
-use tokio::sync::Mutex;
-use twilight_cache_inmemory::{InMemoryCache, ResourceType};
-use twilight_gateway::{
-    stream::{self, ShardEventStream},
-    Config, ConfigBuilder, Event, EventTypeFlags, Intents, ShardId,
-};
+use std::{error::Error, sync::Arc, time::Instant};
+use tokio::sync::{mpsc, watch, Mutex};
+use twilight_cache_inmemory::InMemoryCache;
+use twilight_gateway::Event;
 use twilight_http::Client as HttpClient;
+use twilight_model::id::{marker::UserMarker, Id};
 
-use crate::{cmd::CommandFramework, ctx::OshiroContext};
+use crate::{
+    cli::{Cli, CliCommand, CommandsCommand, ConfigCommand, DbCommand},
+    cmd::{CommandContext, CommandFramework, CommandType},
+    cooldown::Cooldowns,
+    ctx::OshiroContext,
+    shard::{ShardEvent, ShardSet},
+    snapshot::Snapshot,
+};
 
+pub mod cli;
 pub mod cmd;
 pub mod commands;
+pub mod config;
+pub mod cooldown;
 pub mod ctx;
+pub mod db;
 pub mod helper;
+pub mod i18n;
+pub mod listener;
+pub mod logging;
+pub mod metrics;
+pub mod module;
+pub mod record;
+pub mod reload;
+pub mod replay;
+pub mod reshard;
+pub mod server;
+pub mod shard;
 pub mod slash;
+pub mod snapshot;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
     // .env file to the environment
     dotenv::dotenv().ok();
+    let command = Cli::parse().command.unwrap_or(CliCommand::Run);
+    if let CliCommand::Commands(CommandsCommand::Dump) = command {
+        return cli::dump_commands();
+    }
+
+    let config = match config::Config::load(&commands::known_modules()) {
+        Ok(c) => Arc::new(c),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
     // Initialize the tracing subscriber.
-    tracing_subscriber::fmt::init();
+    logging::init(&config.logging);
+
+    match command {
+        CliCommand::Run => run(config).await,
+        CliCommand::Commands(c) => cli::commands(config, c).await,
+        CliCommand::Config(ConfigCommand::Check) => {
+            println!("configuration is valid");
+            Ok(())
+        }
+        CliCommand::Db(DbCommand::Migrate) => cli::migrate(config).await,
+        CliCommand::Replay { path } => replay::run(config, &path).await,
+    }
+}
+
+/// Connect to the gateway and handle events until every shard has stopped
+async fn run(config: Arc<config::Config>) -> OshiroResult<()> {
+    if let Some(path) = &config.gateway.record {
+        record::init(path)?;
+    }
+    i18n::load();
 
-    let token =
-        env::var("DISCORD_TOKEN").expect("Expected a Discord bot token in the environment.");
+    let token = config.bot.token.clone();
 
-    let http = Arc::new(HttpClient::new(token.clone()));
+    let http = Arc::new(
+        HttpClient::builder()
+            .token(token)
+            .ratelimiter(Some(Box::new(metrics::MeteredRatelimiter::new())))
+            .build(),
+    );
     let me = http.current_user().await?.model().await?;
 
     tracing::info!("Logged in as {}#{}", me.name, me.discriminator);
 
     let current_app = http.current_user_application().await?.model().await?;
 
-    let interaction = http.interaction(current_app.id);
+    let total = match config.sharding.total {
+        Some(total) => total,
+        None => http.gateway().authed().await?.model().await?.shards,
+    };
+    let range = config.sharding.range(total);
+    tracing::info!("Running shards {}..{} of {}", range.start, range.end, total);
 
-    interaction
-        .set_global_commands(
-            &slash::commands()
-                .into_iter()
-                .map(|c| c.1.command)
-                .collect::<Vec<twilight_model::application::command::Command>>(),
-        )
-        .await?;
-
-    let intents = Intents::GUILDS
-        | Intents::GUILD_MEMBERS
-        | Intents::GUILD_MESSAGES
-        | Intents::DIRECT_MESSAGES
-        | Intents::MESSAGE_CONTENT;
-
-    let flags = EventTypeFlags::CHANNEL_CREATE
-        | EventTypeFlags::CHANNEL_DELETE
-        | EventTypeFlags::CHANNEL_UPDATE
-        | EventTypeFlags::GUILD_CREATE
-        | EventTypeFlags::GUILD_DELETE
-        | EventTypeFlags::GUILD_UPDATE
-        | EventTypeFlags::INTERACTION_CREATE
-        | EventTypeFlags::MEMBER_ADD
-        | EventTypeFlags::MEMBER_REMOVE
-        | EventTypeFlags::MEMBER_UPDATE
-        | EventTypeFlags::MEMBER_CHUNK
-        | EventTypeFlags::MESSAGE_CREATE
-        | EventTypeFlags::MESSAGE_DELETE
-        | EventTypeFlags::MESSAGE_DELETE_BULK
-        | EventTypeFlags::READY
-        | EventTypeFlags::ROLE_CREATE
-        | EventTypeFlags::ROLE_DELETE
-        | EventTypeFlags::ROLE_UPDATE
-        | EventTypeFlags::THREAD_CREATE
-        | EventTypeFlags::THREAD_DELETE
-        | EventTypeFlags::THREAD_UPDATE
-        | EventTypeFlags::UNAVAILABLE_GUILD
-        | EventTypeFlags::USER_UPDATE;
-
-    let builder = Config::builder(token.clone(), intents)
-        .event_types(flags)
-        .build();
-
-    fn builder_callback(_: ShardId, c: ConfigBuilder) -> Config {
-        c.build()
+    // register to the dev guild only, if there is one, so changes show up immediately.
+    // with several processes only the one running shard 0 does this
+    let registers_commands = range.contains(&0);
+    if registers_commands {
+        slash::sync(&http, current_app.id, &config).await?;
+    } else {
+        tracing::info!("Shard 0 runs elsewhere, not registering slash commands");
     }
 
-    let mut shards = stream::create_recommended(&http, builder, builder_callback)
-        .await?
-        .collect::<Vec<_>>();
-
     //let shard_closers: Vec<MessageSender> = shards.iter().map(Shard::sender).collect();
     //tokio::spawn(async move {
     //    tokio::signal::ctrl_c()
@@ -104,75 +118,90 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
     //    }
     //});
 
-    tracing::trace!("{} shard(s) in the event stream", shards.len());
-
     let cache = InMemoryCache::builder()
-        .resource_types(
-            ResourceType::MESSAGE
-                | ResourceType::CHANNEL
-                | ResourceType::MEMBER
-                | ResourceType::GUILD
-                | ResourceType::ROLE
-                | ResourceType::USER
-                | ResourceType::USER_CURRENT,
-        )
-        .message_cache_size(512)
+        .resource_types(config.cache.resource_types())
+        .message_cache_size(config.cache.message_cache_size)
         .build();
 
     let framework = Arc::new(CommandFramework::create().await?);
+    framework
+        .listeners()
+        .check_subscribed(config.gateway.event_types());
+    let db = db::connect(&config.storage.path).await?;
 
     let arc_cache = Arc::new(cache);
-    let mut latency = Vec::new();
-    let latency_last_checked = SystemTime::now();
 
-    for s in shards.iter() {
-        tracing::info!("Checking latency");
-        latency.push(s.latency().clone());
-    }
+    // every shard receives in its own task and hands events to the loop below
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let (shutdown, shutdown_rx) = watch::channel(false);
+    let shards = ShardSet::start(&config, range, total, 0, tx.clone(), shutdown_rx);
+    tracing::trace!(
+        "{} shard(s) in the event stream",
+        shards.monitor.snapshot().len()
+    );
+    // events are only handled from the newest shard set that is fully ready
+    let generations = Arc::new(reshard::Generations::new(shards.generation));
 
+    let config_server = config.server.clone();
+    let config_reshard = config.sharding.reshard();
     let oshiro_ctx = Arc::new(Mutex::new(OshiroContext {
         framework: Arc::clone(&framework),
         http,
         cache: arc_cache,
-        shard_latency: latency,
         app_id: current_app.id,
+        config,
+        db,
+        shards: Arc::clone(&shards.monitor),
+        cooldowns: Arc::new(Cooldowns::new()),
+        registers_commands,
+        started: Instant::now(),
+        shutdown: Arc::new(shutdown),
     }));
 
-    let mut event_stream = ShardEventStream::new(shards.iter_mut());
+    tokio::spawn(reload::watch(Arc::clone(&oshiro_ctx)));
 
-    // Event loop -
-    while let Some((shard, event_result)) = event_stream.next().await {
-        if SystemTime::now() > latency_last_checked + Duration::from_secs(30) {
-            tracing::info!("Checking latency");
-            if let Some(l) = oshiro_ctx
-                .lock()
-                .await
-                .shard_latency
-                .get_mut(shard.id().number() as usize)
-            {
-                let g = shard.latency().to_owned();
-                *l = g
+    if config_server.enabled {
+        let ctx = Arc::clone(&oshiro_ctx);
+        tokio::spawn(async move {
+            if let Err(e) = server::serve(config_server.bind, ctx).await {
+                tracing::error!("HTTP server stopped: {}", e);
             }
+        });
+    }
+
+    // the shard set has to live as long as the event loop, the resharder takes care of that
+    let _shards = if config_reshard {
+        tokio::spawn(reshard::run(
+            Arc::clone(&oshiro_ctx),
+            shards,
+            Arc::clone(&generations),
+            tx,
+        ));
+        None
+    } else {
+        drop(tx);
+        Some(shards)
+    };
+
+    // Event loop -
+    while let Some(ShardEvent {
+        generation,
+        id,
+        event,
+    }) = rx.recv().await
+    {
+        if !generations.accept(generation, &event) {
+            continue;
         }
-        let event = match event_result {
-            Ok(e) => e,
-            Err(source) => {
-                if source.is_fatal() {
-                    tracing::error!(?source, "fatal error receiving event:");
-                    break;
-                } else {
-                    tracing::warn!(?source, "error receiving event:");
-                }
-
-                continue;
-            }
-        };
+        metrics::EVENTS
+            .with_label_values(&[&format!("{:?}", event.kind()), &id.number().to_string()])
+            .inc();
 
         if let Err(e) = handle_event(
             event,
             Arc::clone(&oshiro_ctx),
             Arc::clone(&framework),
-            me.id.to_string(),
+            me.id,
         )
         .await
         {
@@ -187,43 +216,54 @@ async fn handle_event(
     event: Event,
     ctx: Arc<Mutex<OshiroContext>>,
     framework: Arc<CommandFramework>,
-    me: String,
+    me: Id<UserMarker>,
 ) -> OshiroResult<()> {
-    // TODO: move good_bots to somewhere else
-    let good_bots: Vec<String> = Vec::new();
-    let prefix = env::var("PREFIX").expect("Expected a prefix in the environment.");
-    // update cache
-    ctx.lock().await.cache.update(&event);
+    let config = Arc::clone(&ctx.lock().await.config);
+    let prefix = config.bot.prefix.as_str();
+    // update cache, keeping what it had before for listeners that log changes
+    let snapshot = {
+        let oshi = ctx.lock().await;
+        let snapshot = Snapshot::take(&oshi.cache, &event);
+        oshi.cache.update(&event);
+        Arc::new(snapshot)
+    };
+    framework
+        .listeners()
+        .dispatch(&event, snapshot, Arc::clone(&ctx))
+        .await;
     match event {
+        // never answer ourselves or our own webhooks, whatever the guild's policy says
+        Event::MessageCreate(msg)
+            if msg.author.id == me || msg.application_id == Some(ctx.lock().await.app_id) =>
+        {
+            return Ok(())
+        }
         Event::MessageCreate(msg)
-            if msg.author.bot && !good_bots.contains(&msg.author.id.to_string()) =>
+            if (msg.author.bot || msg.webhook_id.is_some())
+                && !commands::admin::bot_message_allowed(&ctx, &msg).await? =>
         {
             return Ok(())
         }
-        Event::MessageCreate(msg) if msg.content.starts_with(&prefix) => {
+        Event::MessageCreate(msg) if msg.content.starts_with(prefix) => {
             framework
-                .parse_command(&prefix, msg, Arc::clone(&ctx))
+                .parse_command(prefix, msg, Arc::clone(&ctx))
                 .await?;
         }
         Event::MessageCreate(msg) => {
             if msg.content == format!("<@{}>", me) {
-                let context = ctx.lock().await;
-                context
-                    .http
-                    .create_message(msg.channel_id)
-                    .content(&format!(
-                        "My prefix is {}, but you can ping me as well.",
-                        &prefix
-                    ))?
+                let cctx = CommandContext {
+                    command_type: CommandType::TEXT,
+                    oshiro: Arc::clone(&ctx),
+                    msg: Some(Box::new(msg.0)),
+                    stripped: None,
+                    slash: None,
+                };
+                let locale = cctx.locale().await;
+                cctx.reply(&t!(locale, "mention-prefix", prefix = prefix))
                     .await?;
             }
         }
         Event::InteractionCreate(slash) => slash::handle(slash.0, Arc::clone(&ctx)).await?,
-        Event::Ready(r) => {
-            if let Some(s) = r.shard {
-                tracing::info!("Shard {} is ready", s.number())
-            }
-        }
         _ => {}
     }
 
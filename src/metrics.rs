@@ -0,0 +1,194 @@
+use std::{sync::Arc, time::Duration};
+
+use heim::{process, units};
+use once_cell::sync::Lazy;
+use prometheus::{
+    register_counter, register_gauge, register_gauge_vec, register_histogram_vec,
+    register_int_counter, register_int_counter_vec, register_int_gauge_vec, Counter, Encoder,
+    Gauge, GaugeVec, HistogramVec, IntCounter, IntCounterVec, IntGaugeVec, TextEncoder,
+};
+use tokio::sync::Mutex;
+use twilight_http_ratelimiting::{
+    request::Path, GetBucketFuture, GetTicketFuture, HasBucketFuture, InMemoryRatelimiter,
+    IsGloballyLockedFuture, Ratelimiter,
+};
+
+use crate::{cmd::OshiroResult, ctx::OshiroContext};
+
+pub static COMMANDS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "oshiro_commands_total",
+        "Commands executed",
+        &["name", "type", "outcome"]
+    )
+    .unwrap()
+});
+
+pub static COMMAND_DURATION: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "oshiro_command_duration_seconds",
+        "Time taken to run a command",
+        &["name", "type"]
+    )
+    .unwrap()
+});
+
+pub static EVENTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "oshiro_gateway_events_total",
+        "Gateway events received",
+        &["type", "shard"]
+    )
+    .unwrap()
+});
+
+pub static SHARD_LATENCY: Lazy<GaugeVec> = Lazy::new(|| {
+    register_gauge_vec!(
+        "oshiro_shard_latency_seconds",
+        "Average gateway heartbeat latency",
+        &["shard"]
+    )
+    .unwrap()
+});
+
+pub static SHARD_SESSIONS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "oshiro_shard_session_events_total",
+        "Reconnects, resumes, invalidated sessions and closes per shard",
+        &["shard", "event"]
+    )
+    .unwrap()
+});
+
+pub static HTTP_RATELIMITS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "oshiro_http_ratelimit_hits_total",
+        "HTTP requests that had to wait for a ratelimit"
+    )
+    .unwrap()
+});
+
+pub static CACHE_ENTRIES: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "oshiro_cache_entries",
+        "Entries in the in-memory cache",
+        &["resource"]
+    )
+    .unwrap()
+});
+
+pub static PROCESS_MEMORY: Lazy<Gauge> = Lazy::new(|| {
+    register_gauge!(
+        "process_resident_memory_bytes",
+        "Resident memory size in bytes"
+    )
+    .unwrap()
+});
+
+/// Sampled like the gauges, but a counter as the name says, so it goes up by the difference
+pub static PROCESS_CPU: Lazy<Counter> = Lazy::new(|| {
+    register_counter!(
+        "process_cpu_seconds_total",
+        "Total user and system CPU time spent in seconds"
+    )
+    .unwrap()
+});
+
+/// Keeps two scrapes at once from both adding the same CPU time
+static PROCESS_CPU_SAMPLE: Lazy<std::sync::Mutex<()>> = Lazy::new(Default::default);
+
+/// Record a finished command
+pub fn command(name: &str, kind: &str, elapsed: Duration, ok: bool) {
+    let outcome = if ok { "ok" } else { "error" };
+    COMMANDS.with_label_values(&[name, kind, outcome]).inc();
+    COMMAND_DURATION
+        .with_label_values(&[name, kind])
+        .observe(elapsed.as_secs_f64());
+}
+
+/// Refresh the gauges that are sampled rather than counted, then encode everything.
+/// Shard latency is kept up to date by the shard monitor.
+pub async fn render(ctx: &Arc<Mutex<OshiroContext>>) -> OshiroResult<String> {
+    {
+        let oshi = ctx.lock().await;
+
+        let stats = oshi.cache.stats();
+        for (resource, count) in [
+            ("guilds", stats.guilds()),
+            ("channels", stats.channels()),
+            ("users", stats.users()),
+            ("members", stats.members()),
+            ("roles", stats.roles()),
+            ("emojis", stats.emojis()),
+        ] {
+            CACHE_ENTRIES
+                .with_label_values(&[resource])
+                .set(count as i64);
+        }
+    }
+
+    let process = process::current().await?;
+    PROCESS_MEMORY.set(
+        process
+            .memory()
+            .await?
+            .rss()
+            .get::<units::information::byte>() as f64,
+    );
+    let cpu = process.cpu_time().await?;
+    let total = (cpu.user() + cpu.system()).get::<units::time::second>();
+    {
+        let _sampling = PROCESS_CPU_SAMPLE.lock().unwrap();
+        let counted = PROCESS_CPU.get();
+        if total > counted {
+            PROCESS_CPU.inc_by(total - counted);
+        }
+    }
+
+    let mut buf = Vec::new();
+    TextEncoder::new().encode(&prometheus::gather(), &mut buf)?;
+    Ok(String::from_utf8(buf)?)
+}
+
+/// The default ratelimiter, counting requests that run into an exhausted bucket or a global lock
+#[derive(Debug)]
+pub struct MeteredRatelimiter(InMemoryRatelimiter);
+
+impl MeteredRatelimiter {
+    pub fn new() -> Self {
+        MeteredRatelimiter(InMemoryRatelimiter::new())
+    }
+}
+
+impl Default for MeteredRatelimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Ratelimiter for MeteredRatelimiter {
+    fn bucket(&self, path: &Path) -> GetBucketFuture {
+        self.0.bucket(path)
+    }
+
+    fn is_globally_locked(&self) -> IsGloballyLockedFuture {
+        self.0.is_globally_locked()
+    }
+
+    fn has(&self, path: &Path) -> HasBucketFuture {
+        self.0.has(path)
+    }
+
+    fn ticket(&self, path: Path) -> GetTicketFuture {
+        let bucket = self.0.bucket(&path);
+        let globally_locked = self.0.is_globally_locked();
+        let ticket = self.0.ticket(path);
+        Box::pin(async move {
+            let exhausted = matches!(bucket.await, Ok(Some(b)) if b.remaining() == 0);
+            if exhausted || globally_locked.await.unwrap_or(false) {
+                HTTP_RATELIMITS.inc();
+            }
+            ticket.await
+        })
+    }
+}
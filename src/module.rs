@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+
+use sqlx::SqlitePool;
+use twilight_model::id::{marker::GuildMarker, Id};
+
+use crate::{
+    cmd::{CommandInstance, OshiroResult},
+    db,
+    helper::parse_duration,
+    listener::Listener,
+    slash::{CommandWrapper, SlashCommandFn},
+    t,
+};
+
+/// A group of commands and listeners that can be switched on and off per guild.
+pub trait Module: Send + Sync {
+    /// Short lowercase name, used by the `modules` command and in storage
+    fn name(&self) -> &'static str;
+
+    fn description(&self) -> &'static str;
+
+    /// Core modules (like the one holding the `modules` command itself) can't be disabled
+    fn can_disable(&self) -> bool {
+        true
+    }
+
+    /// Text commands
+    fn commands(&self) -> Vec<CommandInstance> {
+        Vec::new()
+    }
+
+    /// Slash commands, keyed by their top level name
+    fn slash_commands(&self) -> HashMap<String, CommandWrapper> {
+        HashMap::new()
+    }
+
+    /// Slash commands only registered in `bot.admin_guild`, keyed by their top level name
+    fn admin_slash_commands(&self) -> HashMap<String, CommandWrapper> {
+        HashMap::new()
+    }
+
+    /// Message component handlers, keyed by the part of the custom id before the first `:`.
+    /// Custom ids live on in posted messages, so a prefix can't change once it's in use.
+    fn components(&self) -> HashMap<String, SlashCommandFn> {
+        HashMap::new()
+    }
+
+    /// Gateway event handlers, only run for guilds where the module is enabled.
+    /// Build them with the `listener!` macro.
+    fn listeners(&self) -> Vec<Listener> {
+        Vec::new()
+    }
+
+    /// Per-guild settings the module understands
+    fn config_schema(&self) -> Vec<ConfigField> {
+        Vec::new()
+    }
+}
+
+/// Read a guild's module setting, falling back to the default from the module's schema
+pub async fn setting(
+    db: &SqlitePool,
+    guild_id: Id<GuildMarker>,
+    module: &dyn Module,
+    key: &str,
+) -> OshiroResult<Option<String>> {
+    match db::module_setting(db, guild_id, module.name(), key).await? {
+        Some(v) => Ok(Some(v)),
+        None => Ok(module
+            .config_schema()
+            .into_iter()
+            .find(|f| f.key == key)
+            .and_then(|f| f.default.map(str::to_string))),
+    }
+}
+
+/// Describes a per-guild module setting
+pub struct ConfigField {
+    pub key: &'static str,
+    pub description: &'static str,
+    pub kind: ConfigKind,
+    pub default: Option<&'static str>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigKind {
+    Bool,
+    Number,
+    Text,
+    Channel,
+    Role,
+    /// A length of time like `30m` or `1h30m`
+    Duration,
+    /// One of a fixed set of words, matched ignoring case
+    Choice(&'static [&'static str]),
+}
+
+/// Why a value didn't parse: the message id, and the input it's about
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub id: &'static str,
+    pub input: String,
+    /// What a `ConfigKind::Choice` could have been
+    pub choices: String,
+}
+
+impl ParseError {
+    fn new(id: &'static str, input: &str) -> Self {
+        ParseError {
+            id,
+            input: input.to_string(),
+            choices: String::new(),
+        }
+    }
+
+    pub fn render(&self, locale: &str) -> String {
+        t!(
+            locale,
+            self.id,
+            input = self.input.clone(),
+            choices = self.choices.clone()
+        )
+    }
+}
+
+impl ConfigKind {
+    /// Validate user input and normalize it into the form that gets stored
+    pub fn parse(&self, input: &str) -> Result<String, ParseError> {
+        let input = input.trim();
+        match self {
+            ConfigKind::Bool => match input.to_lowercase().as_str() {
+                "true" | "yes" | "on" | "1" => Ok("true".to_string()),
+                "false" | "no" | "off" | "0" => Ok("false".to_string()),
+                _ => Err(ParseError::new("config-not-bool", input)),
+            },
+            ConfigKind::Number => input
+                .parse::<i64>()
+                .map(|n| n.to_string())
+                .map_err(|_| ParseError::new("config-not-number", input)),
+            ConfigKind::Text => {
+                if input.is_empty() {
+                    Err(ParseError::new("config-empty", input))
+                } else {
+                    Ok(input.to_string())
+                }
+            }
+            ConfigKind::Channel => parse_mention(input, "<#", ">")
+                .ok_or_else(|| ParseError::new("config-not-channel", input)),
+            ConfigKind::Role => parse_mention(input, "<@&", ">")
+                .ok_or_else(|| ParseError::new("config-not-role", input)),
+            ConfigKind::Duration => parse_duration(input)
+                .map(|_| input.to_lowercase())
+                .ok_or_else(|| ParseError::new("config-not-duration", input)),
+            ConfigKind::Choice(choices) => choices
+                .iter()
+                .find(|c| c.eq_ignore_ascii_case(input))
+                .map(|c| c.to_string())
+                .ok_or_else(|| ParseError {
+                    choices: choices.join(", "),
+                    ..ParseError::new("config-not-choice", input)
+                }),
+        }
+    }
+
+    /// Render a stored value for display
+    pub fn display(&self, value: &str) -> String {
+        match self {
+            ConfigKind::Channel => format!("<#{}>", value),
+            ConfigKind::Role => format!("<@&{}>", value),
+            _ => format!("`{}`", value),
+        }
+    }
+}
+
+/// Accepts either a raw id or a mention like `<#id>`
+fn parse_mention(input: &str, start: &str, end: &str) -> Option<String> {
+    let id = input
+        .strip_prefix(start)
+        .and_then(|s| s.strip_suffix(end))
+        .unwrap_or(input);
+    match id.parse::<u64>() {
+        Ok(n) if n != 0 => Some(n.to_string()),
+        _ => None,
+    }
+}
@@ -0,0 +1,82 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{LineWriter, Write},
+    path::Path,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use twilight_gateway::ShardId;
+
+use crate::cmd::OshiroResult;
+
+static RECORDER: OnceCell<Mutex<LineWriter<File>>> = OnceCell::new();
+
+/// Fields that could be used to take over a session or answer an interaction
+const REDACTED: &[&str] = &["session_id", "resume_gateway_url", "token"];
+
+/// One line of a recording
+#[derive(Serialize, Deserialize)]
+pub struct Recorded {
+    /// Milliseconds since the unix epoch
+    pub at: u128,
+    pub shard: u64,
+    /// The dispatch payload as it came off the gateway, minus anything in `REDACTED`
+    pub payload: Value,
+}
+
+/// Start appending every gateway dispatch to a JSON-lines file
+pub fn init(path: &Path) -> OshiroResult<()> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    if RECORDER.set(Mutex::new(LineWriter::new(file))).is_err() {
+        return Err("the recorder was already started".into());
+    }
+    tracing::warn!("Recording gateway events to {}", path.display());
+    Ok(())
+}
+
+/// Record a raw gateway message, if recording is on. Only dispatches are kept.
+pub fn message(shard: ShardId, json: &str) {
+    let recorder = match RECORDER.get() {
+        Some(r) => r,
+        None => return,
+    };
+
+    let mut payload: Value = match serde_json::from_str(json) {
+        Ok(v) => v,
+        Err(e) => {
+            tracing::warn!("Not recording a message that isn't JSON: {}", e);
+            return;
+        }
+    };
+    if payload["op"] != 0 {
+        return;
+    }
+    if let Some(data) = payload.get_mut("d").and_then(Value::as_object_mut) {
+        for key in REDACTED {
+            data.remove(*key);
+        }
+    }
+
+    let line = Recorded {
+        at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis()),
+        shard: shard.number(),
+        payload,
+    };
+    let mut line = match serde_json::to_string(&line) {
+        Ok(l) => l,
+        Err(e) => {
+            tracing::error!("Could not serialize a gateway event: {}", e);
+            return;
+        }
+    };
+    line.push('\n');
+    if let Err(e) = recorder.lock().unwrap().write_all(line.as_bytes()) {
+        tracing::error!("Could not record a gateway event: {}", e);
+    }
+}
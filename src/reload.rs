@@ -0,0 +1,83 @@
+use std::{
+    fs,
+    path::Path,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use tokio::sync::Mutex;
+
+use crate::{cmd::OshiroResult, config::Config, ctx::OshiroContext, logging, slash};
+
+/// What applying a new config did
+pub struct Reloaded {
+    /// Changed sections that only take effect after a restart
+    pub restart: Vec<&'static str>,
+    /// Slash commands had to be registered again
+    pub synced: bool,
+}
+
+/// Load the config again and apply it without touching the shards. If the new config is
+/// invalid the old one stays.
+///
+/// Most settings (prefix, owners, `commands`, ...) are read every time they're used, so
+/// swapping the config in is enough. The log level is swapped in the subscriber, and slash
+/// commands are only registered again if where they go changed.
+pub async fn apply(ctx: &Arc<Mutex<OshiroContext>>) -> OshiroResult<Reloaded> {
+    let new = Arc::new(Config::load(&crate::commands::known_modules())?);
+    let (old, http, app_id, registers_commands) = {
+        let oshi = ctx.lock().await;
+        (
+            Arc::clone(&oshi.config),
+            Arc::clone(&oshi.http),
+            oshi.app_id,
+            oshi.registers_commands,
+        )
+    };
+
+    if old.logging.level != new.logging.level {
+        logging::set_level(&new.logging.level)?;
+        tracing::info!("Log level is now {}", new.logging.level);
+    }
+    ctx.lock().await.config = Arc::clone(&new);
+    let synced = registers_commands && slash::resync(&http, app_id, &old, &new).await?;
+
+    tracing::info!("Reloaded the config");
+    Ok(Reloaded {
+        restart: old.restart_needed(&new),
+        synced,
+    })
+}
+
+/// Apply the config file whenever its modification time changes, while `reload.watch` is on
+pub async fn watch(ctx: Arc<Mutex<OshiroContext>>) {
+    let (path, _) = Config::path();
+    let mut last = modified(&path);
+    loop {
+        let reload = ctx.lock().await.config.reload.clone();
+        tokio::time::sleep(Duration::from_secs(reload.interval)).await;
+
+        let now = modified(&path);
+        if now == last {
+            continue;
+        }
+        last = now;
+        if !reload.watch {
+            continue;
+        }
+
+        tracing::info!("{} changed, reloading", path.display());
+        match apply(&ctx).await {
+            Ok(r) if !r.restart.is_empty() => tracing::warn!(
+                "These config changes need a restart: {}",
+                r.restart.join(", ")
+            ),
+            Ok(_) => {}
+            Err(e) => tracing::error!("Kept the old config: {}", e),
+        }
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
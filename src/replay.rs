@@ -0,0 +1,199 @@
+use std::{
+    fs,
+    net::{SocketAddr, TcpListener},
+    path::Path,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::State,
+    http::{Method, Uri},
+    Json, Router,
+};
+use serde::Serialize;
+use serde_json::{json, Value};
+use tokio::sync::{watch, Mutex};
+use twilight_cache_inmemory::InMemoryCache;
+use twilight_gateway::{Event, EventTypeFlags};
+use twilight_http::Client;
+use twilight_model::id::{marker::UserMarker, Id};
+
+use crate::{
+    cmd::{CommandFramework, OshiroResult},
+    config::Config,
+    cooldown::Cooldowns,
+    ctx::OshiroContext,
+    db,
+    record::Recorded,
+    shard::ShardMonitor,
+};
+
+/// Our user id when the recording has no READY, and the author of mock messages
+const FALLBACK_USER_ID: &str = "1";
+
+/// A request the handlers made while replaying
+#[derive(Serialize)]
+pub struct MockRequest {
+    pub method: String,
+    pub path: String,
+    pub body: Option<Value>,
+}
+
+#[derive(Clone, Default)]
+struct Mock {
+    requests: Arc<std::sync::Mutex<Vec<MockRequest>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+/// Feed a recording through `handle_event` against a mock of the Discord API and a throwaway
+/// database, then print every request the handlers made as JSON lines.
+pub async fn run(config: Arc<Config>, path: &Path) -> OshiroResult<()> {
+    let recording: Vec<Recorded> = fs::read_to_string(path)?
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+    tracing::info!(
+        "Replaying {} events from {}",
+        recording.len(),
+        path.display()
+    );
+
+    let mock = Mock::default();
+    let addr = serve_mock(mock.clone())?;
+    let http = Client::builder()
+        .token(config.bot.token.clone())
+        .proxy(addr.to_string(), true)
+        .ratelimiter(None)
+        .build();
+
+    let cache = InMemoryCache::builder()
+        .resource_types(config.cache.resource_types())
+        .message_cache_size(config.cache.message_cache_size)
+        .build();
+    let framework = Arc::new(CommandFramework::create().await?);
+    let ctx = Arc::new(Mutex::new(OshiroContext {
+        framework: Arc::clone(&framework),
+        http: Arc::new(http),
+        cache: Arc::new(cache),
+        app_id: Id::new(1),
+        config,
+        db: db::connect_in_memory().await?,
+        shards: Arc::new(ShardMonitor::new(1)),
+        cooldowns: Arc::new(Cooldowns::new()),
+        registers_commands: false,
+        started: Instant::now(),
+        shutdown: Arc::new(watch::channel(false).0),
+    }));
+
+    let me: Id<UserMarker> = recording
+        .iter()
+        .find(|r| r.payload["t"] == "READY")
+        .and_then(|r| r.payload["d"]["user"]["id"].as_str())
+        .unwrap_or(FALLBACK_USER_ID)
+        .parse()?;
+
+    for recorded in recording {
+        let event =
+            match twilight_gateway::parse(recorded.payload.to_string(), EventTypeFlags::all()) {
+                Ok(Some(e)) => Event::from(e),
+                Ok(None) => continue,
+                Err(e) => {
+                    tracing::warn!("Skipping an event that doesn't parse: {}", e);
+                    continue;
+                }
+            };
+        tracing::debug!("Replaying {:?} from shard {}", event.kind(), recorded.shard);
+        if let Err(e) =
+            crate::handle_event(event, Arc::clone(&ctx), Arc::clone(&framework), me).await
+        {
+            tracing::error!("Handler error: {e}");
+        }
+    }
+
+    // listeners run in their own tasks, give them a moment to finish
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    for request in mock.requests.lock().unwrap().iter() {
+        println!("{}", serde_json::to_string(request)?);
+    }
+    Ok(())
+}
+
+fn serve_mock(mock: Mock) -> OshiroResult<SocketAddr> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let addr = listener.local_addr()?;
+    let server = axum::Server::from_tcp(listener)?.serve(
+        Router::new()
+            .fallback(respond)
+            .with_state(mock)
+            .into_make_service(),
+    );
+    tokio::spawn(async move {
+        if let Err(e) = server.await {
+            tracing::error!("Mock HTTP backend stopped: {}", e);
+        }
+    });
+    Ok(addr)
+}
+
+/// Log the request and answer with something that deserializes into what the caller expects,
+/// for the requests that handlers read the response of
+async fn respond(State(mock): State<Mock>, method: Method, uri: Uri, body: String) -> Json<Value> {
+    let path = uri.path().trim_start_matches("/api/v10").to_string();
+    let body: Option<Value> = serde_json::from_str(&body).ok();
+
+    let creates_message = (method == Method::POST && path.ends_with("/messages"))
+        || (method == Method::PATCH && path.contains("/messages/"));
+    let response = if creates_message {
+        let id = 1000 + mock.next_id.fetch_add(1, Ordering::Relaxed);
+        let channel_id = path.split('/').nth(2).unwrap_or("1");
+        fake_message(id, channel_id, body.as_ref())
+    } else if path == "/users/@me" {
+        fake_user(FALLBACK_USER_ID)
+    } else {
+        json!({})
+    };
+
+    mock.requests.lock().unwrap().push(MockRequest {
+        method: method.to_string(),
+        path,
+        body,
+    });
+    Json(response)
+}
+
+fn fake_user(id: &str) -> Value {
+    json!({
+        "id": id,
+        "username": "oshiro",
+        "discriminator": "0000",
+        "avatar": null,
+        "bot": true,
+    })
+}
+
+fn fake_message(id: u64, channel_id: &str, body: Option<&Value>) -> Value {
+    let field =
+        |key: &str, default: Value| body.and_then(|b| b.get(key)).cloned().unwrap_or(default);
+    json!({
+        "id": id.to_string(),
+        "channel_id": channel_id,
+        "author": fake_user(FALLBACK_USER_ID),
+        "content": field("content", json!("")),
+        "embeds": field("embeds", json!([])),
+        "timestamp": "2023-01-01T00:00:00.000000+00:00",
+        "edited_timestamp": null,
+        "tts": false,
+        "mention_everyone": false,
+        "mentions": [],
+        "mention_roles": [],
+        "attachments": [],
+        "pinned": false,
+        "type": 0,
+    })
+}
@@ -0,0 +1,217 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, VecDeque},
+    hash::{Hash, Hasher},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use tokio::sync::{mpsc::UnboundedSender, Mutex};
+use twilight_gateway::Event;
+use twilight_http::Client;
+use twilight_model::gateway::connection_info::BotConnectionInfo;
+
+use crate::{
+    cmd::OshiroResult,
+    ctx::OshiroContext,
+    shard::{ShardEvent, ShardSet},
+};
+
+/// How long both shard sets are handled after a new one is ready, so events the old set was
+/// still delivering aren't lost
+const OVERLAP: Duration = Duration::from_secs(30);
+
+/// How many events are remembered while deduplicating an overlap
+const SEEN_LIMIT: usize = 10_000;
+
+/// Which shard sets the event loop takes events from.
+///
+/// Normally that's only the active set. While a new set takes over both are taken, and an event
+/// that arrives from both is only handled the first time.
+pub struct Generations {
+    inner: std::sync::Mutex<Overlap>,
+}
+
+struct Overlap {
+    active: u64,
+    incoming: Option<u64>,
+    /// Fingerprint of recently handled events and the set they came from
+    seen: HashMap<u64, u64>,
+    order: VecDeque<u64>,
+}
+
+impl Generations {
+    pub fn new(active: u64) -> Self {
+        Generations {
+            inner: std::sync::Mutex::new(Overlap {
+                active,
+                incoming: None,
+                seen: HashMap::new(),
+                order: VecDeque::new(),
+            }),
+        }
+    }
+
+    /// Whether the event loop should handle an event from this shard set
+    pub fn accept(&self, generation: u64, event: &Event) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.incoming.is_none() {
+            return generation == inner.active;
+        }
+        if generation != inner.active && Some(generation) != inner.incoming {
+            return false;
+        }
+
+        let key = fingerprint(event);
+        match inner.seen.get(&key) {
+            // the other set already delivered it
+            Some(&from) if from != generation => {
+                inner.seen.remove(&key);
+                false
+            }
+            _ => {
+                inner.seen.insert(key, generation);
+                inner.order.push_back(key);
+                while inner.order.len() > SEEN_LIMIT {
+                    if let Some(old) = inner.order.pop_front() {
+                        inner.seen.remove(&old);
+                    }
+                }
+                true
+            }
+        }
+    }
+
+    /// Start taking events from a new set as well as the active one
+    fn overlap(&self, incoming: u64) {
+        self.inner.lock().unwrap().incoming = Some(incoming);
+    }
+
+    /// Only take events from the new set from now on
+    fn switch(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(incoming) = inner.incoming.take() {
+            inner.active = incoming;
+        }
+        inner.seen.clear();
+        inner.order.clear();
+    }
+}
+
+/// Both sets get the same payload for an event, so its kind and ids and everything else in it
+/// identify it well enough
+fn fingerprint(event: &Event) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    event.kind().hash(&mut hasher);
+    format!("{:?}", event).hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Watch the gateway's recommended shard count and move to a bigger shard set once it has grown
+/// by `sharding.reshard_threshold`.
+///
+/// The new set is started next to the current one and its events are ignored until every new
+/// shard is ready; the old set still delivers all of them meanwhile. Then both are handled for
+/// `OVERLAP`, with events that arrive from both deduplicated, and only after that is the old set
+/// closed.
+pub async fn run(
+    ctx: Arc<Mutex<OshiroContext>>,
+    mut current: ShardSet,
+    generations: Arc<Generations>,
+    tx: UnboundedSender<ShardEvent>,
+) {
+    let (config, http, mut shutdown) = {
+        let oshi = ctx.lock().await;
+        (
+            Arc::clone(&oshi.config),
+            Arc::clone(&oshi.http),
+            oshi.shutdown.subscribe(),
+        )
+    };
+    let mut ticker = tokio::time::interval(Duration::from_secs(config.sharding.reshard_interval));
+    // the first tick completes immediately, and we've only just started
+    ticker.tick().await;
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            // our shard sets close themselves, and dropping `tx` lets the event loop finish
+            _ = shutdown.changed() => return,
+        }
+
+        let info = match connection_info(&http).await {
+            Ok(i) => i,
+            Err(e) => {
+                tracing::warn!("Could not check the recommended shard count: {}", e);
+                continue;
+            }
+        };
+        if info.shards < current.total + config.sharding.reshard_threshold {
+            continue;
+        }
+        if info.session_start_limit.remaining < info.shards {
+            tracing::warn!(
+                "Want to reshard to {} shards but only {} identifies are left, trying again later",
+                info.shards,
+                info.session_start_limit.remaining
+            );
+            continue;
+        }
+
+        tracing::info!(
+            "Recommended shard count went from {} to {}, starting shard set {}",
+            current.total,
+            info.shards,
+            current.generation + 1
+        );
+        let next = ShardSet::start(
+            &config,
+            0..info.shards,
+            info.shards,
+            current.generation + 1,
+            tx.clone(),
+            shutdown.clone(),
+        );
+
+        // shards identify max_concurrency at a time, one batch every 5 seconds
+        let batches = info.shards / info.session_start_limit.max_concurrency.max(1) + 1;
+        let deadline = Instant::now() + Duration::from_secs(5 * batches + 60);
+        while !next.ready() && Instant::now() < deadline {
+            if *shutdown.borrow() {
+                return;
+            }
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        }
+        if !next.ready() {
+            // dropping the set closes its shards
+            tracing::error!(
+                "Shard set {} wasn't ready in time, staying on {} shards",
+                next.generation,
+                current.total
+            );
+            continue;
+        }
+
+        generations.overlap(next.generation);
+        ctx.lock().await.shards = Arc::clone(&next.monitor);
+        // the old set may still be delivering events the new one got before it was taken
+        tokio::select! {
+            _ = tokio::time::sleep(OVERLAP) => {}
+            _ = shutdown.changed() => return,
+        }
+        generations.switch();
+
+        let old = std::mem::replace(&mut current, next);
+        old.monitor.retire();
+        tracing::info!(
+            "Switched from shard set {} ({} shards) to {} ({} shards)",
+            old.generation,
+            old.total,
+            current.generation,
+            current.total
+        );
+    }
+}
+
+async fn connection_info(http: &Client) -> OshiroResult<BotConnectionInfo> {
+    Ok(http.gateway().authed().await?.model().await?)
+}
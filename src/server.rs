@@ -0,0 +1,122 @@
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde::Serialize;
+use tokio::sync::Mutex;
+
+use crate::{
+    cmd::OshiroResult,
+    ctx::OshiroContext,
+    metrics,
+    shard::{shard_for_guild, ShardReport},
+};
+
+type AppState = Arc<Mutex<OshiroContext>>;
+
+/// Serve the bot's HTTP endpoints until the process exits
+pub async fn serve(bind: SocketAddr, ctx: AppState) -> OshiroResult<()> {
+    let app = Router::new()
+        .route("/metrics", get(metrics_handler))
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+        .route("/status", get(status))
+        .with_state(ctx);
+
+    tracing::info!("HTTP server listening on {}", bind);
+    axum::Server::bind(&bind)
+        .serve(app.into_make_service())
+        .await?;
+    Ok(())
+}
+
+async fn metrics_handler(State(ctx): State<AppState>) -> Response {
+    match metrics::render(&ctx).await {
+        Ok(body) => ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body).into_response(),
+        Err(e) => {
+            tracing::error!("Could not render metrics: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+        }
+    }
+}
+
+/// The process is up and serving requests
+async fn healthz() -> &'static str {
+    "ok"
+}
+
+/// Every shard has received READY and its heartbeats are being acknowledged
+async fn readyz(State(ctx): State<AppState>) -> Response {
+    let oshi = ctx.lock().await;
+    let threshold = Duration::from_secs(oshi.config.server.heartbeat_timeout);
+    let shards = oshi.shards.snapshot();
+    let unhealthy: Vec<String> = shards
+        .iter()
+        .filter(|s| !s.healthy(threshold))
+        .map(|s| s.id.number().to_string())
+        .collect();
+
+    if shards.is_empty() {
+        (StatusCode::SERVICE_UNAVAILABLE, "no shards".to_string()).into_response()
+    } else if unhealthy.is_empty() {
+        "ready".into_response()
+    } else {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            format!("shards not ready: {}", unhealthy.join(", ")),
+        )
+            .into_response()
+    }
+}
+
+#[derive(Serialize)]
+struct StatusReport {
+    ready: bool,
+    uptime_secs: u64,
+    /// Guilds on this process's shards
+    guilds: usize,
+    /// Shards across every process; `shards` only has the ones running here
+    total_shards: u64,
+    shards: Vec<ShardReport>,
+}
+
+async fn status(State(ctx): State<AppState>) -> Json<StatusReport> {
+    let oshi = ctx.lock().await;
+    let threshold = Duration::from_secs(oshi.config.server.heartbeat_timeout);
+    let guilds: Vec<u64> = oshi.cache.iter().guilds().map(|g| g.id().get()).collect();
+
+    let shards: Vec<ShardReport> = oshi
+        .shards
+        .snapshot()
+        .iter()
+        .map(|s| ShardReport {
+            id: s.id.number(),
+            total: s.id.total(),
+            state: s.state,
+            ready: s.ready,
+            healthy: s.healthy(threshold),
+            latency_ms: s.average_latency().map(|l| l.as_millis()),
+            last_heartbeat_ack_secs: s.last_heartbeat_ack.map(|t| t.elapsed().as_secs()),
+            reconnects: s.reconnects,
+            resumes: s.resumes,
+            invalid_sessions: s.invalid_sessions,
+            guilds: guilds
+                .iter()
+                .filter(|g| shard_for_guild(**g, s.id.total()) == s.id.number())
+                .count(),
+        })
+        .collect();
+
+    Json(StatusReport {
+        ready: !shards.is_empty() && shards.iter().all(|s| s.healthy),
+        uptime_secs: oshi.started.elapsed().as_secs(),
+        guilds: guilds.len(),
+        total_shards: shards.first().map_or(0, |s| s.total),
+        shards,
+    })
+}
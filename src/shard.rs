@@ -0,0 +1,417 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    ops::Range,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, Weak,
+    },
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+use tokio::sync::{mpsc::UnboundedSender, watch};
+use twilight_gateway::{
+    stream, CloseFrame, ConnectionStatus, Event, EventTypeFlags, Message, Shard, ShardId,
+};
+
+use crate::{config::Config, metrics, record};
+
+/// An event from one of our shards, tagged with the shard set it came from
+pub struct ShardEvent {
+    pub generation: u64,
+    pub id: ShardId,
+    pub event: Event,
+}
+
+/// Connection state of a shard, simplified from twilight's `ConnectionStatus`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectionState {
+    Connected,
+    Identifying,
+    Resuming,
+    Disconnected,
+    FatallyClosed,
+}
+
+impl From<&ConnectionStatus> for ConnectionState {
+    fn from(status: &ConnectionStatus) -> Self {
+        match status {
+            ConnectionStatus::Connected => ConnectionState::Connected,
+            ConnectionStatus::Identifying => ConnectionState::Identifying,
+            ConnectionStatus::Resuming => ConnectionState::Resuming,
+            ConnectionStatus::Disconnected { .. } => ConnectionState::Disconnected,
+            ConnectionStatus::FatallyClosed { .. } => ConnectionState::FatallyClosed,
+        }
+    }
+}
+
+/// A point in a shard's history
+#[derive(Clone, Debug)]
+pub struct ShardSample {
+    pub at: Instant,
+    pub latency: Option<Duration>,
+    pub state: ConnectionState,
+}
+
+/// What the monitor knows about one of our shards
+#[derive(Clone, Debug)]
+pub struct ShardStatus {
+    pub id: ShardId,
+    /// Between READY (or RESUMED) and the next disconnect
+    pub ready: bool,
+    pub ready_since: Option<Instant>,
+    pub state: ConnectionState,
+    /// Latest values reported by the shard itself
+    pub latency: Option<Duration>,
+    pub heartbeats: u64,
+    pub last_heartbeat_ack: Option<Instant>,
+    pub reconnects: u64,
+    pub resumes: u64,
+    pub invalid_sessions: u64,
+    /// Oldest first, capped at `gateway.latency_history` samples
+    pub history: VecDeque<ShardSample>,
+}
+
+impl ShardStatus {
+    pub fn new(id: ShardId) -> Self {
+        ShardStatus {
+            id,
+            ready: false,
+            ready_since: None,
+            state: ConnectionState::Identifying,
+            latency: None,
+            heartbeats: 0,
+            last_heartbeat_ack: None,
+            reconnects: 0,
+            resumes: 0,
+            invalid_sessions: 0,
+            history: VecDeque::new(),
+        }
+    }
+
+    /// Ready, and heartbeats are being acknowledged within the threshold. A shard that only just
+    /// became ready gets the threshold as grace for its first acknowledgement.
+    pub fn healthy(&self, threshold: Duration) -> bool {
+        if !self.ready {
+            return false;
+        }
+        match self.last_heartbeat_ack.or(self.ready_since) {
+            Some(at) => at.elapsed() <= threshold,
+            None => false,
+        }
+    }
+
+    /// Average latency over the sampled history
+    pub fn average_latency(&self) -> Option<Duration> {
+        let samples: Vec<Duration> = self.history.iter().filter_map(|s| s.latency).collect();
+        if samples.is_empty() {
+            return None;
+        }
+        Some(samples.iter().sum::<Duration>() / samples.len() as u32)
+    }
+}
+
+/// Tracks the state of every shard running in this process.
+///
+/// Shard tasks report in as events arrive (heartbeat acks included, so at least once per
+/// heartbeat interval) and a sampler task records that into a rolling history on an interval.
+pub struct ShardMonitor {
+    shards: Mutex<BTreeMap<u64, ShardStatus>>,
+    history_len: usize,
+    /// Set once a newer shard set has taken over, whose shards now report the metrics
+    retired: AtomicBool,
+}
+
+impl ShardMonitor {
+    pub fn new(history_len: usize) -> Self {
+        ShardMonitor {
+            shards: Mutex::new(BTreeMap::new()),
+            history_len,
+            retired: AtomicBool::new(false),
+        }
+    }
+
+    pub fn register(&self, id: ShardId) {
+        self.shards
+            .lock()
+            .unwrap()
+            .insert(id.number(), ShardStatus::new(id));
+    }
+
+    /// Called by a shard's task for every event it receives
+    pub fn observe(&self, shard: &Shard, event: &Event) {
+        let id = shard.id().number();
+        let mut shards = self.shards.lock().unwrap();
+        let status = match shards.get_mut(&id) {
+            Some(s) => s,
+            None => return,
+        };
+
+        let latency = shard.latency();
+        status.latency = latency.average();
+        status.heartbeats = latency.periods();
+        status.last_heartbeat_ack = latency.received();
+        status.state = ConnectionState::from(shard.status());
+
+        let label = match event {
+            Event::Ready(_) => {
+                tracing::info!("Shard {} is ready", id);
+                status.ready = true;
+                status.ready_since = Some(Instant::now());
+                return;
+            }
+            Event::Resumed => {
+                tracing::info!("Shard {} resumed its session", id);
+                status.ready = true;
+                status.ready_since = Some(Instant::now());
+                status.resumes += 1;
+                "resume"
+            }
+            Event::GatewayReconnect => {
+                tracing::info!("Shard {} was asked to reconnect", id);
+                status.ready = false;
+                status.ready_since = None;
+                status.reconnects += 1;
+                "reconnect"
+            }
+            Event::GatewayInvalidateSession(resumable) => {
+                tracing::warn!(
+                    "Shard {} had its session invalidated (resumable: {})",
+                    id,
+                    resumable
+                );
+                status.ready = false;
+                status.ready_since = None;
+                status.invalid_sessions += 1;
+                "invalid_session"
+            }
+            Event::GatewayClose(frame) => {
+                tracing::warn!("Shard {} connection closed: {:?}", id, frame);
+                status.ready = false;
+                status.ready_since = None;
+                "close"
+            }
+            _ => return,
+        };
+        metrics::SHARD_SESSIONS
+            .with_label_values(&[&id.to_string(), label])
+            .inc();
+    }
+
+    /// Stop sampling and clear the latency series of this set's shards, the set replacing it
+    /// sets its own on its next sample
+    pub fn retire(&self) {
+        self.retired.store(true, Ordering::SeqCst);
+        for id in self.shards.lock().unwrap().keys() {
+            metrics::SHARD_LATENCY
+                .remove_label_values(&[&id.to_string()])
+                .ok();
+        }
+    }
+
+    /// Record a sample for every shard, logging connection state changes
+    pub fn sample(&self) {
+        if self.retired.load(Ordering::SeqCst) {
+            return;
+        }
+        let mut shards = self.shards.lock().unwrap();
+        for (id, status) in shards.iter_mut() {
+            if let Some(previous) = status.history.back() {
+                if previous.state != status.state {
+                    tracing::info!(
+                        "Shard {} went from {:?} to {:?}",
+                        id,
+                        previous.state,
+                        status.state
+                    );
+                }
+            }
+
+            status.history.push_back(ShardSample {
+                at: Instant::now(),
+                latency: status.latency,
+                state: status.state,
+            });
+            while status.history.len() > self.history_len {
+                status.history.pop_front();
+            }
+
+            if let Some(avg) = status.average_latency() {
+                metrics::SHARD_LATENCY
+                    .with_label_values(&[&id.to_string()])
+                    .set(avg.as_secs_f64());
+            }
+        }
+    }
+
+    /// A copy of every shard's status, ordered by shard number
+    pub fn snapshot(&self) -> Vec<ShardStatus> {
+        self.shards.lock().unwrap().values().cloned().collect()
+    }
+}
+
+/// Sample the monitor on an interval until it's dropped
+pub fn spawn_sampler(monitor: Weak<ShardMonitor>, interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match monitor.upgrade() {
+                Some(m) => m.sample(),
+                None => break,
+            }
+        }
+    });
+}
+
+/// The gateway config every shard is started with
+pub fn gateway_config(config: &Config) -> twilight_gateway::Config {
+    // connection events are always needed by the shard monitor
+    let flags = config.gateway.event_types()
+        | EventTypeFlags::READY
+        | EventTypeFlags::RESUMED
+        | EventTypeFlags::GATEWAY_HEARTBEAT_ACK
+        | EventTypeFlags::GATEWAY_RECONNECT
+        | EventTypeFlags::GATEWAY_INVALIDATE_SESSION;
+    twilight_gateway::Config::builder(config.bot.token.clone(), config.gateway.intents())
+        .event_types(flags)
+        .build()
+}
+
+/// Shards started together, each running in its own task and sharing a monitor.
+///
+/// The shards run until the set is dropped or the bot shuts down, then close their connections.
+pub struct ShardSet {
+    /// Which set this is, starting from 0 and going up by one every reshard
+    pub generation: u64,
+    pub total: u64,
+    pub monitor: Arc<ShardMonitor>,
+    _stop: watch::Sender<()>,
+}
+
+impl ShardSet {
+    pub fn start(
+        config: &Config,
+        range: Range<u64>,
+        total: u64,
+        generation: u64,
+        tx: UnboundedSender<ShardEvent>,
+        shutdown: watch::Receiver<bool>,
+    ) -> Self {
+        let monitor = Arc::new(ShardMonitor::new(config.gateway.latency_history));
+        let (stop, stopped) = watch::channel(());
+
+        let shards = stream::create_range(range, total, gateway_config(config), |_, c| c.build());
+        for shard in shards {
+            monitor.register(shard.id());
+            tokio::spawn(run(
+                shard,
+                generation,
+                Arc::clone(&monitor),
+                tx.clone(),
+                stopped.clone(),
+                shutdown.clone(),
+            ));
+        }
+        spawn_sampler(
+            Arc::downgrade(&monitor),
+            Duration::from_secs(config.gateway.sample_interval),
+        );
+
+        ShardSet {
+            generation,
+            total,
+            monitor,
+            _stop: stop,
+        }
+    }
+
+    /// Every shard in the set has received READY
+    pub fn ready(&self) -> bool {
+        let shards = self.monitor.snapshot();
+        !shards.is_empty() && shards.iter().all(|s| s.ready)
+    }
+}
+
+/// Receive events from a shard and pass them on to the event loop, until its set is dropped
+/// or the bot shuts down
+async fn run(
+    mut shard: Shard,
+    generation: u64,
+    monitor: Arc<ShardMonitor>,
+    tx: UnboundedSender<ShardEvent>,
+    mut stopped: watch::Receiver<()>,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    loop {
+        let message = tokio::select! {
+            message = shard.next_message() => message,
+            _ = stopped.changed() => return close(&mut shard, generation).await,
+            _ = shutdown.changed() => return close(&mut shard, generation).await,
+        };
+
+        // what `Shard::next_event` does, but with the raw payload handed to the recorder first
+        let event = match message {
+            Ok(Message::Close(frame)) => Ok(Some(Event::GatewayClose(frame))),
+            Ok(Message::Text(json)) => {
+                record::message(shard.id(), &json);
+                twilight_gateway::parse(json, shard.config().event_types())
+                    .map(|e| e.map(Event::from))
+            }
+            Err(source) => Err(source),
+        };
+        let event = match event {
+            Ok(Some(e)) => e,
+            Ok(None) => continue,
+            Err(source) => {
+                if source.is_fatal() {
+                    tracing::error!(?source, "shard {} fatal error receiving event:", shard.id());
+                    break;
+                }
+                tracing::warn!(?source, "shard {} error receiving event:", shard.id());
+                continue;
+            }
+        };
+
+        monitor.observe(&shard, &event);
+        let event = ShardEvent {
+            generation,
+            id: shard.id(),
+            event,
+        };
+        if tx.send(event).is_err() {
+            // the event loop is gone
+            break;
+        }
+    }
+}
+
+async fn close(shard: &mut Shard, generation: u64) {
+    tracing::debug!("Closing shard {} of set {}", shard.id(), generation);
+    if let Err(e) = shard.close(CloseFrame::NORMAL).await {
+        tracing::warn!("Could not close shard {}: {}", shard.id(), e);
+    }
+}
+
+/// The JSON form of a shard for `/status`
+#[derive(Serialize)]
+pub struct ShardReport {
+    pub id: u64,
+    #[serde(skip)]
+    pub total: u64,
+    pub state: ConnectionState,
+    pub ready: bool,
+    pub healthy: bool,
+    pub latency_ms: Option<u128>,
+    pub last_heartbeat_ack_secs: Option<u64>,
+    pub reconnects: u64,
+    pub resumes: u64,
+    pub invalid_sessions: u64,
+    pub guilds: usize,
+}
+
+/// Which shard a guild lives on
+pub fn shard_for_guild(guild_id: u64, total: u64) -> u64 {
+    (guild_id >> 22) % total
+}
@@ -1,20 +1,29 @@
-use std::{collections::HashMap, pin::Pin, sync::Arc};
+use std::{collections::HashMap, pin::Pin, sync::Arc, time::Instant};
 
 use futures::Future;
 use tokio::sync::Mutex;
+use twilight_http::Client;
 use twilight_model::{
     application::{
-        command::{Command, CommandOption, CommandOptionType, CommandType},
-        interaction::{Interaction, InteractionData, InteractionType},
+        command::Command,
+        interaction::{
+            application_command::{CommandDataOption, CommandOptionValue},
+            Interaction, InteractionData, InteractionType,
+        },
     },
     channel::message::{Embed, MessageFlags},
     http::interaction::{InteractionResponse, InteractionResponseData, InteractionResponseType},
-    id::Id,
+    id::{
+        marker::{ApplicationMarker, GuildMarker},
+        Id,
+    },
 };
 
 use crate::{
     cmd::{CommandContext, OshiroResult},
+    config::Config,
     ctx::OshiroContext,
+    db, i18n, t,
 };
 
 pub type SlashCommandFn = Box<dyn Fn(CommandContext) -> SlashCommandResultOuter + Send + Sync>;
@@ -37,142 +46,121 @@ impl From<CommandWrapper> for Command {
     }
 }
 
+/// Every slash command from every module, admin ones included, keyed by top level name
 pub fn commands() -> HashMap<String, CommandWrapper> {
-    let mut commands = HashMap::new();
-    commands.insert(
-        "ping".to_string(),
-        CommandWrapper {
-            command: Command {
-                application_id: None,
-                default_member_permissions: None,
-                dm_permission: Some(true),
-                description: "Get the current ping to Discord".to_owned(),
-                description_localizations: None,
-                guild_id: None,
-                id: None,
-                kind: CommandType::ChatInput,
-                name: "ping".to_owned(),
-                name_localizations: None,
-                options: vec![],
-                version: Id::new(1),
-                nsfw: None,
-            },
-            function: Some(Box::new(move |ctx| {
-                Box::pin(crate::commands::system::ping(ctx))
-            })),
-            subcommands: None,
-        },
-    );
-    commands.insert(
-        "uwu".to_string(),
-        CommandWrapper {
-            command: Command {
-                application_id: None,
-                default_member_permissions: None,
-                dm_permission: Some(true),
-                description: "Uwuify a piece of text".to_owned(),
-                description_localizations: None,
-                guild_id: None,
-                id: None,
-                kind: CommandType::ChatInput,
-                name: "uwu".to_owned(),
-                name_localizations: None,
-                options: vec![CommandOption {
-                    autocomplete: None,
-                    channel_types: None,
-                    choices: None,
-                    description: "The text you want to process".to_owned(),
-                    description_localizations: None,
-                    kind: CommandOptionType::String,
-                    max_length: None,
-                    max_value: None,
-                    min_length: None,
-                    min_value: None,
-                    name: "text".to_owned(),
-                    name_localizations: None,
-                    options: None,
-                    required: Some(true),
-                }],
-                version: Id::new(1),
-                nsfw: None,
-            },
-            function: Some(Box::new(move |ctx| {
-                Box::pin(crate::commands::novelty::uwu(ctx))
-            })),
-            subcommands: None,
-        },
-    );
-    commands.insert(
-        "stats".to_string(),
-        CommandWrapper {
-            command: Command {
-                application_id: None,
-                default_member_permissions: None,
-                dm_permission: Some(true),
-                description: "Server statistics".to_owned(),
-                description_localizations: None,
-                guild_id: None,
-                id: None,
-                kind: CommandType::ChatInput,
-                name: "stats".to_owned(),
-                name_localizations: None,
-                options: vec![],
-                version: Id::new(1),
-                nsfw: None,
-            },
-            function: Some(Box::new(move |ctx| {
-                Box::pin(crate::commands::system::stats(ctx))
-            })),
-            subcommands: None,
-        },
-    );
-    commands.insert(
-        "server".to_string(),
-        CommandWrapper {
-            command: Command {
-                application_id: None,
-                default_member_permissions: None,
-                dm_permission: Some(true),
-                description: "Server statistics".to_owned(),
-                description_localizations: None,
-                guild_id: None,
-                id: None,
-                kind: CommandType::ChatInput,
-                name: "server".to_owned(),
-                name_localizations: None,
-                options: vec![CommandOption {
-                    name: "info".to_string(),
-                    options: None,
-                    autocomplete: None,
-                    channel_types: None,
-                    choices: None,
-                    description: "Get info about the server".to_string(),
-                    description_localizations: None,
-                    kind: CommandOptionType::SubCommand,
-                    max_length: None,
-                    max_value: None,
-                    min_length: None,
-                    min_value: None,
-                    name_localizations: None,
-                    required: None,
-                }],
-                version: Id::new(1),
-                nsfw: None,
-            },
-            function: None,
-            subcommands: Some({
-                let mut subcommands: HashMap<String, CommandGroup> = HashMap::new();
-                subcommands.insert(
-                    "info".to_string(),
-                    CommandGroup::Command(Box::new(move |ctx| {
-                        Box::pin(crate::commands::system::guild_info(ctx))
-                    })),
-                );
-                subcommands
-            }),
-        },
-    );
-    commands
+    crate::commands::modules()
+        .iter()
+        .flat_map(|m| {
+            m.slash_commands()
+                .into_iter()
+                .chain(m.admin_slash_commands())
+        })
+        .collect()
+}
+
+/// The definitions registered globally (or to `bot.dev_guild`), sorted by name
+pub fn definitions() -> Vec<Command> {
+    sorted(
+        crate::commands::modules()
+            .iter()
+            .flat_map(|m| m.slash_commands().into_values()),
+    )
+}
+
+/// The definitions only registered in `bot.admin_guild`, sorted by name
+pub fn admin_definitions() -> Vec<Command> {
+    sorted(
+        crate::commands::modules()
+            .iter()
+            .flat_map(|m| m.admin_slash_commands().into_values()),
+    )
+}
+
+fn sorted(commands: impl Iterator<Item = CommandWrapper>) -> Vec<Command> {
+    let mut definitions: Vec<Command> = commands.map(|c| c.command).collect();
+    definitions.sort_by(|a, b| a.name.cmp(&b.name));
+    definitions.iter_mut().for_each(i18n::localize_command);
+    definitions
+}
+
+/// Where each set of slash commands goes: the public ones to `bot.dev_guild` or globally
+/// (`None`), and the admin ones to `bot.admin_guild`
+pub fn plan(config: &Config) -> Vec<(Option<Id<GuildMarker>>, Vec<Command>)> {
+    let mut public = definitions();
+    match config.bot.admin_guild {
+        // registering to a guild replaces everything there, so both sets go in one call
+        Some(admin) if config.bot.dev_guild == Some(admin) => {
+            public.extend(admin_definitions());
+            vec![(Some(admin), public)]
+        }
+        Some(admin) => vec![
+            (config.bot.dev_guild, public),
+            (Some(admin), admin_definitions()),
+        ],
+        None => vec![(config.bot.dev_guild, public)],
+    }
+}
+
+/// Register every slash command where `plan` says they go
+pub async fn sync(
+    http: &Client,
+    app_id: Id<ApplicationMarker>,
+    config: &Config,
+) -> OshiroResult<()> {
+    for (guild_id, commands) in plan(config) {
+        register(http, app_id, guild_id, &commands).await?;
+    }
+    Ok(())
+}
+
+/// Register only what differs between two configs' plans, clearing out places that no longer
+/// get any commands. Returns whether anything was registered.
+pub async fn resync(
+    http: &Client,
+    app_id: Id<ApplicationMarker>,
+    old: &Config,
+    new: &Config,
+) -> OshiroResult<bool> {
+    let (old, new) = (plan(old), plan(new));
+    let mut changed = false;
+    for (guild_id, _) in &old {
+        if !new.iter().any(|(g, _)| g == guild_id) {
+            register(http, app_id, *guild_id, &[]).await?;
+            changed = true;
+        }
+    }
+    for (guild_id, commands) in &new {
+        if !old.iter().any(|(g, c)| g == guild_id && c == commands) {
+            register(http, app_id, *guild_id, commands).await?;
+            changed = true;
+        }
+    }
+    Ok(changed)
+}
+
+/// Replace our slash commands in one guild, or globally if there's no guild
+pub async fn register(
+    http: &Client,
+    app_id: Id<ApplicationMarker>,
+    guild_id: Option<Id<GuildMarker>>,
+    commands: &[Command],
+) -> OshiroResult<()> {
+    let interaction = http.interaction(app_id);
+    match guild_id {
+        Some(guild_id) => {
+            tracing::info!(
+                "Registering {} slash commands to guild {}",
+                commands.len(),
+                guild_id
+            );
+            interaction.set_guild_commands(guild_id, commands).await?;
+        }
+        None => {
+            tracing::info!("Registering {} slash commands globally", commands.len());
+            interaction.set_global_commands(commands).await?;
+        }
+    }
+    Ok(())
 }
 
 pub async fn handle(slash: Interaction, ctx: Arc<Mutex<OshiroContext>>) -> OshiroResult<()> {
@@ -182,7 +170,7 @@ pub async fn handle(slash: Interaction, ctx: Arc<Mutex<OshiroContext>>) -> Oshir
             return Ok(());
         }
         InteractionType::ApplicationCommand => Some(slash),
-        InteractionType::MessageComponent => None,
+        InteractionType::MessageComponent => return handle_component(slash, ctx).await,
         InteractionType::ApplicationCommandAutocomplete => None,
         InteractionType::ModalSubmit => None,
         _ => None,
@@ -235,6 +223,68 @@ pub async fn handle(slash: Interaction, ctx: Arc<Mutex<OshiroContext>>) -> Oshir
     };
 
     tracing::info!("Slash command used: {}", fname);
+    let locale = cctx.locale().await;
+
+    let (framework, db, config, cooldowns) = {
+        let oshi = ctx.lock().await;
+        (
+            Arc::clone(&oshi.framework),
+            oshi.db.clone(),
+            Arc::clone(&oshi.config),
+            Arc::clone(&oshi.cooldowns),
+        )
+    };
+    // owners skip the blacklist and cooldowns
+    let user = some_slash
+        .author_id()
+        .filter(|u| !config.bot.owners.contains(u));
+    let blacklisted = match user {
+        Some(user) => db::is_blacklisted(&db, user, some_slash.guild_id).await?,
+        None => false,
+    };
+    if blacklisted {
+        ctx.lock()
+            .await
+            .interaction()
+            .create_response(
+                some_slash.id,
+                &some_slash.token,
+                &error(&t!(locale, "blacklisted")),
+            )
+            .await?;
+        return Ok(());
+    }
+    if !framework
+        .command_enabled(&db, &config.commands, some_slash.guild_id, name)
+        .await?
+    {
+        ctx.lock()
+            .await
+            .interaction()
+            .create_response(
+                some_slash.id,
+                &some_slash.token,
+                &error(&t!(locale, "command-disabled")),
+            )
+            .await?;
+        return Ok(());
+    }
+    let cooldown = config.commands.cooldown(name);
+    if let Some(left) = user.and_then(|u| cooldowns.hit(name, u, cooldown)) {
+        let reply = t!(
+            locale,
+            "cooldown",
+            command = name,
+            seconds = left.as_secs() + 1
+        );
+        ctx.lock()
+            .await
+            .interaction()
+            .create_response(some_slash.id, &some_slash.token, &error(&reply))
+            .await?;
+        return Ok(());
+    }
+
     // TODO: simplify this mess
     // Get the list of commands
     let c = commands();
@@ -307,12 +357,15 @@ pub async fn handle(slash: Interaction, ctx: Arc<Mutex<OshiroContext>>) -> Oshir
         }
     };
     // run the command
-    match (fun)(cctx).await {
+    let started = Instant::now();
+    let result = (fun)(cctx).await;
+    crate::metrics::command(name, "slash", started.elapsed(), result.is_ok());
+    match result {
         Ok(_) => {}
         Err(e) => {
             tracing::error!("Error when running a command {:?}", e);
             // report the command to the user
-            let resp = error(&format!("Error when running a command {:?}", e));
+            let resp = error(&t!(locale, "command-failed", error = format!("{:?}", e)));
             if let Some(slash) = slash {
                 ctx.lock()
                     .await
@@ -326,6 +379,117 @@ pub async fn handle(slash: Interaction, ctx: Arc<Mutex<OshiroContext>>) -> Oshir
     Ok(())
 }
 
+/// Route a button click or menu selection to the module that owns its custom id prefix.
+/// Blacklisted users and modules that are off get the same answers as for commands.
+async fn handle_component(
+    component: Interaction,
+    ctx: Arc<Mutex<OshiroContext>>,
+) -> OshiroResult<()> {
+    let custom_id = match &component.data {
+        Some(InteractionData::MessageComponent(data)) => data.custom_id.clone(),
+        _ => return Err("No component data".into()),
+    };
+    let prefix = custom_id.split(':').next().unwrap_or_default();
+
+    let cctx = CommandContext {
+        command_type: crate::cmd::CommandType::SLASH,
+        oshiro: Arc::clone(&ctx),
+        msg: None,
+        stripped: None,
+        slash: Some(component.clone()),
+    };
+    let locale = cctx.locale().await;
+    let (framework, db, config) = {
+        let oshi = ctx.lock().await;
+        (
+            Arc::clone(&oshi.framework),
+            oshi.db.clone(),
+            Arc::clone(&oshi.config),
+        )
+    };
+    let (module, fun) = match framework
+        .modules()
+        .iter()
+        .find_map(|m| m.components().remove(prefix).map(|f| (Arc::clone(m), f)))
+    {
+        Some(found) => found,
+        None => {
+            tracing::warn!("Unhandled component {}", custom_id);
+            return Ok(());
+        }
+    };
+
+    let user = component
+        .author_id()
+        .filter(|u| !config.bot.owners.contains(u));
+    let blacklisted = match user {
+        Some(user) => db::is_blacklisted(&db, user, component.guild_id).await?,
+        None => false,
+    };
+    let blocked = if blacklisted {
+        Some("blacklisted")
+    } else if !framework
+        .module_enabled(&db, &config.commands, component.guild_id, module.as_ref())
+        .await?
+    {
+        Some("command-disabled")
+    } else {
+        None
+    };
+    if let Some(blocked) = blocked {
+        ctx.lock()
+            .await
+            .interaction()
+            .create_response(component.id, &component.token, &error(&t!(locale, blocked)))
+            .await?;
+        return Ok(());
+    }
+
+    let started = Instant::now();
+    let result = (fun)(cctx).await;
+    crate::metrics::command(prefix, "component", started.elapsed(), result.is_ok());
+    if let Err(e) = result {
+        tracing::error!("Error when handling a component {:?}", e);
+        let resp = error(&t!(locale, "command-failed", error = format!("{:?}", e)));
+        ctx.lock()
+            .await
+            .interaction()
+            .create_response(component.id, &component.token, &resp)
+            .await?;
+    }
+    Ok(())
+}
+
+/// The options passed to the innermost subcommand that was used
+pub fn leaf_options(slash: &Interaction) -> Vec<CommandDataOption> {
+    let mut options = match &slash.data {
+        Some(InteractionData::ApplicationCommand(data)) => data.options.clone(),
+        _ => return Vec::new(),
+    };
+    loop {
+        match options.first().map(|o| &o.value) {
+            Some(CommandOptionValue::SubCommand(s))
+            | Some(CommandOptionValue::SubCommandGroup(s)) => options = s.clone(),
+            _ => return options,
+        }
+    }
+}
+
+/// Get an option by name from the innermost subcommand
+pub fn option(slash: &Interaction, name: &str) -> Option<CommandOptionValue> {
+    leaf_options(slash)
+        .into_iter()
+        .find(|o| o.name == name)
+        .map(|o| o.value)
+}
+
+pub fn option_string(slash: &Interaction, name: &str) -> Option<String> {
+    match option(slash, name) {
+        Some(CommandOptionValue::String(s)) => Some(s),
+        _ => None,
+    }
+}
+
 pub fn error(msg: &str) -> InteractionResponse {
     InteractionResponse {
         kind: InteractionResponseType::ChannelMessageWithSource,
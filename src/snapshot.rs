@@ -0,0 +1,89 @@
+use twilight_cache_inmemory::{
+    model::{CachedMember, CachedMessage},
+    InMemoryCache,
+};
+use twilight_gateway::Event;
+use twilight_model::{
+    channel::Channel,
+    guild::Role,
+    id::{
+        marker::{GuildMarker, MessageMarker, UserMarker},
+        Id,
+    },
+    user::User,
+};
+
+/// What the cache knew about an event's subject just before the event was applied to it.
+///
+/// The cache is updated before listeners run, so without this a listener for a deletion would
+/// find the deleted thing already gone, and one for an edit would only see the new version.
+#[derive(Debug, Clone, Default)]
+pub struct Snapshot {
+    /// The deleted or edited messages that were cached
+    pub messages: Vec<CachedMessage>,
+    /// The member that left or was updated
+    pub member: Option<CachedMember>,
+    /// Their user, for spotting username and avatar changes
+    pub user: Option<User>,
+    /// The channel or thread that was updated or deleted
+    pub channel: Option<Channel>,
+    /// The role that was updated or deleted
+    pub role: Option<Role>,
+}
+
+impl Snapshot {
+    pub fn take(cache: &InMemoryCache, event: &Event) -> Self {
+        let message = |id| cache.message(id).map(|m| m.value().clone());
+        let channel = |id| cache.channel(id).map(|c| c.value().clone());
+        let role = |id| cache.role(id).map(|r| r.resource().clone());
+        match event {
+            Event::MessageDelete(d) => Snapshot {
+                messages: message(d.id).into_iter().collect(),
+                ..Default::default()
+            },
+            Event::MessageDeleteBulk(d) => Snapshot {
+                messages: d.ids.iter().filter_map(|id| message(*id)).collect(),
+                ..Default::default()
+            },
+            Event::MessageUpdate(u) => Snapshot {
+                messages: message(u.id).into_iter().collect(),
+                ..Default::default()
+            },
+            Event::MemberRemove(m) => Snapshot::member(cache, m.guild_id, m.user.id),
+            Event::MemberUpdate(m) => Snapshot::member(cache, m.guild_id, m.user.id),
+            Event::ChannelUpdate(c) => Snapshot {
+                channel: channel(c.id),
+                ..Default::default()
+            },
+            Event::ThreadUpdate(t) => Snapshot {
+                channel: channel(t.id),
+                ..Default::default()
+            },
+            Event::ThreadDelete(t) => Snapshot {
+                channel: channel(t.id),
+                ..Default::default()
+            },
+            Event::RoleUpdate(r) => Snapshot {
+                role: role(r.role.id),
+                ..Default::default()
+            },
+            Event::RoleDelete(r) => Snapshot {
+                role: role(r.role_id),
+                ..Default::default()
+            },
+            _ => Snapshot::default(),
+        }
+    }
+
+    fn member(cache: &InMemoryCache, guild_id: Id<GuildMarker>, user_id: Id<UserMarker>) -> Self {
+        Snapshot {
+            member: cache.member(guild_id, user_id).map(|m| m.value().clone()),
+            user: cache.user(user_id).map(|u| u.value().clone()),
+            ..Default::default()
+        }
+    }
+
+    pub fn message(&self, id: Id<MessageMarker>) -> Option<&CachedMessage> {
+        self.messages.iter().find(|m| m.id() == id)
+    }
+}